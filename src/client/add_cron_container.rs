@@ -0,0 +1,57 @@
+use super::ClientApp;
+use crate::{
+    client::ClientError,
+    domain::{CronContainer, QueuedContainer},
+};
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct CronContainerOutput {
+    id: String,
+    schedule: String,
+}
+
+impl<W: std::io::Write> ClientApp<W> {
+    /// Returns the id of the cron container.
+    pub async fn add_cron_container(
+        &mut self,
+        schedule: String,
+        command: String,
+        is_path: bool,
+    ) -> Result<String> {
+        let client = reqwest::Client::new();
+        let queued_container = if is_path {
+            QueuedContainer::from_path(command).await
+        } else {
+            QueuedContainer::new(command)
+        }?;
+        let cron_container = CronContainer::new(schedule, queued_container)?;
+
+        let response = client
+            .post(format!("http://127.0.0.1:{}/add_cron_container", self.port))
+            .json(&cron_container)
+            .send()
+            .await
+            .context("Failed to execute request.")?;
+
+        if !response.status().is_success() {
+            return Err(ClientError::ServerStatusError(response.status()).into());
+        }
+
+        let output = CronContainerOutput {
+            id: cron_container.id(),
+            schedule: cron_container.schedule().to_string(),
+        };
+        self.write_record(&output, |writer| {
+            writeln!(
+                writer,
+                "Cron container \"{}\" added ({})",
+                output.id, output.schedule
+            )?;
+            Ok(())
+        })?;
+
+        Ok(cron_container.id())
+    }
+}
@@ -0,0 +1,49 @@
+use super::{ClientApp, ClientError, OutputFormat};
+use crate::domain::{PreflightIssue, QueuedContainer, Severity};
+use anyhow::{Context, Result};
+
+impl<W: std::io::Write> ClientApp<W> {
+    /// Get the problems the server finds in the container before queueing it.
+    pub async fn check_container(
+        &self,
+        container: &QueuedContainer,
+    ) -> Result<Vec<PreflightIssue>> {
+        let client = reqwest::Client::new();
+        let response = client
+            .post(format!("http://127.0.0.1:{}/check_container", self.port))
+            .json(container)
+            .send()
+            .await
+            .context("Failed to execute request.")?;
+
+        if !response.status().is_success() {
+            return Err(ClientError::ServerStatusError(response.status()).into());
+        }
+        let issues = response
+            .json()
+            .await
+            .context("Failed to deserialize json response.")?;
+        Ok(issues)
+    }
+
+    /// Checks the containers before queueing them, the warnings are written with the
+    /// table format and it fails with all the errors if there is any.
+    pub(super) async fn preflight(&mut self, containers: &[QueuedContainer]) -> Result<()> {
+        let mut errors = Vec::new();
+        for container in containers {
+            for issue in self.check_container(container).await? {
+                match issue.severity() {
+                    Severity::Error => errors.push(issue.message().to_string()),
+                    Severity::Warning if self.format == OutputFormat::Table => {
+                        writeln!(self.writer, "{}", issue)?
+                    }
+                    Severity::Warning => {}
+                }
+            }
+        }
+        if !errors.is_empty() {
+            return Err(ClientError::PreflightError(errors).into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use super::{humanize::time_until, ClientApp};
+use crate::domain::QueueInfo;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, Utc};
+use serde::Serialize;
+
+const ESTIMATED_START_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+#[derive(Serialize)]
+struct ContainerStatusOutput {
+    id: String,
+    status: String,
+    position: Option<usize>,
+    estimated_start: Option<DateTime<Utc>>,
+}
+
+impl<W: std::io::Write> ClientApp<W> {
+    pub async fn get_queue_info(&self, id: &str) -> Result<Option<QueueInfo>> {
+        let client = reqwest::Client::new();
+        client
+            .get(format!(
+                "http://127.0.0.1:{}/get_queue_info/{}",
+                self.port, id
+            ))
+            .send()
+            .await
+            .context("Failed to execute request.")?
+            .json::<Option<QueueInfo>>()
+            .await
+            .context("Failed to deserealize queue info.")
+    }
+
+    /// Shows the status of the queued container `id`, with its position and estimated
+    /// start time while it is in the queue.
+    pub async fn container_status(&mut self, id: &str) -> Result<()> {
+        let status = self.get_container_status(id).await?;
+        let info = self.get_queue_info(id).await?;
+        let output = ContainerStatusOutput {
+            id: id.to_string(),
+            status: status
+                .map(|status| status.to_string())
+                .unwrap_or_else(|| "Not found".to_string()),
+            position: info.as_ref().and_then(|info| info.position()),
+            estimated_start: info.as_ref().and_then(|info| info.estimated_start()),
+        };
+
+        self.write_record(&output, |writer| {
+            write!(writer, "Container \"{}\": {}", output.id, output.status)?;
+            if let Some(position) = output.position {
+                write!(writer, ", position {}", position)?;
+                match output.estimated_start {
+                    Some(start) => write!(
+                        writer,
+                        ", estimated start {} ({})",
+                        time_until(start, Utc::now()),
+                        start.with_timezone(&Local).format(ESTIMATED_START_FORMAT)
+                    )?,
+                    None => write!(writer, ", no estimated start")?,
+                }
+            }
+            writeln!(writer)?;
+            Ok(())
+        })
+    }
+}
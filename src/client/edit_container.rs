@@ -0,0 +1,116 @@
+use super::ClientApp;
+use crate::{
+    client::ClientError,
+    domain::{strip_comments, ContainerStatus, QueuedContainer},
+};
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use std::env;
+use tokio::{fs, process::Command};
+
+#[derive(Serialize)]
+struct EditedContainerOutput {
+    id: String,
+    changed: bool,
+}
+
+impl<W: std::io::Write> ClientApp<W> {
+    /// Opens the command of the queued container `id` in the editor set in `$VISUAL` or
+    /// `$EDITOR` and replaces it with the result, the container keeps its position.
+    pub async fn edit_container(&mut self, id: &str) -> Result<()> {
+        let details = self.get_job(id).await?;
+        if !matches!(
+            details.status(),
+            ContainerStatus::Queued | ContainerStatus::Paused
+        ) {
+            bail!(
+                "Only queued containers can be edited, container {} is {}.",
+                id,
+                details.status()
+            );
+        }
+
+        let path = env::temp_dir().join(format!("docker_queue_{}.sh", details.id()));
+        let content = format!(
+            "# Editing container {}, lines starting with \"#\" are ignored.\n{}\n",
+            details.id(),
+            details.original_command().trim_end()
+        );
+        fs::write(&path, content)
+            .await
+            .context("Failed to write temporary file.")?;
+        let edited = open_editor(&path).await;
+        let edited = match edited {
+            Ok(()) => fs::read_to_string(&path)
+                .await
+                .context("Failed to read temporary file."),
+            Err(error) => Err(error),
+        };
+        fs::remove_file(&path)
+            .await
+            .context("Failed to remove temporary file.")?;
+        let command = strip_comments(&edited?);
+
+        let changed = command.trim() != details.original_command().trim();
+        if changed {
+            // A server env job keeps its variables to replace them when it is launched
+            let container =
+                QueuedContainer::with_env_expansion(command.trim(), details.env_expansion())?;
+            self.replace_container_command(details.id(), &container)
+                .await?;
+        }
+
+        let output = EditedContainerOutput {
+            id: details.id().to_string(),
+            changed,
+        };
+        self.write_record(&output, |writer| {
+            if output.changed {
+                writeln!(writer, "Container \"{}\" edited", output.id)?;
+            } else {
+                writeln!(writer, "Container \"{}\" not changed", output.id)?;
+            }
+            Ok(())
+        })
+    }
+
+    async fn replace_container_command(&self, id: &str, container: &QueuedContainer) -> Result<()> {
+        let client = reqwest::Client::new();
+        let response = client
+            .post(format!(
+                "http://127.0.0.1:{}/edit_container/{}",
+                self.port, id
+            ))
+            .json(container)
+            .send()
+            .await
+            .context("Failed to execute request.")?;
+
+        if !response.status().is_success() {
+            return Err(ClientError::ServerStatusError(response.status()).into());
+        }
+        Ok(())
+    }
+}
+
+/// Runs the user's editor on `path` and waits until it is closed.
+async fn open_editor(path: &std::path::Path) -> Result<()> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let mut args = shellwords::split(&editor).context("Failed to split editor command.")?;
+    if args.is_empty() {
+        bail!("Empty editor command.");
+    }
+    let program = args.remove(0);
+    let status = Command::new(&program)
+        .args(args)
+        .arg(path)
+        .status()
+        .await
+        .with_context(|| format!("Failed to launch editor {:?}.", program))?;
+    if !status.success() {
+        bail!("Editor {:?} exited with {}.", program, status);
+    }
+    Ok(())
+}
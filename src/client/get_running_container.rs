@@ -1,6 +1,12 @@
 use super::ClientApp;
 use crate::domain::RunningContainerId;
 use anyhow::{Context, Result};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct RunningContainerOutput {
+    id: Option<String>,
+}
 
 impl<W: std::io::Write> ClientApp<W> {
     pub async fn get_running_container(&mut self) -> Result<()> {
@@ -15,8 +21,12 @@ impl<W: std::io::Write> ClientApp<W> {
             .context("Failed to execute request.")?
             .json::<Option<RunningContainerId>>()
             .await?;
-        let show_id = container_id.as_ref().map(|id| id.as_ref()).unwrap_or("-");
-        writeln!(self.writer, "{}", show_id)?;
-        Ok(())
+        let output = RunningContainerOutput {
+            id: container_id.map(String::from),
+        };
+        self.write_record(&output, |writer| {
+            writeln!(writer, "{}", output.id.as_deref().unwrap_or("-"))?;
+            Ok(())
+        })
     }
 }
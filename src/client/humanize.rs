@@ -0,0 +1,60 @@
+use chrono::{DateTime, Duration, Utc};
+
+/// Shows a duration with its largest unit, as "3 minutes" or "2 days".
+pub(super) fn humanize_duration(duration: Duration) -> String {
+    let seconds = duration.num_seconds().max(0);
+    let (value, unit) = match seconds {
+        0..=59 => (seconds, "second"),
+        60..=3599 => (seconds / 60, "minute"),
+        3600..=86399 => (seconds / 3600, "hour"),
+        _ => (seconds / 86400, "day"),
+    };
+    if value == 1 {
+        format!("1 {}", unit)
+    } else {
+        format!("{} {}s", value, unit)
+    }
+}
+
+/// Shows how long ago `time` was, as "3 minutes ago".
+pub(super) fn time_ago(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    format!("{} ago", humanize_duration(now - time))
+}
+
+/// Shows how long until `time`, as "in 3 minutes".
+pub(super) fn time_until(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    if time <= now {
+        return "now".to_string();
+    }
+    format!("in {}", humanize_duration(time - now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(0, "0 seconds")]
+    #[test_case(1, "1 second")]
+    #[test_case(59, "59 seconds")]
+    #[test_case(60, "1 minute")]
+    #[test_case(185, "3 minutes")]
+    #[test_case(7200, "2 hours")]
+    #[test_case(86400 * 3 + 5, "3 days")]
+    fn humanize_duration_works(seconds: i64, expected: &str) {
+        assert_eq!(expected, humanize_duration(Duration::seconds(seconds)));
+    }
+
+    #[test]
+    fn time_ago_works() {
+        let now = Utc::now();
+        assert_eq!("3 minutes ago", time_ago(now - Duration::seconds(200), now));
+    }
+
+    #[test]
+    fn time_until_works() {
+        let now = Utc::now();
+        assert_eq!("in 2 hours", time_until(now + Duration::hours(2), now));
+        assert_eq!("now", time_until(now - Duration::hours(2), now));
+    }
+}
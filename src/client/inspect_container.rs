@@ -0,0 +1,126 @@
+use super::ClientApp;
+use crate::{client::ClientError, domain::JobDetails};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, Utc};
+use uuid::Uuid;
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+impl<W: std::io::Write> ClientApp<W> {
+    pub async fn get_job(&self, id: &str) -> Result<JobDetails> {
+        let client = reqwest::Client::new();
+        let response = client
+            .get(format!("http://127.0.0.1:{}/jobs/{}", self.port, id))
+            .send()
+            .await
+            .context("Failed to execute request.")?;
+
+        if !response.status().is_success() {
+            return Err(ClientError::ServerStatusError(response.status()).into());
+        }
+
+        response
+            .json::<JobDetails>()
+            .await
+            .context("Failed to deserealize job details.")
+    }
+
+    /// Get the queued container id of the job with name or id `id`.
+    pub async fn resolve_id(&self, id: &str) -> Result<Uuid> {
+        if let Ok(id) = Uuid::parse_str(id) {
+            return Ok(id);
+        }
+        let details = self.get_job(id).await?;
+        Uuid::parse_str(details.id()).context("Failed to parse container id.")
+    }
+
+    /// Shows everything known about the job with queued container id or docker id `id`.
+    pub async fn inspect_container(&mut self, id: &str) -> Result<()> {
+        let details = self.get_job(id).await?;
+        self.write_record(&details, |writer| {
+            let time = |time: Option<DateTime<Utc>>| {
+                time.map(|time| time.with_timezone(&Local).format(TIME_FORMAT).to_string())
+                    .unwrap_or_else(|| "-".to_string())
+            };
+            let or_dash = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+
+            writeln!(writer, "Id:               {}", details.id())?;
+            writeln!(
+                writer,
+                "Name:             {}",
+                or_dash(details.name().map(String::from))
+            )?;
+            writeln!(writer, "Status:           {}", details.status())?;
+            writeln!(
+                writer,
+                "Owner:            {}",
+                or_dash(details.owner().map(String::from))
+            )?;
+            let mut labels = details
+                .labels()
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect::<Vec<_>>();
+            labels.sort();
+            writeln!(writer, "Labels:           {}", labels.join(", "))?;
+            writeln!(writer, "Original command: {}", details.original_command())?;
+            let detach_added = if details.detach_added() {
+                " (--detach added)"
+            } else {
+                ""
+            };
+            writeln!(
+                writer,
+                "Command:          {}{}",
+                details.command(),
+                detach_added
+            )?;
+            writeln!(
+                writer,
+                "Args:             {}",
+                or_dash(details.args().map(|args| format!("{:?}", args)))
+            )?;
+            writeln!(
+                writer,
+                "Submitted:        {}",
+                time(Some(details.created()))
+            )?;
+            writeln!(writer, "Resources:        {}", details.resources())?;
+            writeln!(writer, "Not before:       {}", time(details.not_before()))?;
+            writeln!(
+                writer,
+                "Image pull:       {}",
+                or_dash(details.pull_status().map(|status| status.to_string()))
+            )?;
+            writeln!(writer, "Started:          {}", time(details.started_at()))?;
+            writeln!(writer, "Finished:         {}", time(details.finished_at()))?;
+            writeln!(
+                writer,
+                "Dependencies:     {}",
+                details
+                    .dependencies()
+                    .iter()
+                    .map(|dependency| format!("{} {}", dependency.condition(), dependency.id()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+            writeln!(
+                writer,
+                "Docker id:        {}",
+                or_dash(details.running_id().map(|id| id.as_ref().to_string()))
+            )?;
+            writeln!(
+                writer,
+                "Exit code:        {}",
+                or_dash(details.exit_code().map(|code| code.to_string()))
+            )?;
+            writeln!(writer, "Attempts:         {}", details.attempts())?;
+            writeln!(
+                writer,
+                "Log file:         {}",
+                or_dash(details.log_path().map(String::from))
+            )?;
+            Ok(())
+        })
+    }
+}
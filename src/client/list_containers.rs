@@ -1,7 +1,196 @@
-use super::ClientApp;
-use crate::domain::{Container, RunningContainer};
+use super::{
+    humanize::{humanize_duration, time_ago, time_until},
+    ClientApp, OutputFormat,
+};
+use crate::domain::{
+    has_labels, Container, Dependency, DependencyState, PullStatus, QueuedContainer, ResourceUsage,
+    RunningContainer, ServerMode,
+};
 use anyhow::{Context, Result};
+use chrono::{DateTime, Local, TimeZone, Utc};
 use console::{pad_str, style, Alignment};
+use serde::Serialize;
+use std::{collections::HashMap, str::FromStr};
+use uuid::Uuid;
+
+/// A container as written by the machine readable formats.
+#[derive(Serialize)]
+struct ContainerOutput {
+    status: String,
+    id: String,
+    image: Option<String>,
+    command: Option<String>,
+    created: Option<i64>,
+    names: Vec<String>,
+    external: bool,
+    dependencies: Vec<Dependency>,
+    dependency_state: Option<DependencyState>,
+    scheduled: Option<DateTime<Utc>>,
+    position: Option<usize>,
+    estimated_start: Option<DateTime<Utc>>,
+    sweep: Option<String>,
+    labels: HashMap<String, String>,
+    /// Queued container id of the running containers launched by the queue.
+    job: Option<String>,
+    /// Prefetch status of the image of the queued containers.
+    pull_status: Option<PullStatus>,
+    /// Queue of the queued containers and of the running containers it launched.
+    queue: Option<String>,
+    /// Launch time of the running containers launched by the queue.
+    started_at: Option<DateTime<Utc>>,
+}
+
+impl From<&Container> for ContainerOutput {
+    fn from(container: &Container) -> Self {
+        match container {
+            Container::Running(container) => {
+                let job = container.job_id().map(String::from);
+                let queue = container.queue().map(String::from);
+                let (container, started_at, external) = match container.as_ref() {
+                    RunningContainer::Tracked(container, started_at) => {
+                        (container, *started_at, false)
+                    }
+                    RunningContainer::External(container) => (container, None, true),
+                };
+                ContainerOutput {
+                    status: "Running".to_string(),
+                    id: container.id.clone().unwrap_or_default(),
+                    image: container.image.clone(),
+                    command: container.command.clone(),
+                    created: container.created,
+                    names: container.names.clone().unwrap_or_default(),
+                    external,
+                    dependencies: Vec::new(),
+                    dependency_state: None,
+                    scheduled: None,
+                    position: None,
+                    estimated_start: None,
+                    sweep: None,
+                    labels: container.labels.clone().unwrap_or_default(),
+                    job,
+                    pull_status: None,
+                    queue,
+                    started_at,
+                }
+            }
+            Container::Queued(container, info) => ContainerOutput {
+                status: container.status().to_string(),
+                id: container.id(),
+                image: None,
+                command: Some(container.command().to_string()),
+                created: Some(container.created().timestamp()),
+                names: container.name().map(String::from).into_iter().collect(),
+                external: false,
+                dependencies: container.dependencies().to_vec(),
+                dependency_state: Some(info.dependency_state().clone()),
+                scheduled: container.not_before(),
+                position: info.position(),
+                estimated_start: info.estimated_start(),
+                sweep: container.sweep_id(),
+                labels: container.labels().clone(),
+                job: None,
+                pull_status: info.pull_status().cloned(),
+                queue: Some(container.queue_name().to_string()),
+                started_at: None,
+            },
+        }
+    }
+}
+
+/// Filter for `list_containers` on the status of the containers.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StatusFilter {
+    Running,
+    Queued,
+    Paused,
+}
+
+impl FromStr for StatusFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "running" => Ok(Self::Running),
+            "queued" => Ok(Self::Queued),
+            "paused" => Ok(Self::Paused),
+            _ => Err(anyhow::anyhow!(
+                "Expected \"running\", \"queued\" or \"paused\": {:?}",
+                s
+            )),
+        }
+    }
+}
+
+impl StatusFilter {
+    fn matches(&self, container: &ContainerOutput) -> bool {
+        let status = match self {
+            StatusFilter::Running => "Running",
+            StatusFilter::Queued => "Queued",
+            StatusFilter::Paused => "Paused",
+        };
+        container.status == status
+    }
+}
+
+/// Field `list_containers` sorts the containers by.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SortKey {
+    Status,
+    Id,
+    Image,
+    Created,
+    Names,
+}
+
+impl FromStr for SortKey {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "status" => Ok(Self::Status),
+            "id" => Ok(Self::Id),
+            "image" => Ok(Self::Image),
+            "created" => Ok(Self::Created),
+            "names" => Ok(Self::Names),
+            _ => Err(anyhow::anyhow!(
+                "Expected \"status\", \"id\", \"image\", \"created\" or \"names\": {:?}",
+                s
+            )),
+        }
+    }
+}
+
+impl SortKey {
+    fn sort(&self, containers: &mut [(ContainerOutput, Container)]) {
+        match self {
+            SortKey::Status => containers.sort_by(|(a, _), (b, _)| a.status.cmp(&b.status)),
+            SortKey::Id => containers.sort_by(|(a, _), (b, _)| a.id.cmp(&b.id)),
+            SortKey::Image => containers.sort_by(|(a, _), (b, _)| a.image.cmp(&b.image)),
+            SortKey::Created => containers.sort_by_key(|(container, _)| container.created),
+            SortKey::Names => containers.sort_by(|(a, _), (b, _)| a.names.cmp(&b.names)),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ListOptions {
+    /// Show the full command.
+    pub show_all: bool,
+    /// Show the dependencies, schedule, position, estimated start, job and pull status
+    /// columns.
+    pub wide: bool,
+    /// Only show the containers with this status.
+    pub status: Option<StatusFilter>,
+    /// Hide the running containers not launched by the queue.
+    pub tracked_only: bool,
+    /// Only show the queued containers of this sweep.
+    pub sweep: Option<Uuid>,
+    /// Only show the containers with all these labels.
+    pub labels: Vec<(String, String)>,
+    pub sort: Option<SortKey>,
+    /// Don't style the table.
+    pub no_color: bool,
+}
 
 struct ShowContainer {
     status: String,
@@ -9,11 +198,47 @@ struct ShowContainer {
     image: String,
     command: String,
     created: String,
+    duration: String,
+    after: String,
+    scheduled: String,
+    position: String,
+    eta: String,
     names: String,
+    job: String,
+    pull: String,
     external: bool,
 }
 
 const COMMAND_MAX_LEN: usize = 40;
+const SHORT_ID_LEN: usize = 8;
+const SCHEDULED_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+impl ShowContainer {
+    /// The fields of the `wide` table or of the default one.
+    fn fields(&self, wide: bool) -> Vec<&str> {
+        let fields: [&str; 13] = [
+            &self.status,
+            &self.id,
+            &self.image,
+            &self.command,
+            &self.created,
+            &self.duration,
+            &self.after,
+            &self.scheduled,
+            &self.position,
+            &self.eta,
+            &self.names,
+            &self.job,
+            &self.pull,
+        ];
+        fields
+            .into_iter()
+            .zip(COLUMNS)
+            .filter(|(_, (_, is_wide))| wide || !is_wide)
+            .map(|(field, _)| field)
+            .collect()
+    }
+}
 
 struct ShowContainerBuilder {
     show_all: bool,
@@ -22,7 +247,14 @@ struct ShowContainerBuilder {
     image: String,
     command: String,
     created: String,
+    duration: String,
+    after: String,
+    scheduled: String,
+    position: String,
+    eta: String,
     names: String,
+    job: String,
+    pull: String,
     external: bool,
 }
 
@@ -42,7 +274,14 @@ impl ShowContainerBuilder {
             image: self.image,
             command,
             created: self.created,
+            duration: self.duration,
+            after: self.after,
+            scheduled: self.scheduled,
+            position: self.position,
+            eta: self.eta,
             names: self.names,
+            job: self.job,
+            pull: self.pull,
             external: self.external,
         }
     }
@@ -61,7 +300,14 @@ impl Default for ShowContainerBuilder {
             image: "-".to_string(),
             command: "-".to_string(),
             created: "-".to_string(),
+            duration: "-".to_string(),
+            after: "-".to_string(),
+            scheduled: "-".to_string(),
+            position: "-".to_string(),
+            eta: "-".to_string(),
             names: "-".to_string(),
+            job: "-".to_string(),
+            pull: "-".to_string(),
             show_all: false,
             external: false,
         }
@@ -70,23 +316,32 @@ impl Default for ShowContainerBuilder {
 
 impl From<RunningContainer> for ShowContainerBuilder {
     fn from(container: RunningContainer) -> Self {
-        let (container, external) = match container {
-            RunningContainer::Tracked(container) => (container, false),
-            RunningContainer::External(container) => (container, true),
+        let job = container
+            .job_id()
+            .map(|id| id.chars().take(SHORT_ID_LEN).collect())
+            .unwrap_or_else(|| "-".to_string());
+        let (container, started_at, external) = match container {
+            RunningContainer::Tracked(container, started_at) => (container, started_at, false),
+            RunningContainer::External(container) => (container, None, true),
         };
+        let now = Utc::now();
+        let created = container.created.map(|created| Utc.timestamp(created, 0));
         ShowContainerBuilder {
             status: "Running".to_string(),
             id: container.id.unwrap_or_else(|| "-".to_string()),
             image: container.image.unwrap_or_else(|| "-".to_string()),
             command: container.command.unwrap_or_else(|| "-".to_string()),
-            created: container
-                .created
-                .map(|o| o.to_string())
+            created: created
+                .map(|created| time_ago(created, now))
+                .unwrap_or_else(|| "-".to_string()),
+            duration: started_at
+                .map(|started_at| humanize_duration(now - started_at))
                 .unwrap_or_else(|| "-".to_string()),
             names: container
                 .names
-                .map(|o| o.concat())
+                .map(|names| show_names(&names))
                 .unwrap_or_else(|| "-".to_string()),
+            job,
             external,
             ..Default::default()
         }
@@ -97,10 +352,37 @@ impl From<Container> for ShowContainerBuilder {
     fn from(container: Container) -> Self {
         let builder = match container {
             Container::Running(container) => (*container).into(),
-            Container::Queued(container) => ShowContainerBuilder {
+            Container::Queued(container, info) => ShowContainerBuilder {
                 status: container.status().to_string(),
                 id: container.id(),
                 command: container.command().to_string(),
+                created: time_ago(container.created(), Utc::now()),
+                names: container
+                    .name()
+                    .map(String::from)
+                    .unwrap_or_else(|| "-".to_string()),
+                after: show_dependencies(&container, info.dependency_state()),
+                position: info
+                    .position()
+                    .map(|position| position.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                eta: info
+                    .estimated_start()
+                    .map(|start| time_until(start, Utc::now()))
+                    .unwrap_or_else(|| "-".to_string()),
+                scheduled: container
+                    .not_before()
+                    .map(|not_before| {
+                        not_before
+                            .with_timezone(&Local)
+                            .format(SCHEDULED_FORMAT)
+                            .to_string()
+                    })
+                    .unwrap_or_else(|| "-".to_string()),
+                pull: info
+                    .pull_status()
+                    .map(show_pull_status)
+                    .unwrap_or_else(|| "-".to_string()),
                 ..Default::default()
             },
         };
@@ -108,34 +390,83 @@ impl From<Container> for ShowContainerBuilder {
     }
 }
 
-fn get_max_lens(containers: &[ShowContainer], pad: usize) -> [usize; 6] {
-    let mut lens = HEADERS.map(|o| o.len());
+/// Orders the containers by queue, in the order of the first container of each queue,
+/// when there is more than one. Returns the queue of each container.
+fn group_by_queue(containers: &mut [(ContainerOutput, Container)]) -> Vec<Option<String>> {
+    let mut queues: Vec<Option<String>> = Vec::new();
+    for (output, _) in containers.iter() {
+        if !queues.contains(&output.queue) {
+            queues.push(output.queue.clone());
+        }
+    }
+    if queues.len() > 1 {
+        containers
+            .sort_by_key(|(output, _)| queues.iter().position(|queue| queue == &output.queue));
+    }
+    containers
+        .iter()
+        .map(|(output, _)| output.queue.clone())
+        .collect()
+}
+
+/// Docker names start with a "/", shows them as "name1, name2".
+fn show_names(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|name| name.trim_start_matches('/'))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Shows the pull status without the error, which is too long for the table.
+fn show_pull_status(pull_status: &PullStatus) -> String {
+    match pull_status {
+        PullStatus::Failed(_) => "Failed".to_string(),
+        pull_status => pull_status.to_string(),
+    }
+}
+
+/// Shows the dependencies as "after-ok 1b4e28ba, after 6a2f41a3 (Pending)".
+fn show_dependencies(container: &QueuedContainer, dependency_state: &DependencyState) -> String {
+    if container.dependencies().is_empty() {
+        return "-".to_string();
+    }
+    let dependencies = container
+        .dependencies()
+        .iter()
+        .map(|dependency| {
+            let id = dependency.id();
+            format!("{} {}", dependency.condition(), &id[..SHORT_ID_LEN])
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("{} ({})", dependencies, dependency_state)
+}
+
+fn get_max_lens(containers: &[ShowContainer], pad: usize, wide: bool) -> Vec<usize> {
+    let mut lens = headers(wide)
+        .iter()
+        .map(|header| header.len())
+        .collect::<Vec<_>>();
     containers.iter().for_each(|container| {
-        lens[0] = lens[0].max(container.status.len());
-        lens[1] = lens[1].max(container.id.len());
-        lens[2] = lens[2].max(container.image.len());
-        lens[3] = lens[3].max(container.command.len());
-        lens[4] = lens[4].max(container.created.len());
-        // lens[5] = lens[5].max(container.names.len());
+        lens.iter_mut()
+            .zip(container.fields(wide))
+            .for_each(|(len, field)| *len = (*len).max(field.len()));
     });
-    lens[5] = 0;
     lens.iter_mut().for_each(|len| *len += pad);
     lens
 }
 
-fn get_print_line(container: ShowContainer, max_lens: [usize; 6]) -> String {
-    let line = [
-        container.status,
-        container.id,
-        container.image,
-        container.command,
-        container.created,
-        container.names,
-    ]
-    .iter()
-    .zip(max_lens)
-    .map(|(s, width)| pad_str(s, width, Alignment::Left, None))
-    .collect::<String>();
+fn get_print_line(container: ShowContainer, max_lens: &[usize], wide: bool, color: bool) -> String {
+    let line = container
+        .fields(wide)
+        .iter()
+        .zip(max_lens)
+        .map(|(s, &width)| pad_str(s, width, Alignment::Left, None))
+        .collect::<String>();
+    if !color {
+        return line;
+    }
     if line.starts_with("Running") {
         if container.external {
             return style(line).bold().color256(8).to_string();
@@ -148,7 +479,31 @@ fn get_print_line(container: ShowContainer, max_lens: [usize; 6]) -> String {
     line
 }
 
-const HEADERS: [&str; 6] = ["status", "id", "image", "command", "created", "names"];
+/// The columns of the table, and whether only the wide table shows them.
+const COLUMNS: [(&str, bool); 13] = [
+    ("status", false),
+    ("id", false),
+    ("image", false),
+    ("command", false),
+    ("created", false),
+    ("duration", false),
+    ("after", true),
+    ("scheduled", true),
+    ("position", true),
+    ("eta", true),
+    ("names", false),
+    ("job", true),
+    ("pull", true),
+];
+
+/// The headers of the `wide` table or of the default one.
+fn headers(wide: bool) -> Vec<&'static str> {
+    COLUMNS
+        .iter()
+        .filter(|(_, is_wide)| wide || !is_wide)
+        .map(|(header, _)| *header)
+        .collect()
+}
 
 impl<W: std::io::Write> ClientApp<W> {
     pub async fn get_containers(&self) -> Result<Vec<Container>> {
@@ -163,30 +518,127 @@ impl<W: std::io::Write> ClientApp<W> {
             .context("Failed to deserealize containers.")
     }
 
+    pub async fn get_resources(&self) -> Result<ResourceUsage> {
+        let client = reqwest::Client::new();
+        client
+            .get(format!("http://127.0.0.1:{}/resources", self.port))
+            .send()
+            .await
+            .context("Failed to execute request.")?
+            .json::<ResourceUsage>()
+            .await
+            .context("Failed to deserealize resources.")
+    }
+
     pub async fn list_containers(&mut self, show_all: bool) -> Result<()> {
-        let containers = self
+        let options = ListOptions {
+            show_all,
+            ..Default::default()
+        };
+        self.list_containers_with_options(options).await
+    }
+
+    pub async fn list_containers_with_options(&mut self, options: ListOptions) -> Result<()> {
+        let labels = options.labels.iter().cloned().collect();
+        let mut containers = self
             .get_containers()
             .await?
             .into_iter()
-            .map(|container| {
-                ShowContainerBuilder::from(container)
-                    .show_all(show_all)
-                    .build()
+            .map(|container| (ContainerOutput::from(&container), container))
+            .filter(|(output, _)| !(options.tracked_only && output.external))
+            .filter(|(output, _)| has_labels(&output.labels, &labels))
+            .filter(|(output, _)| {
+                options
+                    .sweep
+                    .is_none_or(|sweep| output.sweep == Some(sweep.to_string()))
+            })
+            .filter(|(output, _)| {
+                options
+                    .status
+                    .as_ref()
+                    .is_none_or(|status| status.matches(output))
             })
             .collect::<Vec<_>>();
+        if let Some(sort) = &options.sort {
+            sort.sort(&mut containers);
+        }
+        let queues = group_by_queue(&mut containers);
+        let is_grouped = queues.iter().any(|queue| queue != &queues[0]);
+        let (outputs, containers): (Vec<_>, Vec<_>) = containers.into_iter().unzip();
+        let (resources, server_status) = if self.format == OutputFormat::Table {
+            let resources =
+                Some(self.get_resources().await?).filter(|usage| !usage.capacity().is_empty());
+            let server_status = Some(self.get_server_status().await?)
+                .filter(|status| status.mode() != ServerMode::Active);
+            (resources, server_status)
+        } else {
+            (None, None)
+        };
 
-        let max_lens = get_max_lens(&containers, 2);
-        let headers = HEADERS
-            .iter()
-            .zip(max_lens)
-            .map(|(header, len)| pad_str(header, len, Alignment::Left, None))
-            .collect::<String>();
-        writeln!(self.writer, "{}", style(headers).bold())?;
+        self.write_records(&outputs, |writer| {
+            let containers = containers
+                .into_iter()
+                .map(|container| {
+                    ShowContainerBuilder::from(container)
+                        .show_all(options.show_all)
+                        .build()
+                })
+                .collect::<Vec<_>>();
 
-        for container in containers {
-            writeln!(self.writer, "{}", get_print_line(container, max_lens))?;
-        }
+            let max_lens = get_max_lens(&containers, 2, options.wide);
+            let headers = headers(options.wide)
+                .into_iter()
+                .zip(&max_lens)
+                .map(|(header, &len)| pad_str(header, len, Alignment::Left, None))
+                .collect::<String>();
+            if options.no_color {
+                writeln!(writer, "{}", headers)?;
+            } else {
+                writeln!(writer, "{}", style(headers).bold())?;
+            }
+
+            let mut current_queue = None;
+            for (container, queue) in containers.into_iter().zip(&queues) {
+                if is_grouped && current_queue != Some(queue) {
+                    let title = match queue {
+                        Some(queue) => format!("Queue {}", queue),
+                        None => "Not queued".to_string(),
+                    };
+                    if options.no_color {
+                        writeln!(writer, "{}", title)?;
+                    } else {
+                        writeln!(writer, "{}", style(title).bold().underlined())?;
+                    }
+                    current_queue = Some(queue);
+                }
+                let line = get_print_line(container, &max_lens, options.wide, !options.no_color);
+                writeln!(writer, "{}", line)?;
+            }
+
+            if let Some(resources) = resources {
+                writeln!(writer, "\nResources: {}", resources)?;
+            }
+            if let Some(server_status) = server_status {
+                writeln!(writer, "\nServer: {}", server_status)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        Ok(())
+    #[test]
+    fn default_table_skips_the_wide_columns() {
+        let container = ShowContainerBuilder::default().build();
+        assert_eq!(
+            vec!["status", "id", "image", "command", "created", "duration", "names"],
+            headers(false)
+        );
+        assert_eq!(headers(false).len(), container.fields(false).len());
+        assert_eq!(COLUMNS.len(), headers(true).len());
+        assert_eq!(COLUMNS.len(), container.fields(true).len());
     }
 }
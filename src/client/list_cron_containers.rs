@@ -0,0 +1,96 @@
+use super::ClientApp;
+use crate::domain::CronContainer;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, Utc};
+use console::{pad_str, style, Alignment};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct CronContainerOutput {
+    id: String,
+    schedule: String,
+    next: Option<DateTime<Utc>>,
+    command: String,
+}
+
+impl From<&CronContainer> for CronContainerOutput {
+    fn from(cron_container: &CronContainer) -> Self {
+        CronContainerOutput {
+            id: cron_container.id(),
+            schedule: cron_container.schedule().to_string(),
+            next: cron_container.next_after(Utc::now()),
+            command: cron_container.container().command().to_string(),
+        }
+    }
+}
+
+const HEADERS: [&str; 4] = ["id", "schedule", "next", "command"];
+const NEXT_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+fn get_fields(cron_container: &CronContainerOutput) -> [String; 4] {
+    let next = cron_container
+        .next
+        .map(|next| next.with_timezone(&Local).format(NEXT_FORMAT).to_string())
+        .unwrap_or_else(|| "-".to_string());
+    [
+        cron_container.id.clone(),
+        cron_container.schedule.clone(),
+        next,
+        cron_container.command.clone(),
+    ]
+}
+
+impl<W: std::io::Write> ClientApp<W> {
+    pub async fn get_cron_containers(&self) -> Result<Vec<CronContainer>> {
+        let client = reqwest::Client::new();
+        client
+            .get(format!(
+                "http://127.0.0.1:{}/list_cron_containers",
+                self.port
+            ))
+            .send()
+            .await
+            .context("Failed to execute request.")?
+            .json::<Vec<CronContainer>>()
+            .await
+            .context("Failed to deserealize cron containers.")
+    }
+
+    pub async fn list_cron_containers(&mut self) -> Result<()> {
+        let outputs = self
+            .get_cron_containers()
+            .await?
+            .iter()
+            .map(CronContainerOutput::from)
+            .collect::<Vec<_>>();
+
+        self.write_records(&outputs, |writer| {
+            let rows = outputs.iter().map(get_fields).collect::<Vec<_>>();
+            let mut max_lens = HEADERS.map(|header| header.len() + 2);
+            rows.iter().for_each(|row| {
+                max_lens
+                    .iter_mut()
+                    .zip(row)
+                    .for_each(|(len, field)| *len = (*len).max(field.len() + 2));
+            });
+
+            let headers = HEADERS
+                .iter()
+                .zip(max_lens)
+                .map(|(header, len)| pad_str(header, len, Alignment::Left, None))
+                .collect::<String>();
+            writeln!(writer, "{}", style(headers).bold())?;
+
+            for row in rows {
+                let line = row
+                    .iter()
+                    .zip(max_lens)
+                    .map(|(field, len)| pad_str(field, len, Alignment::Left, None))
+                    .collect::<String>();
+                writeln!(writer, "{}", line)?;
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,41 @@
+use super::ClientApp;
+use crate::domain::SweepSummary;
+use anyhow::{Context, Result};
+
+impl<W: std::io::Write> ClientApp<W> {
+    pub async fn get_sweeps(&self) -> Result<Vec<SweepSummary>> {
+        let client = reqwest::Client::new();
+        client
+            .get(format!("http://127.0.0.1:{}/list_sweeps", self.port))
+            .send()
+            .await
+            .context("Failed to execute request.")?
+            .json::<Vec<SweepSummary>>()
+            .await
+            .context("Failed to deserealize sweeps.")
+    }
+
+    pub async fn list_sweeps(&mut self) -> Result<()> {
+        let sweeps = self.get_sweeps().await?;
+        self.write_records(&sweeps, |writer| {
+            writeln!(
+                writer,
+                "{:<38}{:<8}{:<8}{:<8}{:<8}{:<8}",
+                "id", "total", "queued", "paused", "running", "finished"
+            )?;
+            for sweep in &sweeps {
+                writeln!(
+                    writer,
+                    "{:<38}{:<8}{:<8}{:<8}{:<8}{:<8}",
+                    sweep.id(),
+                    sweep.total(),
+                    sweep.queued(),
+                    sweep.paused(),
+                    sweep.running(),
+                    sweep.finished()
+                )?;
+            }
+            Ok(())
+        })
+    }
+}
@@ -1,6 +1,28 @@
+mod add_cron_container;
+mod check_container;
+mod container_status;
+mod edit_container;
 mod get_running_container;
+mod humanize;
+mod inspect_container;
 mod list_containers;
+mod list_cron_containers;
+mod list_sweeps;
+mod output;
+mod pause_containers;
+mod pause_sweep;
 mod queue_container;
+mod remove_cron_container;
+mod remove_sweep;
+mod resubmit_container;
+mod server_status;
+mod sweep_containers;
+mod wait_container;
+
+pub use list_containers::{ListOptions, SortKey, StatusFilter};
+pub use output::OutputFormat;
+pub use queue_container::QueueOptions;
+pub use wait_container::{CANCELLED_EXIT_CODE, LAUNCH_FAILED_EXIT_CODE, NOT_FOUND_EXIT_CODE};
 
 use crate::error_chain_fmt;
 use axum::http::StatusCode;
@@ -8,11 +30,21 @@ use axum::http::StatusCode;
 pub struct ClientApp<W: std::io::Write> {
     pub port: u16,
     pub writer: W,
+    pub format: OutputFormat,
 }
 
 impl<W: std::io::Write> ClientApp<W> {
     pub fn new(port: u16, writer: W) -> Self {
-        Self { port, writer }
+        Self {
+            port,
+            writer,
+            format: OutputFormat::default(),
+        }
+    }
+
+    pub fn format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
     }
 }
 
@@ -20,6 +52,10 @@ impl<W: std::io::Write> ClientApp<W> {
 pub enum ClientError {
     #[error("Unexpected status received: {0}")]
     ServerStatusError(StatusCode),
+    #[error("The container would fail:\n{}", .0.join("\n"))]
+    PreflightError(Vec<String>),
+    #[error("A name is unique, it can't be given to every container of {0:?}.")]
+    NamedBatch(String),
     #[error(transparent)]
     UnexpectedError(#[from] anyhow::Error),
 }
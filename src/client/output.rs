@@ -0,0 +1,159 @@
+use super::ClientApp;
+use anyhow::{Context, Result};
+use once_cell::sync::OnceCell;
+use regex::{Captures, Regex};
+use serde::Serialize;
+use serde_json::Value;
+use std::str::FromStr;
+
+/// How the client commands write their results.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum OutputFormat {
+    /// Human readable tables and messages.
+    #[default]
+    Table,
+    Json,
+    Yaml,
+    /// A template as "{{.id}} {{.status}}", rendered once per record.
+    Template(String),
+    /// Only the ids, one per line.
+    Quiet,
+}
+
+impl OutputFormat {
+    /// Whether the output is a single json or yaml document, which can't be followed by
+    /// another one.
+    pub fn is_document(&self) -> bool {
+        matches!(self, OutputFormat::Json | OutputFormat::Yaml)
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Self::Table),
+            "json" => Ok(Self::Json),
+            "yaml" => Ok(Self::Yaml),
+            template if template.contains("{{") => Ok(Self::Template(template.to_string())),
+            _ => Err(anyhow::anyhow!(
+                "Expected \"json\", \"yaml\", \"table\" or a template as \"{{{{.id}}}}\": {:?}",
+                s
+            )),
+        }
+    }
+}
+
+/// Replaces every "{{.field}}" in `template` with the field of `record`, missing fields
+/// and nulls render as an empty string.
+fn render_template(template: &str, record: &Value) -> String {
+    static RE: OnceCell<Regex> = OnceCell::new();
+    let re = RE.get_or_init(|| Regex::new(r"\{\{\s*\.(\w+)\s*\}\}").unwrap());
+    let template = template.replace("\\t", "\t").replace("\\n", "\n");
+    re.replace_all(&template, |caps: &Captures| match record.get(&caps[1]) {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(value) => value.to_string(),
+    })
+    .to_string()
+}
+
+impl<W: std::io::Write> ClientApp<W> {
+    /// Writes a single record in the output format, `write_table` writes it for
+    /// `OutputFormat::Table`.
+    pub(super) fn write_record<T: Serialize>(
+        &mut self,
+        record: &T,
+        write_table: impl FnOnce(&mut W) -> Result<()>,
+    ) -> Result<()> {
+        match &self.format {
+            OutputFormat::Table => write_table(&mut self.writer),
+            OutputFormat::Json | OutputFormat::Yaml => self.write_serialized(record),
+            OutputFormat::Template(_) | OutputFormat::Quiet => {
+                self.write_templated(std::slice::from_ref(record))
+            }
+        }
+    }
+
+    /// Writes a list of records in the output format, `write_table` writes them for
+    /// `OutputFormat::Table`.
+    pub(super) fn write_records<T: Serialize>(
+        &mut self,
+        records: &[T],
+        write_table: impl FnOnce(&mut W) -> Result<()>,
+    ) -> Result<()> {
+        match &self.format {
+            OutputFormat::Table => write_table(&mut self.writer),
+            OutputFormat::Json | OutputFormat::Yaml => self.write_serialized(&records),
+            OutputFormat::Template(_) | OutputFormat::Quiet => self.write_templated(records),
+        }
+    }
+
+    fn write_serialized<T: Serialize>(&mut self, value: &T) -> Result<()> {
+        if self.format == OutputFormat::Yaml {
+            let yaml = serde_yaml::to_string(value).context("Failed to write yaml.")?;
+            write!(self.writer, "{}", yaml)?;
+        } else {
+            let json = serde_json::to_string_pretty(value).context("Failed to write json.")?;
+            writeln!(self.writer, "{}", json)?;
+        }
+        Ok(())
+    }
+
+    fn write_templated<T: Serialize>(&mut self, records: &[T]) -> Result<()> {
+        let template = match &self.format {
+            OutputFormat::Template(template) => template.clone(),
+            _ => "{{.id}}".to_string(),
+        };
+        for record in records {
+            let record = serde_json::to_value(record).context("Failed to serialize.")?;
+            writeln!(self.writer, "{}", render_template(&template, &record))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claim::assert_err;
+    use serde_json::json;
+
+    #[test]
+    fn parse_output_format_works() {
+        assert_eq!(OutputFormat::Json, "json".parse().unwrap());
+        assert_eq!(OutputFormat::Yaml, "yaml".parse().unwrap());
+        assert_eq!(OutputFormat::Table, "table".parse().unwrap());
+        assert_eq!(
+            OutputFormat::Template("{{.id}}".into()),
+            "{{.id}}".parse().unwrap()
+        );
+        assert_err!("xml".parse::<OutputFormat>());
+    }
+
+    #[test]
+    fn render_template_works() {
+        let record = json!({"id": "1234", "status": "Queued", "exit_code": 3, "image": null});
+        assert_eq!(
+            "1234\tQueued 3 []",
+            render_template(
+                "{{.id}}\\t{{ .status }} {{.exit_code}} [{{.image}}{{.missing}}]",
+                &record
+            )
+        );
+    }
+
+    #[test]
+    fn write_records_works() {
+        let records = vec![json!({"id": "1", "status": "Queued"}), json!({"id": "2"})];
+        let mut client = ClientApp::new(0, Vec::new()).format(OutputFormat::Quiet);
+        client.write_records(&records, |_| Ok(())).unwrap();
+        assert_eq!("1\n2\n", String::from_utf8(client.writer).unwrap());
+
+        let mut client = ClientApp::new(0, Vec::new()).format(OutputFormat::Json);
+        client.write_records(&records, |_| Ok(())).unwrap();
+        let output: Value = serde_json::from_slice(&client.writer).unwrap();
+        assert_eq!(json!(records), output);
+    }
+}
@@ -0,0 +1,71 @@
+use super::ClientApp;
+use crate::{client::ClientError, domain::Selector};
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct UpdatedContainerOutput {
+    id: String,
+    status: String,
+}
+
+impl<W: std::io::Write> ClientApp<W> {
+    /// Pauses the queued containers selected, returns the ids of the ones that were queued.
+    pub async fn pause_containers(&mut self, selector: &Selector) -> Result<Vec<String>> {
+        let paused = self.post_selector("pause_containers", selector).await?;
+        self.write_updated_containers(&paused, "Paused")?;
+        Ok(paused)
+    }
+
+    /// Queues the paused containers selected, returns the ids of the ones that were paused.
+    pub async fn resume_containers(&mut self, selector: &Selector) -> Result<Vec<String>> {
+        let resumed = self.post_selector("resume_containers", selector).await?;
+        self.write_updated_containers(&resumed, "Queued")?;
+        Ok(resumed)
+    }
+
+    /// Removes from the queue the containers selected, returns their ids.
+    pub async fn remove_containers(&mut self, selector: &Selector) -> Result<Vec<String>> {
+        let removed = self.post_selector("remove_containers", selector).await?;
+        self.write_updated_containers(&removed, "Cancelled")?;
+        Ok(removed)
+    }
+
+    fn write_updated_containers(&mut self, ids: &[String], status: &str) -> Result<()> {
+        let output = ids
+            .iter()
+            .map(|id| UpdatedContainerOutput {
+                id: id.clone(),
+                status: status.to_string(),
+            })
+            .collect::<Vec<_>>();
+        self.write_records(&output, |writer| {
+            if output.is_empty() {
+                writeln!(writer, "No containers changed")?;
+            }
+            for output in &output {
+                writeln!(writer, "Container \"{}\": {}", output.id, output.status)?;
+            }
+            Ok(())
+        })
+    }
+
+    async fn post_selector(&self, route: &str, selector: &Selector) -> Result<Vec<String>> {
+        let client = reqwest::Client::new();
+        let response = client
+            .post(format!("http://127.0.0.1:{}/{}", self.port, route))
+            .json(selector)
+            .send()
+            .await
+            .context("Failed to execute request.")?;
+
+        if !response.status().is_success() {
+            return Err(ClientError::ServerStatusError(response.status()).into());
+        }
+
+        response
+            .json::<Vec<String>>()
+            .await
+            .context("Failed to deserealize container ids.")
+    }
+}
@@ -0,0 +1,64 @@
+use super::ClientApp;
+use crate::client::ClientError;
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct UpdatedSweepOutput {
+    id: String,
+    updated: usize,
+}
+
+impl<W: std::io::Write> ClientApp<W> {
+    /// Pauses every queued container of the sweep `id`.
+    pub async fn pause_sweep(&mut self, id: &str) -> Result<()> {
+        let paused = self.update_sweep("pause_sweep", id).await?;
+        let output = UpdatedSweepOutput {
+            id: id.to_string(),
+            updated: paused,
+        };
+        self.write_record(&output, |writer| {
+            writeln!(
+                writer,
+                "Sweep \"{}\": {} containers paused",
+                output.id, output.updated
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Queues again every paused container of the sweep `id`.
+    pub async fn resume_sweep(&mut self, id: &str) -> Result<()> {
+        let resumed = self.update_sweep("resume_sweep", id).await?;
+        let output = UpdatedSweepOutput {
+            id: id.to_string(),
+            updated: resumed,
+        };
+        self.write_record(&output, |writer| {
+            writeln!(
+                writer,
+                "Sweep \"{}\": {} containers resumed",
+                output.id, output.updated
+            )?;
+            Ok(())
+        })
+    }
+
+    async fn update_sweep(&self, route: &str, id: &str) -> Result<usize> {
+        let client = reqwest::Client::new();
+        let response = client
+            .post(format!("http://127.0.0.1:{}/{}/{}", self.port, route, id))
+            .send()
+            .await
+            .context("Failed to execute request.")?;
+
+        if !response.status().is_success() {
+            return Err(ClientError::ServerStatusError(response.status()).into());
+        }
+
+        response
+            .json::<usize>()
+            .await
+            .context("Failed to deserealize updated containers.")
+    }
+}
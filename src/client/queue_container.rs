@@ -1,23 +1,156 @@
-use super::ClientApp;
-use crate::{client::ClientError, domain::QueuedContainer};
+use super::{ClientApp, OutputFormat};
+use crate::{
+    client::ClientError,
+    domain::{
+        read_batch, read_job_spec, Dependency, DependencyCondition, EnvExpansion, QueuedContainer,
+        QueuedContainerError, Resources,
+    },
+};
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(Serialize)]
+struct QueuedContainerOutput {
+    id: String,
+    status: String,
+}
+
+#[derive(Debug, Default)]
+pub struct QueueOptions {
+    /// Treats the command as a file path to read.
+    pub is_path: bool,
+    /// Treats the command as the path of a job spec or a Docker Compose file.
+    pub is_spec: bool,
+    /// Service of the Docker Compose file to queue.
+    pub service: Option<String>,
+    /// The container gets queued but not started even if the queue is empty.
+    pub paused: bool,
+    /// Queued containers that have to finish before this one runs.
+    pub after: Vec<Uuid>,
+    /// Queued containers that have to exit with code 0 before this one runs.
+    pub after_ok: Vec<Uuid>,
+    /// The container doesn't run before this time.
+    pub not_before: Option<DateTime<Utc>>,
+    /// Expected duration of the container, used to estimate start times.
+    pub estimate: Option<std::time::Duration>,
+    /// Unique name of the container, it can be used instead of the id.
+    pub name: Option<String>,
+    /// Docker labels of the container.
+    pub labels: Vec<(String, String)>,
+    /// The env vars are replaced by the server when the container is launched.
+    pub server_env: bool,
+    /// Docker env files of the container, read when the container is launched.
+    pub env_files: Vec<PathBuf>,
+    /// Checks the image, the bind mounts and the container name before queueing.
+    pub check: bool,
+    /// Resources the container needs to start, also passed as limits.
+    pub resources: Resources,
+    /// Queue the container runs in, the default queue if `None`.
+    pub queue_name: Option<String>,
+    /// The containers are waited for once queued, the json and yaml formats then only
+    /// write the records of `wait_containers`, as a single document.
+    pub wait: bool,
+}
+
+impl QueueOptions {
+    pub(super) fn apply(
+        &self,
+        queued_container: &mut QueuedContainer,
+    ) -> Result<(), QueuedContainerError> {
+        if !self.paused {
+            queued_container.queue();
+        }
+        for &id in &self.after {
+            queued_container.add_dependency(Dependency::new(id, DependencyCondition::Finished));
+        }
+        for &id in &self.after_ok {
+            queued_container.add_dependency(Dependency::new(id, DependencyCondition::Succeeded));
+        }
+        if let Some(not_before) = self.not_before {
+            queued_container.set_not_before(not_before);
+        }
+        if let Some(estimate) = self.estimate {
+            queued_container.set_estimate(estimate);
+        }
+        if let Some(name) = &self.name {
+            queued_container.set_name(name.clone())?;
+        }
+        for (key, value) in &self.labels {
+            queued_container.add_label(key.clone(), value.clone());
+        }
+        if let Some(queue_name) = &self.queue_name {
+            queued_container.set_queue_name(queue_name.clone())?;
+        }
+        if !self.resources.is_empty() {
+            queued_container.set_resources(self.resources)?;
+        }
+        for path in &self.env_files {
+            // The server reads the env file, it might run in another directory
+            let path = std::fs::canonicalize(path)
+                .with_context(|| format!("Env file not found: {:?}", path))?;
+            queued_container.add_env_file(path);
+        }
+        Ok(())
+    }
+
+    /// Get where the env vars of the commands are replaced.
+    pub(super) fn env_expansion(&self) -> EnvExpansion {
+        if self.server_env {
+            EnvExpansion::Server
+        } else {
+            EnvExpansion::Client
+        }
+    }
+}
 
 impl<W: std::io::Write> ClientApp<W> {
+    /// Returns the id of the queued container.
     pub async fn queue_container(
         &mut self,
         command: String,
         is_path: bool,
         paused: bool,
-    ) -> Result<()> {
+    ) -> Result<String> {
+        let options = QueueOptions {
+            is_path,
+            paused,
+            ..Default::default()
+        };
+        self.queue_container_with_options(command, options).await
+    }
+
+    /// Returns the id of the queued container.
+    pub async fn queue_container_with_options(
+        &mut self,
+        command: String,
+        options: QueueOptions,
+    ) -> Result<String> {
         let client = reqwest::Client::new();
-        let mut queued_container = if is_path {
-            QueuedContainer::from_path(command).await
+        let env_expansion = options.env_expansion();
+        let mut queued_container = if options.is_spec {
+            let (command, ignored_keys) =
+                read_job_spec(&command, options.service.as_deref()).await?;
+            if !ignored_keys.is_empty() && self.format == OutputFormat::Table {
+                writeln!(
+                    self.writer,
+                    "Warning: Ignored keys of the job spec: {}",
+                    ignored_keys.join(", ")
+                )?;
+            }
+            QueuedContainer::with_env_expansion(command, env_expansion)
+        } else if options.is_path {
+            QueuedContainer::from_path_with_env_expansion(command, env_expansion).await
         } else {
-            QueuedContainer::new(command)
+            QueuedContainer::with_env_expansion(command, env_expansion)
         }?;
 
-        if !paused {
-            queued_container.queue();
+        options.apply(&mut queued_container)?;
+        if options.check {
+            self.preflight(std::slice::from_ref(&queued_container))
+                .await?;
         }
 
         let response = client
@@ -31,13 +164,88 @@ impl<W: std::io::Write> ClientApp<W> {
             return Err(ClientError::ServerStatusError(response.status()).into());
         }
 
-        writeln!(
-            self.writer,
-            "Container \"{}\" added to queue ({})",
-            queued_container.id(),
-            queued_container.status()
-        )?;
+        let output = QueuedContainerOutput {
+            id: queued_container.id(),
+            status: queued_container.status().to_string(),
+        };
+        if options.wait && self.format.is_document() {
+            return Ok(queued_container.id());
+        }
+        self.write_record(&output, |writer| {
+            writeln!(
+                writer,
+                "Container \"{}\" added to queue ({})",
+                output.id, output.status
+            )?;
+            Ok(())
+        })?;
+
+        Ok(queued_container.id())
+    }
+
+    /// Queues every container of the batch at `path`, a directory or a batch file, in a
+    /// single request, nothing gets queued if any of the commands is invalid. Returns the
+    /// ids of the queued containers.
+    pub async fn queue_containers_with_options(
+        &mut self,
+        path: String,
+        options: QueueOptions,
+    ) -> Result<Vec<String>> {
+        if options.name.is_some() {
+            return Err(ClientError::NamedBatch(path).into());
+        }
+        let mut queued_containers = read_batch(path, options.env_expansion()).await?;
+        for queued_container in queued_containers.iter_mut() {
+            options.apply(queued_container)?;
+        }
+        if options.check {
+            self.preflight(&queued_containers).await?;
+        }
+        self.post_queued_containers(&queued_containers).await?;
+
+        let output = queued_containers
+            .iter()
+            .map(|queued_container| QueuedContainerOutput {
+                id: queued_container.id(),
+                status: queued_container.status().to_string(),
+            })
+            .collect::<Vec<_>>();
+        if options.wait && self.format.is_document() {
+            return Ok(output.into_iter().map(|output| output.id).collect());
+        }
+        self.write_records(&output, |writer| {
+            for output in &output {
+                writeln!(
+                    writer,
+                    "Container \"{}\" added to queue ({})",
+                    output.id, output.status
+                )?;
+            }
+            Ok(())
+        })?;
 
+        Ok(queued_containers
+            .iter()
+            .map(|queued_container| queued_container.id())
+            .collect())
+    }
+
+    /// Queues all the `queued_containers` or none of them.
+    pub(super) async fn post_queued_containers(
+        &self,
+        queued_containers: &[QueuedContainer],
+    ) -> Result<()> {
+        let client = reqwest::Client::new();
+        let response = client
+            .post(format!("http://127.0.0.1:{}/queue_containers", self.port))
+            .json(queued_containers)
+            .send()
+            .await
+            .context("Failed to execute request.")?;
+
+        if !response.status().is_success() {
+            return Err(ClientError::ServerStatusError(response.status()).into());
+        }
         Ok(())
     }
 }
@@ -0,0 +1,33 @@
+use super::ClientApp;
+use crate::client::ClientError;
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct RemovedCronContainerOutput {
+    id: String,
+}
+
+impl<W: std::io::Write> ClientApp<W> {
+    pub async fn remove_cron_container(&mut self, id: &str) -> Result<()> {
+        let client = reqwest::Client::new();
+        let response = client
+            .delete(format!(
+                "http://127.0.0.1:{}/remove_cron_container/{}",
+                self.port, id
+            ))
+            .send()
+            .await
+            .context("Failed to execute request.")?;
+
+        if !response.status().is_success() {
+            return Err(ClientError::ServerStatusError(response.status()).into());
+        }
+
+        let output = RemovedCronContainerOutput { id: id.to_string() };
+        self.write_record(&output, |writer| {
+            writeln!(writer, "Cron container \"{}\" removed", output.id)?;
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,46 @@
+use super::ClientApp;
+use crate::client::ClientError;
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct RemovedSweepOutput {
+    id: String,
+    removed: usize,
+}
+
+impl<W: std::io::Write> ClientApp<W> {
+    /// Removes from the queue every container of the sweep `id`.
+    pub async fn remove_sweep(&mut self, id: &str) -> Result<()> {
+        let client = reqwest::Client::new();
+        let response = client
+            .delete(format!(
+                "http://127.0.0.1:{}/remove_sweep/{}",
+                self.port, id
+            ))
+            .send()
+            .await
+            .context("Failed to execute request.")?;
+
+        if !response.status().is_success() {
+            return Err(ClientError::ServerStatusError(response.status()).into());
+        }
+
+        let removed = response
+            .json::<usize>()
+            .await
+            .context("Failed to deserealize removed containers.")?;
+        let output = RemovedSweepOutput {
+            id: id.to_string(),
+            removed,
+        };
+        self.write_record(&output, |writer| {
+            writeln!(
+                writer,
+                "Sweep \"{}\": {} containers removed from the queue",
+                output.id, output.removed
+            )?;
+            Ok(())
+        })
+    }
+}
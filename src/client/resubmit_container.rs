@@ -0,0 +1,59 @@
+use super::ClientApp;
+use crate::{
+    client::ClientError,
+    domain::{QueuedContainer, ResubmitOptions},
+};
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct ResubmittedContainerOutput {
+    id: String,
+    resubmitted_from: String,
+    status: String,
+}
+
+impl<W: std::io::Write> ClientApp<W> {
+    /// Queues again the command of the finished or running container `id`, returns the
+    /// id of the new queued container.
+    pub async fn resubmit_container(
+        &mut self,
+        id: &str,
+        options: ResubmitOptions,
+    ) -> Result<String> {
+        let client = reqwest::Client::new();
+        let response = client
+            .post(format!(
+                "http://127.0.0.1:{}/resubmit_container/{}",
+                self.port, id
+            ))
+            .json(&options)
+            .send()
+            .await
+            .context("Failed to execute request.")?;
+
+        if !response.status().is_success() {
+            return Err(ClientError::ServerStatusError(response.status()).into());
+        }
+
+        let container = response
+            .json::<QueuedContainer>()
+            .await
+            .context("Failed to deserealize queued container.")?;
+        let output = ResubmittedContainerOutput {
+            id: container.id(),
+            resubmitted_from: id.to_string(),
+            status: container.status().to_string(),
+        };
+        self.write_record(&output, |writer| {
+            writeln!(
+                writer,
+                "Container \"{}\" resubmitted as \"{}\" ({})",
+                output.resubmitted_from, output.id, output.status
+            )?;
+            Ok(())
+        })?;
+
+        Ok(container.id())
+    }
+}
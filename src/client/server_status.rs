@@ -0,0 +1,57 @@
+use super::ClientApp;
+use crate::{client::ClientError, domain::ServerStatus};
+use anyhow::{Context, Result};
+
+impl<W: std::io::Write> ClientApp<W> {
+    /// Stops starting queued containers, the running ones finish. It lasts until
+    /// `resume_server`, across restarts of the server.
+    pub async fn drain_server(&mut self) -> Result<ServerStatus> {
+        let status = self.post_server_mode("drain").await?;
+        self.write_server_status(&status)?;
+        Ok(status)
+    }
+
+    /// Starts the queued containers again after `drain_server`.
+    pub async fn resume_server(&mut self) -> Result<ServerStatus> {
+        let status = self.post_server_mode("resume").await?;
+        self.write_server_status(&status)?;
+        Ok(status)
+    }
+
+    pub async fn get_server_status(&self) -> Result<ServerStatus> {
+        let client = reqwest::Client::new();
+        client
+            .get(format!("http://127.0.0.1:{}/status", self.port))
+            .send()
+            .await
+            .context("Failed to execute request.")?
+            .json::<ServerStatus>()
+            .await
+            .context("Failed to deserealize server status.")
+    }
+
+    fn write_server_status(&mut self, status: &ServerStatus) -> Result<()> {
+        self.write_record(status, |writer| {
+            writeln!(writer, "Server: {}", status)?;
+            Ok(())
+        })
+    }
+
+    async fn post_server_mode(&self, route: &str) -> Result<ServerStatus> {
+        let client = reqwest::Client::new();
+        let response = client
+            .post(format!("http://127.0.0.1:{}/{}", self.port, route))
+            .send()
+            .await
+            .context("Failed to execute request.")?;
+
+        if !response.status().is_success() {
+            return Err(ClientError::ServerStatusError(response.status()).into());
+        }
+
+        response
+            .json::<ServerStatus>()
+            .await
+            .context("Failed to deserealize server status.")
+    }
+}
@@ -0,0 +1,89 @@
+use super::{ClientApp, QueueOptions};
+use crate::domain::{expand_sweep, parse_commands, parse_sweep_params, strip_comments};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tokio::fs;
+use uuid::Uuid;
+
+#[derive(Serialize)]
+struct SweepJobOutput {
+    id: String,
+    command: String,
+}
+
+#[derive(Serialize)]
+struct SweepOutput {
+    id: String,
+    jobs: Vec<SweepJobOutput>,
+}
+
+impl<W: std::io::Write> ClientApp<W> {
+    /// Queues a container per combination of the placeholder values of `template`, all
+    /// tagged with a new sweep id, nothing gets queued if any of the commands is invalid.
+    /// Returns the sweep id.
+    ///
+    /// * `params` - Path to a yaml file with the values of the "{name}" placeholders
+    pub async fn sweep_containers(
+        &mut self,
+        template: String,
+        params: Option<String>,
+        options: QueueOptions,
+    ) -> Result<String> {
+        let template = if options.is_path {
+            let text = fs::read_to_string(&template)
+                .await
+                .context("Failed to read file.")?;
+            strip_comments(&text)
+        } else {
+            template
+        };
+        let params = match params {
+            Some(path) => {
+                let text = fs::read_to_string(&path)
+                    .await
+                    .context("Failed to read parameters file.")?;
+                parse_sweep_params(&text)?
+            }
+            None => BTreeMap::new(),
+        };
+
+        let commands = expand_sweep(&template, &params)?
+            .into_iter()
+            .enumerate()
+            .map(|(i, command)| (format!("job {}", i + 1), command))
+            .collect();
+        let mut queued_containers = parse_commands(commands, options.env_expansion())?;
+        let sweep_id = Uuid::new_v4();
+        for queued_container in queued_containers.iter_mut() {
+            queued_container.set_sweep_id(sweep_id);
+            options.apply(queued_container)?;
+        }
+        self.post_queued_containers(&queued_containers).await?;
+
+        let output = SweepOutput {
+            id: sweep_id.to_string(),
+            jobs: queued_containers
+                .iter()
+                .map(|queued_container| SweepJobOutput {
+                    id: queued_container.id(),
+                    command: queued_container.command().to_string(),
+                })
+                .collect(),
+        };
+        self.write_record(&output, |writer| {
+            writeln!(
+                writer,
+                "Sweep \"{}\" added to queue with {} containers",
+                output.id,
+                output.jobs.len()
+            )?;
+            for job in &output.jobs {
+                writeln!(writer, "  {}  {}", job.id, job.command)?;
+            }
+            Ok(())
+        })?;
+
+        Ok(sweep_id.to_string())
+    }
+}
@@ -0,0 +1,122 @@
+use super::ClientApp;
+use crate::domain::{ContainerStatus, FinishedOutcome};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::time::Duration;
+use tokio::time::sleep;
+
+// A container can exit with any code from 0 to 255, so these can't be told apart from
+// its own codes by the number alone, the "status" of the machine readable output does.
+// They are above the 128 + signal codes of a killed process and clear of the 126 and
+// 127 a shell reports for a command it can't run.
+
+/// Exit code returned by `wait_container` when "docker run" failed for the container,
+/// as docker run does.
+pub const LAUNCH_FAILED_EXIT_CODE: i32 = 125;
+/// Exit code returned by `wait_container` when the container was cancelled, e.g. a
+/// dependency failed.
+pub const CANCELLED_EXIT_CODE: i32 = 240;
+/// Exit code returned by `wait_container` when the container is unknown to the server,
+/// e.g. it was removed from the queue.
+pub const NOT_FOUND_EXIT_CODE: i32 = 241;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Serialize)]
+struct WaitContainerOutput {
+    id: String,
+    status: String,
+    exit_code: i32,
+}
+
+impl<W: std::io::Write> ClientApp<W> {
+    pub async fn get_container_status(&self, id: &str) -> Result<Option<ContainerStatus>> {
+        let client = reqwest::Client::new();
+        client
+            .get(format!(
+                "http://127.0.0.1:{}/get_container_status/{}",
+                self.port, id
+            ))
+            .send()
+            .await
+            .context("Failed to execute request.")?
+            .json::<Option<ContainerStatus>>()
+            .await
+            .context("Failed to deserealize container status.")
+    }
+
+    /// Blocks until the queued container `id` has finished, returns the exit code
+    /// the process should exit with.
+    pub async fn wait_container(&mut self, id: &str) -> Result<i32> {
+        let (output, message) = self.wait_for_container(id).await?;
+        self.write_record(&output, |writer| {
+            writeln!(writer, "Container \"{}\" {}", output.id, message)?;
+            Ok(())
+        })?;
+        Ok(output.exit_code)
+    }
+
+    /// Blocks until all the queued containers `ids` have finished, returns the first
+    /// non-zero exit code. The json and yaml formats write a single list once they all
+    /// finished, the others a record as each one finishes.
+    pub async fn wait_containers(&mut self, ids: &[String]) -> Result<i32> {
+        let mut exit_code = 0;
+        let mut outputs = Vec::new();
+        for id in ids {
+            let code = if self.format.is_document() {
+                let (output, _) = self.wait_for_container(id).await?;
+                let code = output.exit_code;
+                outputs.push(output);
+                code
+            } else {
+                self.wait_container(id).await?
+            };
+            if exit_code == 0 {
+                exit_code = code;
+            }
+        }
+        if self.format.is_document() {
+            self.write_records(&outputs, |_| Ok(()))?;
+        }
+        Ok(exit_code)
+    }
+
+    /// Blocks until the queued container `id` has finished, returns its record and the
+    /// message of the table format.
+    async fn wait_for_container(&self, id: &str) -> Result<(WaitContainerOutput, String)> {
+        let (status, exit_code, message) = loop {
+            match self.get_container_status(id).await? {
+                Some(ContainerStatus::Finished(outcome)) => {
+                    let (exit_code, message) = match &outcome {
+                        FinishedOutcome::Exited(exit_code) => {
+                            (*exit_code as i32, format!("exited with code {}", exit_code))
+                        }
+                        FinishedOutcome::LaunchFailed(error) => (
+                            LAUNCH_FAILED_EXIT_CODE,
+                            format!("failed to launch: {}", error),
+                        ),
+                        FinishedOutcome::Cancelled(reason) => {
+                            (CANCELLED_EXIT_CODE, format!("cancelled: {}", reason))
+                        }
+                    };
+                    break (outcome.to_string(), exit_code, message);
+                }
+                Some(_) => sleep(POLL_INTERVAL).await,
+                None => {
+                    break (
+                        "Not found".to_string(),
+                        NOT_FOUND_EXIT_CODE,
+                        "not found".to_string(),
+                    )
+                }
+            }
+        };
+
+        let output = WaitContainerOutput {
+            id: id.to_string(),
+            status,
+            exit_code,
+        };
+        Ok((output, message))
+    }
+}
@@ -1,4 +1,27 @@
-#[derive(Debug)]
+use crate::domain::{Lane, Resources, SchedulingPolicy, ShutdownPolicy};
+use std::path::PathBuf;
+
+#[derive(Debug, Default)]
 pub struct Settings {
     pub port: u16,
+    /// Name of the server in the labels of the containers it launches, "127.0.0.1:<port>"
+    /// if not set.
+    pub instance: Option<String>,
+    /// Number of queued containers ahead whose images are pulled in the background,
+    /// 0 to disable the prefetcher.
+    pub prefetch_depth: usize,
+    /// Maximum number of images pulled at the same time by the prefetcher.
+    pub prefetch_concurrency: usize,
+    /// Resources of the host shared by the running containers, empty to run them one at
+    /// a time.
+    pub capacity: Resources,
+    /// How the launcher picks the next container.
+    pub scheduling: SchedulingPolicy,
+    /// Queues with their own FIFO and concurrency limit, next to the default queue.
+    pub lanes: Vec<Lane>,
+    /// File where the state that survives restarts is saved, the drain mode and on
+    /// shutdown the queued containers. `None` to keep it in memory only.
+    pub state_file: Option<PathBuf>,
+    /// What happens to the running containers when the server shuts down.
+    pub shutdown_policy: ShutdownPolicy,
 }
@@ -0,0 +1,192 @@
+use super::{strip_comments, EnvExpansion, QueuedContainer};
+use crate::error_chain_fmt;
+use anyhow::Context;
+use std::path::Path;
+use tokio::fs;
+
+#[derive(thiserror::Error)]
+pub enum BatchError {
+    #[error("Invalid batch entries:\n{}", .0.join("\n"))]
+    InvalidEntries(Vec<String>),
+    #[error("Invalid yaml batch, expected a list of commands: {0}")]
+    InvalidYaml(String),
+    #[error("The batch has no commands.")]
+    EmptyBatch,
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+impl std::fmt::Debug for BatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        error_chain_fmt(self, f)
+    }
+}
+
+/// Reads the queued containers of a batch, `path` is either:
+/// * A directory, with a docker run command per file, queued in file name order.
+/// * A ".yaml" or ".yml" file with a list of docker run commands.
+/// * Any other file, with a docker run command per block of lines separated by
+///   blank lines.
+///
+/// Fails if any of the commands is invalid.
+pub async fn read_batch(
+    path: impl AsRef<Path>,
+    env_expansion: EnvExpansion,
+) -> Result<Vec<QueuedContainer>, BatchError> {
+    let path = path.as_ref();
+    let entries = if path.is_dir() {
+        read_dir_entries(path).await?
+    } else {
+        let text = fs::read_to_string(path)
+            .await
+            .context("Failed to read file.")?;
+        let is_yaml = matches!(
+            path.extension().and_then(|extension| extension.to_str()),
+            Some("yaml" | "yml")
+        );
+        if is_yaml {
+            yaml_entries(&text)?
+        } else {
+            block_entries(&text)
+        }
+    };
+    parse_commands(entries, env_expansion)
+}
+
+/// Get a command per file in the directory, as `(file name, command)`.
+async fn read_dir_entries(path: &Path) -> Result<Vec<(String, String)>, BatchError> {
+    let mut paths = Vec::new();
+    let mut dir = fs::read_dir(path)
+        .await
+        .context("Failed to read directory.")?;
+    while let Some(entry) = dir
+        .next_entry()
+        .await
+        .context("Failed to read directory.")?
+    {
+        let path = entry.path();
+        let is_hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if path.is_file() && !is_hidden {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut entries = Vec::new();
+    for path in paths {
+        let text = fs::read_to_string(&path)
+            .await
+            .with_context(|| format!("Failed to read file {:?}.", path))?;
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        entries.push((name, strip_comments(&text)));
+    }
+    Ok(entries)
+}
+
+/// Get a command per item of a yaml list, as `("entry N", command)`.
+fn yaml_entries(text: &str) -> Result<Vec<(String, String)>, BatchError> {
+    let commands: Vec<String> =
+        serde_yaml::from_str(text).map_err(|error| BatchError::InvalidYaml(error.to_string()))?;
+    Ok(commands
+        .into_iter()
+        .enumerate()
+        .map(|(i, command)| (format!("entry {}", i + 1), command))
+        .collect())
+}
+
+/// Get a command per block of lines separated by blank lines, as `("block N", command)`,
+/// blocks with only comments are skipped.
+fn block_entries(text: &str) -> Vec<(String, String)> {
+    let text = strip_comments(text);
+    let mut blocks = vec![Vec::new()];
+    for line in text.lines() {
+        if !line.trim().is_empty() {
+            blocks.last_mut().unwrap().push(line);
+        } else if !blocks.last().unwrap().is_empty() {
+            blocks.push(Vec::new());
+        }
+    }
+    blocks
+        .into_iter()
+        .filter(|block| !block.is_empty())
+        .enumerate()
+        .map(|(i, block)| (format!("block {}", i + 1), block.join("\n")))
+        .collect()
+}
+
+/// Creates a queued container per `(name, command)`, fails naming every invalid entry.
+pub fn parse_commands(
+    entries: Vec<(String, String)>,
+    env_expansion: EnvExpansion,
+) -> Result<Vec<QueuedContainer>, BatchError> {
+    if entries.is_empty() {
+        return Err(BatchError::EmptyBatch);
+    }
+
+    let mut containers = Vec::new();
+    let mut errors = Vec::new();
+    for (name, command) in entries {
+        match QueuedContainer::with_env_expansion(command.trim(), env_expansion) {
+            Ok(container) => containers.push(container),
+            Err(error) => errors.push(format!("{}: {}", name, error)),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(BatchError::InvalidEntries(errors));
+    }
+    Ok(containers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claim::{assert_err, assert_ok};
+
+    #[test]
+    fn block_entries_are_separated_by_blank_lines() {
+        let text = "# Sweep\ndocker run -d \\\n  img a\n \n\n# Second\n\ndocker run -d img b\n";
+        let entries = block_entries(text);
+        let commands = entries
+            .iter()
+            .map(|(_, command)| command.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            commands,
+            vec!["docker run -d \\\n  img a", "docker run -d img b"]
+        );
+    }
+
+    #[test]
+    fn parse_entries_rejects_the_whole_batch() {
+        let entries = vec![
+            ("block 1".to_string(), "docker run -d img".to_string()),
+            ("block 2".to_string(), "docker run -it img".to_string()),
+        ];
+        match parse_commands(entries, EnvExpansion::Client) {
+            Err(BatchError::InvalidEntries(errors)) => {
+                assert_eq!(1, errors.len());
+                assert!(errors[0].starts_with("block 2:"));
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+        assert_err!(parse_commands(Vec::new(), EnvExpansion::Client));
+    }
+
+    #[test]
+    fn yaml_entries_are_a_list_of_commands() {
+        let entries = yaml_entries("- docker run -d img a\n- |\n  docker run -d \\\n    img b\n");
+        let containers = parse_commands(entries.unwrap(), EnvExpansion::Client).unwrap();
+        assert_eq!(2, containers.len());
+        assert_err!(yaml_entries("command: docker run -d img"));
+    }
+
+    #[tokio::test]
+    async fn read_batch_reads_a_directory() {
+        let containers = read_batch("tests/examples", EnvExpansion::Client).await;
+        assert_ok!(&containers);
+        assert_eq!(5, containers.unwrap().len());
+    }
+}
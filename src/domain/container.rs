@@ -1,8 +1,8 @@
-use super::{QueuedContainer, RunningContainer};
+use super::{QueueInfo, QueuedContainer, RunningContainer};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum Container {
     Running(Box<RunningContainer>),
-    Queued(QueuedContainer),
+    Queued(Box<QueuedContainer>, QueueInfo),
 }
@@ -0,0 +1,24 @@
+use super::{FinishedOutcome, RunningContainerId};
+use serde::{Deserialize, Serialize};
+
+/// Where a queued container is in its lifecycle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ContainerStatus {
+    Queued,
+    Paused,
+    Launching,
+    Running(RunningContainerId),
+    Finished(FinishedOutcome),
+}
+
+impl std::fmt::Display for ContainerStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContainerStatus::Queued => write!(f, "Queued"),
+            ContainerStatus::Paused => write!(f, "Paused"),
+            ContainerStatus::Launching => write!(f, "Launching"),
+            ContainerStatus::Running(_) => write!(f, "Running"),
+            ContainerStatus::Finished(outcome) => write!(f, "{}", outcome),
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use super::QueuedContainer;
+use crate::error_chain_fmt;
+use chrono::{DateTime, Local, Utc};
+use cron::Schedule;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(thiserror::Error)]
+pub enum CronContainerError {
+    #[error("Invalid cron expression {0:?}: {1}")]
+    InvalidSchedule(String, String),
+}
+
+impl std::fmt::Debug for CronContainerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        error_chain_fmt(self, f)
+    }
+}
+
+/// A template of a queued container that gets queued on every tick of a cron schedule.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CronContainer {
+    id: Uuid,
+    schedule: String,
+    container: QueuedContainer,
+}
+
+impl CronContainer {
+    /// * `schedule` - A cron expression as "min hour day month weekday", optionally with a
+    ///   leading seconds field, evaluated in local time. The weekdays are numbered as in
+    ///   crontab, from 0 for Sunday to 6 for Saturday, 7 is Sunday too. As in crontab, a
+    ///   schedule that restricts both the day of the month and the weekday ticks on the
+    ///   days matching either of them
+    pub fn new(
+        schedule: impl Into<String>,
+        container: QueuedContainer,
+    ) -> Result<Self, CronContainerError> {
+        let cron_container = Self {
+            id: Uuid::new_v4(),
+            schedule: schedule.into(),
+            container,
+        };
+        cron_container.validate()?;
+        Ok(cron_container)
+    }
+
+    /// Checks the schedule is a valid cron expression.
+    pub fn validate(&self) -> Result<(), CronContainerError> {
+        parse_schedule(&self.schedule).map(|_| ())
+    }
+
+    /// Get a reference to the cron container's id.
+    pub fn id(&self) -> String {
+        self.id.to_string()
+    }
+
+    /// Get a reference to the cron container's schedule.
+    pub fn schedule(&self) -> &str {
+        self.schedule.as_ref()
+    }
+
+    /// Get a reference to the queued container used as template.
+    pub fn container(&self) -> &QueuedContainer {
+        &self.container
+    }
+
+    /// Get the first tick of the schedule strictly after `time`.
+    pub fn next_after(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let schedules = parse_schedule(&self.schedule).ok()?;
+        let next = schedules
+            .iter()
+            .filter_map(|schedule| schedule.after(&time.with_timezone(&Local)).next())
+            .min()?;
+        Some(next.with_timezone(&Utc))
+    }
+
+    /// Creates the queued container to run for a tick of the schedule.
+    pub fn instantiate(&self) -> QueuedContainer {
+        let mut container = self.container.duplicate();
+        container.queue();
+        container
+    }
+}
+
+/// Names of the weekdays in crontab order, from 0 for Sunday.
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+/// Index of the day of the month field once the expression has its seconds field.
+const DAY_FIELD: usize = 3;
+/// Index of the weekday field once the expression has its seconds field.
+const WEEKDAY_FIELD: usize = 5;
+
+/// The `cron` crate expects a seconds field, a standard five field expression runs at
+/// second zero. It also numbers the weekdays from 1 for Sunday, so the numeric weekdays
+/// are given to it by name. It ticks on the days matching both the day of the month and
+/// the weekday, crontab on the days matching either if both are restricted, i.e. don't
+/// start with "*", so such a schedule is split into one schedule per field.
+fn parse_schedule(schedule: &str) -> Result<Vec<Schedule>, CronContainerError> {
+    let invalid = |error: String| CronContainerError::InvalidSchedule(schedule.into(), error);
+    let mut fields = schedule.split_whitespace().collect::<Vec<_>>();
+    if fields.len() == 5 {
+        fields.insert(0, "0");
+    }
+    let weekdays = fields
+        .get(WEEKDAY_FIELD)
+        .map(|field| name_weekdays(field))
+        .transpose()
+        .map_err(invalid)?;
+    let mut fields = fields.into_iter().map(String::from).collect::<Vec<_>>();
+    if let Some(weekdays) = weekdays {
+        fields[WEEKDAY_FIELD] = weekdays;
+    }
+    let is_restricted = |index: usize| {
+        fields
+            .get(index)
+            .is_some_and(|field| !field.starts_with('*') && field != "?")
+    };
+    let mut variants = vec![fields.clone()];
+    if is_restricted(DAY_FIELD) && is_restricted(WEEKDAY_FIELD) {
+        variants[0][WEEKDAY_FIELD] = "*".to_string();
+        let mut by_weekday = fields;
+        by_weekday[DAY_FIELD] = "*".to_string();
+        variants.push(by_weekday);
+    }
+    variants
+        .iter()
+        .map(|fields| {
+            Schedule::from_str(&fields.join(" ")).map_err(|error| invalid(error.to_string()))
+        })
+        .collect()
+}
+
+/// Replaces the crontab weekday numbers of a weekday field with names, as "1-5" with
+/// "Mon,Tue,Wed,Thu,Fri" or "*/2" with "Sun,Tue,Thu,Sat". Items without numbers, as
+/// "*" or "Mon-Fri", are kept.
+fn name_weekdays(field: &str) -> Result<String, String> {
+    let invalid = || format!("Invalid weekdays {:?}, expected numbers from 0 to 7", field);
+    let items = field.split(',').map(|item| {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => {
+                let step = step.parse::<usize>().ok().filter(|&step| step > 0);
+                (range, Some(step.ok_or_else(invalid)?))
+            }
+            None => (item, None),
+        };
+        let is_numeric = range.contains(|c: char| c.is_ascii_digit());
+        if !is_numeric && (range != "*" || step.is_none()) {
+            return Ok(item.to_string());
+        }
+        let parse = |day: &str| day.parse::<usize>().map_err(|_| invalid());
+        let (first, last) = match range.split_once('-') {
+            _ if range == "*" => (0, 6),
+            Some((first, last)) => (parse(first)?, parse(last)?),
+            // As in crontab, "1/2" steps from 1 to the end of the week
+            None if step.is_some() => (parse(range)?, 6),
+            None => (parse(range)?, parse(range)?),
+        };
+        if first > last || last > 7 {
+            return Err(invalid());
+        }
+        let mut days = Vec::new();
+        for day in (first..=last).step_by(step.unwrap_or(1)) {
+            let name = WEEKDAYS[day % 7];
+            if !days.contains(&name) {
+                days.push(name);
+            }
+        }
+        Ok(days.join(","))
+    });
+    Ok(items.collect::<Result<Vec<_>, String>>()?.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use claim::{assert_err, assert_ok};
+    use test_case::test_case;
+
+    fn container() -> QueuedContainer {
+        QueuedContainer::new("docker run -d some_image").unwrap()
+    }
+
+    #[test]
+    fn reject_invalid_schedules() {
+        assert_ok!(CronContainer::new("0 2 * * *", container()));
+        assert_ok!(CronContainer::new("30 0 2 * * Mon-Fri", container()));
+        assert_err!(CronContainer::new("every night", container()));
+        assert_err!(CronContainer::new("0 25 * * *", container()));
+    }
+
+    // Friday 2021-11-05 at 12:00
+    #[test_case("0 2 * * 1", 8; "Monday")]
+    #[test_case("0 2 * * 0", 7; "Sunday as 0")]
+    #[test_case("0 2 * * 7", 7; "Sunday as 7")]
+    #[test_case("0 2 * * 1-4", 8; "Range")]
+    #[test_case("0 2 * * 2,5-6", 6; "List")]
+    #[test_case("0 2 * * */2", 6; "Step")]
+    #[test_case("0 0 2 * * Sun", 7; "Name with seconds")]
+    fn weekdays_are_numbered_as_in_crontab(schedule: &str, day: u32) {
+        let cron_container = CronContainer::new(schedule, container()).unwrap();
+        let time = Local.ymd(2021, 11, 5).and_hms(12, 0, 0);
+        let next = cron_container.next_after(time.with_timezone(&Utc)).unwrap();
+        assert_eq!(Local.ymd(2021, 11, day).and_hms(2, 0, 0), next);
+    }
+
+    // Friday 2021-11-05 at 12:00
+    #[test_case("0 2 13 * 1", (2021, 11, 8); "Weekday first")]
+    #[test_case("0 2 6 * 1", (2021, 11, 6); "Day of the month first")]
+    #[test_case("0 2 */10 * 1", (2022, 1, 31); "Day of the month with a star matches both")]
+    fn restricted_day_and_weekday_match_either(
+        schedule: &str,
+        (year, month, day): (i32, u32, u32),
+    ) {
+        let cron_container = CronContainer::new(schedule, container()).unwrap();
+        let time = Local.ymd(2021, 11, 5).and_hms(12, 0, 0);
+        let next = cron_container.next_after(time.with_timezone(&Utc)).unwrap();
+        assert_eq!(Local.ymd(year, month, day).and_hms(2, 0, 0), next);
+    }
+
+    #[test]
+    fn name_weekdays_rejects_invalid_numbers() {
+        assert_eq!("Mon,Tue,Wed,Thu,Fri", name_weekdays("1-5").unwrap());
+        assert_eq!("Fri,Sat,Sun", name_weekdays("5-7").unwrap());
+        assert_eq!("*", name_weekdays("*").unwrap());
+        assert_eq!("Mon-Fri", name_weekdays("Mon-Fri").unwrap());
+        assert_err!(name_weekdays("8"));
+        assert_err!(name_weekdays("5-1"));
+        assert_err!(name_weekdays("*/0"));
+    }
+
+    #[test]
+    fn next_after_works() {
+        let cron_container = CronContainer::new("0 2 * * *", container()).unwrap();
+        let time = Local.ymd(2021, 11, 5).and_hms(12, 0, 0);
+        let next = cron_container.next_after(time.with_timezone(&Utc)).unwrap();
+        assert_eq!(Local.ymd(2021, 11, 6).and_hms(2, 0, 0), next);
+    }
+
+    #[test]
+    fn instantiate_creates_new_queued_containers() {
+        let cron_container = CronContainer::new("0 2 * * *", container()).unwrap();
+        let first = cron_container.instantiate();
+        let second = cron_container.instantiate();
+        assert_ne!(first.id(), second.id());
+        assert_eq!(first.command(), cron_container.container().command());
+        assert!(first.is_queued());
+    }
+}
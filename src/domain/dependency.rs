@@ -0,0 +1,142 @@
+use super::{ContainerStatus, FinishedOutcome};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A queued container that has to finish before another one can run.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Dependency {
+    id: Uuid,
+    condition: DependencyCondition,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum DependencyCondition {
+    /// The dependency has run and exited, whatever the exit code.
+    Finished,
+    /// The dependency has exited with code 0.
+    Succeeded,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum DependencyState {
+    /// The dependency has not finished yet.
+    Pending,
+    Satisfied,
+    /// The dependency can not be satisfied anymore.
+    Failed,
+}
+
+impl std::fmt::Display for DependencyCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            DependencyCondition::Finished => "after",
+            DependencyCondition::Succeeded => "after-ok",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl std::fmt::Display for DependencyState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            DependencyState::Pending => "Pending",
+            DependencyState::Satisfied => "Satisfied",
+            DependencyState::Failed => "Failed",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl Dependency {
+    pub fn new(id: Uuid, condition: DependencyCondition) -> Self {
+        Self { id, condition }
+    }
+
+    /// Get the id of the queued container depended on.
+    pub fn id(&self) -> String {
+        self.id.to_string()
+    }
+
+    /// Get a reference to the dependency's condition.
+    pub fn condition(&self) -> &DependencyCondition {
+        &self.condition
+    }
+
+    /// Get the state of the dependency given the status of the container depended on,
+    /// `None` if the container is unknown.
+    pub fn state(&self, status: Option<&ContainerStatus>) -> DependencyState {
+        match (status, &self.condition) {
+            (None, _) => DependencyState::Failed,
+            (
+                Some(ContainerStatus::Finished(FinishedOutcome::Exited(_))),
+                DependencyCondition::Finished,
+            ) => DependencyState::Satisfied,
+            (
+                Some(ContainerStatus::Finished(FinishedOutcome::Exited(0))),
+                DependencyCondition::Succeeded,
+            ) => DependencyState::Satisfied,
+            (Some(ContainerStatus::Finished(_)), _) => DependencyState::Failed,
+            (Some(_), _) => DependencyState::Pending,
+        }
+    }
+}
+
+impl DependencyState {
+    /// Combines the states of several dependencies, any failed dependency fails
+    /// the whole set and all of them have to be satisfied to satisfy it.
+    pub fn all(states: impl IntoIterator<Item = DependencyState>) -> DependencyState {
+        states
+            .into_iter()
+            .fold(DependencyState::Satisfied, |acc, state| {
+                match (acc, state) {
+                    (DependencyState::Failed, _) | (_, DependencyState::Failed) => {
+                        DependencyState::Failed
+                    }
+                    (DependencyState::Pending, _) | (_, DependencyState::Pending) => {
+                        DependencyState::Pending
+                    }
+                    _ => DependencyState::Satisfied,
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::RunningContainerId;
+    use test_case::test_case;
+
+    #[test_case(None, DependencyState::Failed; "Not found")]
+    #[test_case(Some(ContainerStatus::Queued), DependencyState::Pending; "Queued")]
+    #[test_case(Some(ContainerStatus::Running(RunningContainerId::new("1"))), DependencyState::Pending; "Running")]
+    #[test_case(Some(ContainerStatus::Finished(FinishedOutcome::Exited(0))), DependencyState::Satisfied; "Succeeded")]
+    #[test_case(Some(ContainerStatus::Finished(FinishedOutcome::Exited(1))), DependencyState::Satisfied; "Failed")]
+    #[test_case(Some(ContainerStatus::Finished(FinishedOutcome::Cancelled("".into()))), DependencyState::Failed; "Cancelled")]
+    fn after_dependency_state(status: Option<ContainerStatus>, expected: DependencyState) {
+        let dependency = Dependency::new(Uuid::new_v4(), DependencyCondition::Finished);
+        assert_eq!(expected, dependency.state(status.as_ref()));
+    }
+
+    #[test_case(None, DependencyState::Failed; "Not found")]
+    #[test_case(Some(ContainerStatus::Paused), DependencyState::Pending; "Paused")]
+    #[test_case(Some(ContainerStatus::Finished(FinishedOutcome::Exited(0))), DependencyState::Satisfied; "Succeeded")]
+    #[test_case(Some(ContainerStatus::Finished(FinishedOutcome::Exited(1))), DependencyState::Failed; "Failed")]
+    #[test_case(Some(ContainerStatus::Finished(FinishedOutcome::LaunchFailed("".into()))), DependencyState::Failed; "Launch failed")]
+    fn after_ok_dependency_state(status: Option<ContainerStatus>, expected: DependencyState) {
+        let dependency = Dependency::new(Uuid::new_v4(), DependencyCondition::Succeeded);
+        assert_eq!(expected, dependency.state(status.as_ref()));
+    }
+
+    #[test]
+    fn all_dependency_states_combine() {
+        use DependencyState::*;
+        assert_eq!(Satisfied, DependencyState::all(vec![]));
+        assert_eq!(Satisfied, DependencyState::all(vec![Satisfied, Satisfied]));
+        assert_eq!(Pending, DependencyState::all(vec![Satisfied, Pending]));
+        assert_eq!(
+            Failed,
+            DependencyState::all(vec![Pending, Failed, Satisfied])
+        );
+    }
+}
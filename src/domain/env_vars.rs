@@ -0,0 +1,199 @@
+use anyhow::{Context, Result};
+use std::{collections::HashMap, path::Path};
+
+/// Expands the env vars of a command as the shell does: "$VAR", "${VAR}",
+/// "${VAR:-default}" (default if unset or empty) and "${VAR-default}" (default if unset)
+/// are replaced, except between single quotes or after a "\". The values are quoted so
+/// they stay a single argument. Returns the names of the env vars not found otherwise.
+pub fn expand_env_vars(
+    command: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<String, Vec<String>> {
+    let mut expanded = String::with_capacity(command.len());
+    let mut not_found: Vec<String> = Vec::new();
+    let mut in_single_quotes = false;
+    let mut in_double_quotes = false;
+    let mut chars = command.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if in_single_quotes {
+            in_single_quotes = c != '\'';
+            expanded.push(c);
+            continue;
+        }
+        match c {
+            '\\' => {
+                expanded.push(c);
+                if let Some((_, escaped)) = chars.next() {
+                    expanded.push(escaped);
+                }
+            }
+            '\'' if !in_double_quotes => {
+                in_single_quotes = true;
+                expanded.push(c);
+            }
+            '"' => {
+                in_double_quotes = !in_double_quotes;
+                expanded.push(c);
+            }
+            '$' => {
+                let rest = &command[i + 1..];
+                let expansion = match parse_expansion(rest) {
+                    Some(expansion) => expansion,
+                    None => {
+                        expanded.push(c);
+                        continue;
+                    }
+                };
+                // Skips the expansion, the "$" is already consumed
+                let end = i + expansion.len;
+                while chars.next_if(|&(j, _)| j <= end).is_some() {}
+                // ":-" also uses the default for empty values
+                let value = lookup(expansion.name).filter(|value| {
+                    !(value.is_empty() && matches!(expansion.default, Some((_, true))))
+                });
+                match (value, expansion.default) {
+                    (Some(value), _) => expanded.push_str(&quote(&value, in_double_quotes)),
+                    (None, Some((default, _))) => expanded.push_str(default),
+                    (None, None) => {
+                        if !not_found.iter().any(|name| name == expansion.name) {
+                            not_found.push(expansion.name.to_string());
+                        }
+                    }
+                }
+            }
+            _ => expanded.push(c),
+        }
+    }
+    if !not_found.is_empty() {
+        return Err(not_found);
+    }
+    Ok(expanded)
+}
+
+struct Expansion<'a> {
+    name: &'a str,
+    /// The default value, and whether it is also used for empty values.
+    default: Option<(&'a str, bool)>,
+    /// Length of the expansion after the "$".
+    len: usize,
+}
+
+/// Parses the expansion after a "$", `None` if it isn't one.
+fn parse_expansion(rest: &str) -> Option<Expansion<'_>> {
+    if let Some(braced) = rest.strip_prefix('{') {
+        let end = braced.find('}')?;
+        let content = &braced[..end];
+        let name_len = name_len(content);
+        let (name, modifier) = content.split_at(name_len);
+        let default = if modifier.is_empty() {
+            None
+        } else if let Some(default) = modifier.strip_prefix(":-") {
+            Some((default, true))
+        } else if let Some(default) = modifier.strip_prefix('-') {
+            Some((default, false))
+        } else {
+            return None;
+        };
+        (name_len > 0).then(|| Expansion {
+            name,
+            default,
+            len: end + 2,
+        })
+    } else {
+        let name_len = name_len(rest);
+        (name_len > 0).then(|| Expansion {
+            name: &rest[..name_len],
+            default: None,
+            len: name_len,
+        })
+    }
+}
+
+/// Length of the env var name at the start of `s`, 0 if there is none.
+fn name_len(s: &str) -> usize {
+    if !s.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        return 0;
+    }
+    s.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(s.len())
+}
+
+/// Quotes `value` so it is read back as is.
+fn quote(value: &str, in_double_quotes: bool) -> String {
+    if in_double_quotes {
+        value
+            .chars()
+            .flat_map(|c| match c {
+                '"' | '\\' | '$' | '`' => vec!['\\', c],
+                c => vec![c],
+            })
+            .collect()
+    } else if value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "_-./:=@%+,".contains(c))
+    {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// Reads the env vars of a docker env file: a "VAR=value" per line, lines starting with
+/// "#" are comments and a "VAR" alone takes its value from the environment.
+pub fn read_env_file(path: impl AsRef<Path>) -> Result<HashMap<String, String>> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read env file {:?}.", path))?;
+    let vars = text
+        .lines()
+        .map(str::trim_start)
+        .filter(|line| !(line.is_empty() || line.starts_with('#')))
+        .filter_map(|line| match line.split_once('=') {
+            Some((name, value)) => Some((name.to_string(), value.to_string())),
+            None => std::env::var(line.trim_end())
+                .ok()
+                .map(|value| (line.trim_end().to_string(), value)),
+        })
+        .collect();
+    Ok(vars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "A" => Some("a".to_string()),
+            "EMPTY" => Some("".to_string()),
+            "SPACES" => Some("a b".to_string()),
+            "QUOTE" => Some("it's \"x\"".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test_case("-e A=$A", "-e A=a"; "Plain")]
+    #[test_case("-e A=${A}_1", "-e A=a_1"; "Braces")]
+    #[test_case("${MISSING:-x} ${EMPTY:-y} ${EMPTY-z}", "x y "; "Defaults")]
+    #[test_case("'$A' \"$A\"", "'$A' \"a\""; "Single quotes")]
+    #[test_case("\\$A $ $1 ${1}", "\\$A $ $1 ${1}"; "Not expanded")]
+    #[test_case("$SPACES \"$SPACES\"", "'a b' \"a b\""; "Spaces")]
+    #[test_case("$QUOTE \"$QUOTE\"", "'it'\\''s \"x\"' \"it's \\\"x\\\"\""; "Quotes")]
+    fn expand_env_vars_works(command: &str, expected: &str) {
+        assert_eq!(Ok(expected.to_string()), expand_env_vars(command, lookup));
+    }
+
+    #[test]
+    fn expanded_values_stay_one_argument() {
+        let expanded = expand_env_vars("$SPACES \"$SPACES\" $QUOTE \"$QUOTE\"", lookup).unwrap();
+        let args = shellwords::split(&expanded).unwrap();
+        assert_eq!(args, vec!["a b", "a b", "it's \"x\"", "it's \"x\""]);
+    }
+
+    #[test]
+    fn expand_env_vars_lists_the_vars_not_found() {
+        let result = expand_env_vars("$X ${Y} $X $A ${Z:-z}", lookup);
+        assert_eq!(Err(vec!["X".to_string(), "Y".to_string()]), result);
+    }
+}
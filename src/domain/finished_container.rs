@@ -0,0 +1,86 @@
+use super::{QueuedContainer, RunningContainerId};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A queued container that is no longer in the queue nor running.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinishedContainer {
+    container: QueuedContainer,
+    running_id: Option<RunningContainerId>,
+    outcome: FinishedOutcome,
+    started_at: Option<DateTime<Utc>>,
+    finished_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FinishedOutcome {
+    /// The container ran and exited with the given code.
+    Exited(i64),
+    /// "docker run" failed, the container never ran.
+    LaunchFailed(String),
+    /// The container was removed from the queue without running.
+    Cancelled(String),
+}
+
+impl std::fmt::Display for FinishedOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FinishedOutcome::Exited(exit_code) => write!(f, "Exited ({})", exit_code),
+            FinishedOutcome::LaunchFailed(_) => write!(f, "Launch failed"),
+            FinishedOutcome::Cancelled(_) => write!(f, "Cancelled"),
+        }
+    }
+}
+
+impl FinishedContainer {
+    pub fn new(
+        container: QueuedContainer,
+        running_id: Option<RunningContainerId>,
+        outcome: FinishedOutcome,
+    ) -> Self {
+        Self {
+            container,
+            running_id,
+            outcome,
+            started_at: None,
+            finished_at: Utc::now(),
+        }
+    }
+
+    /// Set the time the container was launched.
+    pub fn with_started_at(mut self, started_at: DateTime<Utc>) -> Self {
+        self.started_at = Some(started_at);
+        self
+    }
+
+    /// Get a reference to the finished container's queued container.
+    pub fn container(&self) -> &QueuedContainer {
+        &self.container
+    }
+
+    /// Get a reference to the docker id, if the container was launched.
+    pub fn running_id(&self) -> Option<&RunningContainerId> {
+        self.running_id.as_ref()
+    }
+
+    /// Get a reference to the finished container's outcome.
+    pub fn outcome(&self) -> &FinishedOutcome {
+        &self.outcome
+    }
+
+    /// Get the time the container was launched.
+    pub fn started_at(&self) -> Option<DateTime<Utc>> {
+        self.started_at
+    }
+
+    /// Get the time the container finished or left the queue.
+    pub fn finished_at(&self) -> DateTime<Utc> {
+        self.finished_at
+    }
+
+    /// Get how long the container ran, if it was launched.
+    pub fn duration(&self) -> Option<Duration> {
+        self.started_at
+            .map(|started_at| self.finished_at - started_at)
+    }
+}
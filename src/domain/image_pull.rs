@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+/// Where the prefetch of the image of a queued container stands.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PullStatus {
+    /// Waiting for a free pull slot.
+    Waiting,
+    /// Being pulled, with the latest progress reported by docker.
+    Pulling(String),
+    /// Pulled by the prefetcher.
+    Pulled,
+    /// Already available locally.
+    Present,
+    Failed(String),
+}
+
+impl std::fmt::Display for PullStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PullStatus::Waiting => write!(f, "Waiting"),
+            PullStatus::Pulling(progress) if progress.is_empty() => write!(f, "Pulling"),
+            PullStatus::Pulling(progress) => write!(f, "Pulling ({})", progress),
+            PullStatus::Pulled => write!(f, "Pulled"),
+            PullStatus::Present => write!(f, "Present"),
+            PullStatus::Failed(error) => write!(f, "Failed: {}", error),
+        }
+    }
+}
+
+/// Splits an image reference into the image and tag to pull: the tag is "latest" if
+/// there is none so docker doesn't pull every tag, and empty for digests that stay in
+/// the image.
+pub fn split_image_tag(image: &str) -> (&str, &str) {
+    if image.contains('@') {
+        return (image, "");
+    }
+    let name_start = image.rfind('/').map_or(0, |i| i + 1);
+    match image[name_start..].rfind(':') {
+        Some(i) => (&image[..name_start + i], &image[name_start + i + 1..]),
+        None => (image, "latest"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("alpine", ("alpine", "latest"); "No tag")]
+    #[test_case("alpine:3.14", ("alpine", "3.14"); "Tag")]
+    #[test_case("localhost:5000/team/app", ("localhost:5000/team/app", "latest"); "Registry port")]
+    #[test_case("localhost:5000/app:v1", ("localhost:5000/app", "v1"); "Registry port and tag")]
+    #[test_case("app@sha256:abc", ("app@sha256:abc", ""); "Digest")]
+    fn split_image_tag_works(image: &str, expected: (&str, &str)) {
+        assert_eq!(expected, split_image_tag(image));
+    }
+}
@@ -0,0 +1,219 @@
+use super::{
+    ContainerStatus, Dependency, EnvExpansion, FinishedContainer, FinishedOutcome,
+    LaunchedContainer, PullStatus, QueuedContainer, QueuedContainerStatus, Resources,
+    RunningContainerId,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Everything known about one queued container, wherever it is in its lifecycle.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JobDetails {
+    id: String,
+    name: Option<String>,
+    labels: HashMap<String, String>,
+    original_command: String,
+    command: String,
+    args: Option<Vec<String>>,
+    detach_added: bool,
+    env_expansion: EnvExpansion,
+    status: ContainerStatus,
+    created: DateTime<Utc>,
+    started_at: Option<DateTime<Utc>>,
+    finished_at: Option<DateTime<Utc>>,
+    running_id: Option<RunningContainerId>,
+    exit_code: Option<i64>,
+    attempts: u32,
+    owner: Option<String>,
+    log_path: Option<String>,
+    dependencies: Vec<Dependency>,
+    not_before: Option<DateTime<Utc>>,
+    pull_status: Option<PullStatus>,
+    resources: Resources,
+}
+
+impl JobDetails {
+    fn new(container: &QueuedContainer, status: ContainerStatus) -> Self {
+        Self {
+            id: container.id(),
+            name: container.name().map(String::from),
+            labels: container.labels().clone(),
+            original_command: container.original_command().to_string(),
+            command: container.command().to_string(),
+            args: container.get_cmd_args().ok(),
+            detach_added: container.detach_added(),
+            env_expansion: container.env_expansion(),
+            status,
+            created: container.created(),
+            started_at: None,
+            finished_at: None,
+            running_id: None,
+            exit_code: None,
+            attempts: container.attempts(),
+            owner: container.owner().map(String::from),
+            log_path: None,
+            dependencies: container.dependencies().to_vec(),
+            not_before: container.not_before(),
+            pull_status: None,
+            resources: *container.resources(),
+        }
+    }
+
+    /// Set the path of the log file of the docker container.
+    pub fn with_log_path(mut self, log_path: Option<String>) -> Self {
+        self.log_path = log_path;
+        self
+    }
+
+    /// Set the prefetch status of the image of the queued container.
+    pub fn with_pull_status(mut self, pull_status: Option<PullStatus>) -> Self {
+        self.pull_status = pull_status;
+        self
+    }
+
+    /// Get a reference to the queued container id.
+    pub fn id(&self) -> &str {
+        self.id.as_ref()
+    }
+
+    /// Get a reference to the unique name of the job.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Get a reference to the docker labels of the job.
+    pub fn labels(&self) -> &HashMap<String, String> {
+        &self.labels
+    }
+
+    /// Get a reference to the command as given, before replacing the env vars.
+    pub fn original_command(&self) -> &str {
+        self.original_command.as_ref()
+    }
+
+    /// Get a reference to the command that is run.
+    pub fn command(&self) -> &str {
+        self.command.as_ref()
+    }
+
+    /// Get the arguments passed to docker, `None` if the command can't be parsed.
+    pub fn args(&self) -> Option<&[String]> {
+        self.args.as_deref()
+    }
+
+    /// Whether "--detach" was added to the command because it had no detach flag.
+    pub fn detach_added(&self) -> bool {
+        self.detach_added
+    }
+
+    /// Get where the env vars of the command are replaced.
+    pub fn env_expansion(&self) -> EnvExpansion {
+        self.env_expansion
+    }
+
+    /// Get a reference to the job status.
+    pub fn status(&self) -> &ContainerStatus {
+        &self.status
+    }
+
+    /// Get the time the container was queued.
+    pub fn created(&self) -> DateTime<Utc> {
+        self.created
+    }
+
+    /// Get the time the container was launched.
+    pub fn started_at(&self) -> Option<DateTime<Utc>> {
+        self.started_at
+    }
+
+    /// Get the time the container finished or left the queue.
+    pub fn finished_at(&self) -> Option<DateTime<Utc>> {
+        self.finished_at
+    }
+
+    /// Get a reference to the docker container id.
+    pub fn running_id(&self) -> Option<&RunningContainerId> {
+        self.running_id.as_ref()
+    }
+
+    /// Get the exit code of the container, if it ran until the end.
+    pub fn exit_code(&self) -> Option<i64> {
+        self.exit_code
+    }
+
+    /// Get the number of times the container was launched.
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Get a reference to the user that queued the container.
+    pub fn owner(&self) -> Option<&str> {
+        self.owner.as_deref()
+    }
+
+    /// Get a reference to the path of the log file of the docker container.
+    pub fn log_path(&self) -> Option<&str> {
+        self.log_path.as_deref()
+    }
+
+    /// Get a reference to the dependencies of the container.
+    pub fn dependencies(&self) -> &[Dependency] {
+        &self.dependencies
+    }
+
+    /// Get the time before which the container won't be started.
+    pub fn not_before(&self) -> Option<DateTime<Utc>> {
+        self.not_before
+    }
+
+    /// Get a reference to the resources the container needs to start.
+    pub fn resources(&self) -> &Resources {
+        &self.resources
+    }
+
+    /// Get a reference to the prefetch status of the image, only set while queued.
+    pub fn pull_status(&self) -> Option<&PullStatus> {
+        self.pull_status.as_ref()
+    }
+}
+
+impl From<&QueuedContainer> for JobDetails {
+    fn from(container: &QueuedContainer) -> Self {
+        let status = match container.status() {
+            QueuedContainerStatus::Queued => ContainerStatus::Queued,
+            QueuedContainerStatus::Paused => ContainerStatus::Paused,
+            QueuedContainerStatus::Launching => ContainerStatus::Launching,
+        };
+        Self::new(container, status)
+    }
+}
+
+impl From<&LaunchedContainer> for JobDetails {
+    fn from(container: &LaunchedContainer) -> Self {
+        let mut details = Self::new(
+            container.container(),
+            ContainerStatus::Running(container.id().clone()),
+        );
+        details.started_at = Some(container.started_at());
+        details.running_id = Some(container.id().clone());
+        details
+    }
+}
+
+impl From<&FinishedContainer> for JobDetails {
+    fn from(container: &FinishedContainer) -> Self {
+        let mut details = Self::new(
+            container.container(),
+            ContainerStatus::Finished(container.outcome().clone()),
+        );
+        details.started_at = container.started_at();
+        details.finished_at = Some(container.finished_at());
+        details.running_id = container.running_id().cloned();
+        details.exit_code = match container.outcome() {
+            FinishedOutcome::Exited(exit_code) => Some(*exit_code),
+            _ => None,
+        };
+        details
+    }
+}
@@ -0,0 +1,520 @@
+use crate::error_chain_fmt;
+use anyhow::Context;
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    path::{Component, Path, PathBuf},
+};
+use tokio::fs;
+
+#[derive(thiserror::Error)]
+pub enum JobSpecError {
+    #[error("Invalid job spec: {0}")]
+    InvalidSpec(String),
+    #[error("Service {0:?} not found, the services are: {}", .1.join(", "))]
+    ServiceNotFound(String, Vec<String>),
+    #[error("Choose a service of the compose file: {}", .0.join(", "))]
+    ServiceRequired(Vec<String>),
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+impl std::fmt::Debug for JobSpecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        error_chain_fmt(self, f)
+    }
+}
+
+/// A container described by its options instead of a docker run command, the keys are
+/// the ones of a Docker Compose service, plus "gpus" and "rm". The compose keys without
+/// docker run flag, as "restart" or "depends_on", are ignored.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct JobSpec {
+    image: String,
+    command: Option<StringOrList>,
+    entrypoint: Option<StringOrList>,
+    #[serde(default)]
+    environment: KeyValues,
+    env_file: Option<StringOrList>,
+    #[serde(default)]
+    volumes: Vec<String>,
+    #[serde(default)]
+    ports: Vec<String>,
+    #[serde(default)]
+    labels: KeyValues,
+    gpus: Option<Scalar>,
+    container_name: Option<String>,
+    working_dir: Option<String>,
+    user: Option<String>,
+    network_mode: Option<String>,
+    ipc: Option<String>,
+    shm_size: Option<Scalar>,
+    runtime: Option<String>,
+    #[serde(default)]
+    privileged: bool,
+    #[serde(default)]
+    init: bool,
+    #[serde(default)]
+    rm: bool,
+    deploy: Option<Deploy>,
+    #[serde(flatten)]
+    ignored: BTreeMap<String, serde_yaml::Value>,
+}
+
+/// The "deploy" key of a compose service, only its device reservations are used.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+struct Deploy {
+    #[serde(default)]
+    resources: DeployResources,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+struct DeployResources {
+    #[serde(default)]
+    reservations: Reservations,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+struct Reservations {
+    #[serde(default)]
+    devices: Vec<DeviceReservation>,
+}
+
+/// A device reservation, a GPU one if its capabilities have "gpu".
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+struct DeviceReservation {
+    count: Option<Scalar>,
+    #[serde(default)]
+    device_ids: Vec<String>,
+    #[serde(default)]
+    capabilities: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+enum StringOrList {
+    String(String),
+    List(Vec<String>),
+}
+
+/// Env vars or labels, as a map or as a list of "KEY=value".
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+enum KeyValues {
+    Map(BTreeMap<String, Option<Scalar>>),
+    List(Vec<String>),
+}
+
+impl Default for KeyValues {
+    fn default() -> Self {
+        Self::Map(BTreeMap::new())
+    }
+}
+
+impl KeyValues {
+    /// Get the values as "KEY=value", or "KEY" alone without value.
+    fn to_vec(&self) -> Vec<String> {
+        match self {
+            KeyValues::Map(map) => map
+                .iter()
+                .map(|(key, value)| match value {
+                    Some(value) => format!("{}={}", key, value),
+                    None => key.clone(),
+                })
+                .collect(),
+            KeyValues::List(list) => list.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+enum Scalar {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+impl Display for Scalar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Scalar::String(s) => write!(f, "{}", s),
+            Scalar::Integer(n) => write!(f, "{}", n),
+            Scalar::Float(n) => write!(f, "{}", n),
+            Scalar::Bool(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+impl JobSpec {
+    /// Get the keys of the spec that don't translate to docker run flags.
+    pub fn ignored_keys(&self) -> Vec<&str> {
+        self.ignored.keys().map(String::as_str).collect()
+    }
+
+    /// Get the value of "--gpus" from the "gpus" key, or else from the GPU reservation
+    /// of "deploy", all the GPUs when it gives neither count nor device ids.
+    fn gpus(&self) -> Option<String> {
+        if let Some(gpus) = &self.gpus {
+            return Some(gpus.to_string());
+        }
+        let device = self
+            .deploy
+            .as_ref()?
+            .resources
+            .reservations
+            .devices
+            .iter()
+            .find(|device| device.capabilities.iter().any(|c| c == "gpu"))?;
+        if device.device_ids.is_empty() {
+            let count = device.count.as_ref();
+            Some(count.map_or_else(|| "all".to_string(), Scalar::to_string))
+        } else {
+            Some(format!("\"device={}\"", device.device_ids.join(",")))
+        }
+    }
+
+    /// Get the docker run command of the job, relative paths of the env files and bind
+    /// mounts are relative to `dir`. The env vars in the values are kept to be replaced
+    /// as in any other command.
+    pub fn to_command(&self, dir: &Path) -> String {
+        let mut args = vec![
+            "docker".to_string(),
+            "run".to_string(),
+            "--detach".to_string(),
+        ];
+        let mut add_flag = |flag: &str, value: &str| {
+            args.push(flag.to_string());
+            args.push(quote_arg(value));
+        };
+
+        if let Some(name) = &self.container_name {
+            add_flag("--name", name);
+        }
+        for var in self.environment.to_vec() {
+            add_flag("--env", &var);
+        }
+        let env_files = match &self.env_file {
+            Some(StringOrList::String(path)) => vec![path.clone()],
+            Some(StringOrList::List(paths)) => paths.clone(),
+            None => Vec::new(),
+        };
+        for path in env_files {
+            add_flag("--env-file", &dir.join(path).to_string_lossy());
+        }
+        for volume in &self.volumes {
+            add_flag("--volume", &resolve_volume(volume, dir));
+        }
+        for port in &self.ports {
+            add_flag("--publish", port);
+        }
+        for label in self.labels.to_vec() {
+            add_flag("--label", &label);
+        }
+        let options = [
+            ("--gpus", self.gpus()),
+            ("--workdir", self.working_dir.clone()),
+            ("--user", self.user.clone()),
+            ("--network", self.network_mode.clone()),
+            ("--ipc", self.ipc.clone()),
+            ("--shm-size", self.shm_size.as_ref().map(Scalar::to_string)),
+            ("--runtime", self.runtime.clone()),
+        ];
+        for (flag, value) in options {
+            if let Some(value) = value {
+                add_flag(flag, &value);
+            }
+        }
+
+        // The entrypoint of docker run is a single executable, its args go first
+        let mut entrypoint = match &self.entrypoint {
+            Some(StringOrList::String(entrypoint)) => {
+                shellwords::split(entrypoint).unwrap_or_else(|_| vec![entrypoint.clone()])
+            }
+            Some(StringOrList::List(entrypoint)) => entrypoint.clone(),
+            None => Vec::new(),
+        };
+        if !entrypoint.is_empty() {
+            add_flag("--entrypoint", &entrypoint.remove(0));
+        }
+        let flags = [
+            ("--privileged", self.privileged),
+            ("--init", self.init),
+            ("--rm", self.rm),
+        ];
+        for (flag, is_set) in flags {
+            if is_set {
+                args.push(flag.to_string());
+            }
+        }
+
+        args.push(quote_arg(&self.image));
+        args.extend(entrypoint.iter().map(|arg| quote_arg(arg)));
+        match &self.command {
+            // Split by the shell, as in a docker run command
+            Some(StringOrList::String(command)) => args.push(command.clone()),
+            Some(StringOrList::List(command)) => {
+                args.extend(command.iter().map(|arg| quote_arg(arg)))
+            }
+            None => {}
+        }
+        args.join(" ")
+    }
+}
+
+/// Resolves the source of a bind mount relative to the spec, as "./data:/data" or
+/// "..:/code", against `dir`. Other volumes are kept.
+fn resolve_volume(volume: &str, dir: &Path) -> String {
+    let (source, target) = match volume.split_once(':') {
+        Some(split) => split,
+        None => return volume.to_string(),
+    };
+    let is_relative =
+        source == "." || source == ".." || source.starts_with("./") || source.starts_with("../");
+    if !is_relative {
+        return volume.to_string();
+    }
+    let mut path = dir.to_path_buf();
+    for component in Path::new(source).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                path.pop();
+            }
+            component => path.push(component),
+        }
+    }
+    format!("{}:{}", path.to_string_lossy(), target)
+}
+
+/// Quotes `arg` so it stays a single argument, between double quotes if possible to
+/// keep replacing its env vars.
+fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./:=@%+,$".contains(c))
+    {
+        arg.to_string()
+    } else if !arg.contains(['"', '\\', '`']) {
+        format!("\"{}\"", arg)
+    } else {
+        format!("'{}'", arg.replace('\'', "'\"'\"'"))
+    }
+}
+
+/// Parses a job spec, or a service of a Docker Compose file if it has "services".
+/// * `is_toml` - The spec is in toml, yaml otherwise
+/// * `service` - The service to use, only needed if the compose file has more than one
+pub fn parse_job_spec(
+    text: &str,
+    is_toml: bool,
+    service: Option<&str>,
+) -> Result<JobSpec, JobSpecError> {
+    let invalid = |error: &dyn Display| JobSpecError::InvalidSpec(error.to_string());
+    if is_toml {
+        let mut value: toml::Value = toml::from_str(text).map_err(|e| invalid(&e))?;
+        let value = match value
+            .as_table_mut()
+            .and_then(|table| table.remove("services"))
+        {
+            Some(services) => {
+                select_service(services.try_into().map_err(|e| invalid(&e))?, service)?
+            }
+            None => select_service(BTreeMap::from([(String::new(), value)]), service)?,
+        };
+        value.try_into().map_err(|e| invalid(&e))
+    } else {
+        let value: serde_yaml::Value = serde_yaml::from_str(text).map_err(|e| invalid(&e))?;
+        let value = match value.get("services") {
+            Some(services) => select_service(
+                serde_yaml::from_value(services.clone()).map_err(|e| invalid(&e))?,
+                service,
+            )?,
+            None => select_service(BTreeMap::from([(String::new(), value)]), service)?,
+        };
+        serde_yaml::from_value(value).map_err(|e| invalid(&e))
+    }
+}
+
+/// Get `service`, or the only service if it is `None`. A job spec is a single service
+/// without name.
+fn select_service<T>(
+    mut services: BTreeMap<String, T>,
+    service: Option<&str>,
+) -> Result<T, JobSpecError> {
+    let names = services
+        .keys()
+        .filter(|name| !name.is_empty())
+        .cloned()
+        .collect();
+    match service {
+        Some(service) => match services.remove(service) {
+            Some(value) => Ok(value),
+            None => Err(JobSpecError::ServiceNotFound(service.to_string(), names)),
+        },
+        None if services.len() == 1 => Ok(services.into_values().next().unwrap()),
+        None => Err(JobSpecError::ServiceRequired(names)),
+    }
+}
+
+/// Reads the job spec at `path`, a toml file if its extension is ".toml" and yaml
+/// otherwise, and get its docker run command with the keys it ignores.
+pub async fn read_job_spec(
+    path: impl AsRef<Path>,
+    service: Option<&str>,
+) -> Result<(String, Vec<String>), JobSpecError> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path)
+        .await
+        .with_context(|| format!("Failed to read job spec {:?}.", path))?;
+    let is_toml = path.extension().and_then(|extension| extension.to_str()) == Some("toml");
+    let spec = parse_job_spec(&text, is_toml, service)?;
+    let dir = fs::canonicalize(path)
+        .await
+        .context("Failed to get the job spec directory.")?
+        .parent()
+        .map(PathBuf::from)
+        .unwrap_or_default();
+    let ignored_keys = spec.ignored_keys().into_iter().map(String::from).collect();
+    Ok((spec.to_command(&dir), ignored_keys))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::QueuedContainer;
+    use claim::assert_err;
+
+    #[test]
+    fn yaml_job_spec_becomes_a_docker_run_command() {
+        let spec = r#"
+image: pytorch/pytorch:latest
+command: ["python", "train.py", "--name", "run 1"]
+environment:
+  LR: 0.1
+  DATA: $SPEC_DATA_DIR/data
+volumes: ["./data:/data", "cache:/cache"]
+gpus: all
+shm_size: 8g
+rm: true
+"#;
+        let spec = parse_job_spec(spec, false, None).unwrap();
+        let command = spec.to_command(Path::new("/jobs"));
+        assert_eq!(
+            command,
+            "docker run --detach --env DATA=$SPEC_DATA_DIR/data --env LR=0.1 \
+             --volume /jobs/data:/data --volume cache:/cache --gpus all --shm-size 8g \
+             --rm pytorch/pytorch:latest python train.py --name \"run 1\""
+        );
+
+        std::env::set_var("SPEC_DATA_DIR", "/home/user");
+        let container = QueuedContainer::new(command).unwrap();
+        let args = container.get_cmd_args().unwrap();
+        assert!(args.contains(&"DATA=/home/user/data".to_string()));
+        assert_eq!(args.last().unwrap(), "run 1");
+        assert_eq!(
+            Some("pytorch/pytorch:latest".to_string()),
+            container.image()
+        );
+    }
+
+    #[test]
+    fn toml_job_spec_is_parsed() {
+        let spec = r#"
+image = "alpine"
+entrypoint = "sh -c"
+command = "'echo hello'"
+labels = ["team=vision"]
+restart = "always"
+"#;
+        let spec = parse_job_spec(spec, true, None).unwrap();
+        assert_eq!(
+            spec.to_command(Path::new("/jobs")),
+            "docker run --detach --label team=vision --entrypoint sh alpine -c 'echo hello'"
+        );
+    }
+
+    #[test]
+    fn compose_services_are_selected_by_name() {
+        let compose = r#"
+version: "3.8"
+services:
+  train:
+    image: trainer
+  eval:
+    image: evaluator
+    command: python eval.py
+"#;
+        let spec = parse_job_spec(compose, false, Some("eval")).unwrap();
+        assert_eq!(
+            spec.to_command(Path::new("/")),
+            "docker run --detach evaluator python eval.py"
+        );
+        assert_err!(parse_job_spec(compose, false, None));
+        assert_err!(parse_job_spec(compose, false, Some("test")));
+    }
+
+    #[test]
+    fn compose_only_keys_are_ignored() {
+        let spec = r#"
+image: alpine
+restart: always
+depends_on: [db]
+build: .
+"#;
+        let spec = parse_job_spec(spec, false, None).unwrap();
+        assert_eq!(vec!["build", "depends_on", "restart"], spec.ignored_keys());
+        assert_eq!(
+            spec.to_command(Path::new("/")),
+            "docker run --detach alpine"
+        );
+        assert_err!(parse_job_spec("command: ls\n", false, None));
+    }
+
+    #[test]
+    fn deploy_gpu_reservations_become_gpus() {
+        let spec = |device: &str| {
+            let spec = format!(
+                "image: trainer\ndeploy:\n  resources:\n    reservations:\n      devices:\n        - {}\n",
+                device
+            );
+            parse_job_spec(&spec, false, None)
+                .unwrap()
+                .to_command(Path::new("/"))
+        };
+        assert_eq!(
+            spec("{driver: nvidia, count: 2, capabilities: [gpu]}"),
+            "docker run --detach --gpus 2 trainer"
+        );
+        assert_eq!(
+            spec("{capabilities: [gpu]}"),
+            "docker run --detach --gpus all trainer"
+        );
+        assert_eq!(
+            spec("{device_ids: [\"0\", \"3\"], capabilities: [gpu]}"),
+            "docker run --detach --gpus '\"device=0,3\"' trainer"
+        );
+        assert_eq!(
+            spec("{count: 1, capabilities: [tpu]}"),
+            "docker run --detach trainer"
+        );
+    }
+
+    #[test]
+    fn relative_volumes_are_resolved_against_the_spec_directory() {
+        let spec = r#"
+image: alpine
+volumes: [".:/code", "..:/parent", "./data:/data:ro", "../cache:/cache", "named:/named"]
+"#;
+        let spec = parse_job_spec(spec, false, None).unwrap();
+        assert_eq!(
+            spec.to_command(Path::new("/jobs/train")),
+            "docker run --detach --volume /jobs/train:/code --volume /jobs:/parent \
+             --volume /jobs/train/data:/data:ro --volume /jobs/cache:/cache \
+             --volume named:/named alpine"
+        );
+    }
+}
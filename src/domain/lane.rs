@@ -0,0 +1,112 @@
+use crate::error_chain_fmt;
+use serde::{Deserialize, Serialize};
+
+/// Queue of the containers queued without one.
+pub const DEFAULT_QUEUE: &str = "default";
+
+#[derive(thiserror::Error)]
+pub enum LaneError {
+    #[error("Invalid queue, expected \"name\" or \"name=concurrency\" with letters, digits, \"_\", \".\" and \"-\": {0:?}")]
+    InvalidLane(String),
+    #[error("The queue {0:?} is given more than once.")]
+    DuplicateLane(String),
+}
+
+impl std::fmt::Debug for LaneError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        error_chain_fmt(self, f)
+    }
+}
+
+/// A named queue of the server, its containers run in their own FIFO next to the
+/// containers of the other queues.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Lane {
+    name: String,
+    concurrency: Option<usize>,
+}
+
+impl Lane {
+    /// * `concurrency` - Maximum number of running containers of the queue, `None` to
+    ///   run them one at a time unless their resource requests fit in the host capacity
+    pub fn new(name: impl Into<String>, concurrency: Option<usize>) -> Self {
+        Self {
+            name: name.into(),
+            concurrency,
+        }
+    }
+
+    /// Get a reference to the name of the queue.
+    pub fn name(&self) -> &str {
+        self.name.as_ref()
+    }
+
+    /// Get the maximum number of running containers of the queue.
+    pub fn concurrency(&self) -> Option<usize> {
+        self.concurrency
+    }
+}
+
+/// Whether `name` can name a queue, it is used in docker labels.
+pub fn is_valid_queue_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_.-".contains(c))
+}
+
+/// Parses a queue given as "name" or "name=concurrency".
+pub fn parse_lane(s: &str) -> Result<Lane, LaneError> {
+    let invalid = || LaneError::InvalidLane(s.to_string());
+    let (name, concurrency) = match s.split_once('=') {
+        Some((name, concurrency)) => {
+            let concurrency = concurrency
+                .parse::<usize>()
+                .ok()
+                .filter(|&concurrency| concurrency > 0)
+                .ok_or_else(invalid)?;
+            (name, Some(concurrency))
+        }
+        None => (s, None),
+    };
+    if !is_valid_queue_name(name) {
+        return Err(invalid());
+    }
+    Ok(Lane::new(name, concurrency))
+}
+
+/// Fails if two of the `lanes` have the same name.
+pub fn check_lanes(lanes: &[Lane]) -> Result<(), LaneError> {
+    let duplicate = lanes.iter().enumerate().find(|(index, lane)| {
+        lanes[..*index]
+            .iter()
+            .any(|previous| previous.name() == lane.name())
+    });
+    match duplicate {
+        Some((_, lane)) => Err(LaneError::DuplicateLane(lane.name().to_string())),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claim::assert_err;
+
+    #[test]
+    fn parse_lane_works() {
+        assert_eq!(Lane::new("gpu0", None), parse_lane("gpu0").unwrap());
+        assert_eq!(Lane::new("cpu", Some(4)), parse_lane("cpu=4").unwrap());
+        assert_err!(parse_lane("cpu=0"));
+        assert_err!(parse_lane("=2"));
+        assert_err!(parse_lane("gpu 0"));
+    }
+
+    #[test]
+    fn check_lanes_rejects_duplicate_names() {
+        let gpu0 = Lane::new("gpu0", None);
+        let cpu = Lane::new("cpu", Some(4));
+        assert!(check_lanes(&[gpu0.clone(), cpu.clone()]).is_ok());
+        assert_err!(check_lanes(&[gpu0, cpu, Lane::new("gpu0", Some(2))]));
+    }
+}
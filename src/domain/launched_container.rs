@@ -0,0 +1,53 @@
+use super::{FinishedContainer, FinishedOutcome, QueuedContainer, RunningContainerId};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A queued container that was started with `docker run`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchedContainer {
+    id: RunningContainerId,
+    container: QueuedContainer,
+    started_at: DateTime<Utc>,
+}
+
+impl LaunchedContainer {
+    pub fn new(id: RunningContainerId, container: QueuedContainer) -> Self {
+        Self {
+            id,
+            container,
+            started_at: Utc::now(),
+        }
+    }
+
+    /// Set the time the container was launched, of a container launched before a restart
+    /// of the server.
+    pub fn with_started_at(mut self, started_at: DateTime<Utc>) -> Self {
+        self.started_at = started_at;
+        self
+    }
+
+    /// Get a reference to the docker id of the launched container.
+    pub fn id(&self) -> &RunningContainerId {
+        &self.id
+    }
+
+    /// Get a reference to the queued container that was launched.
+    pub fn container(&self) -> &QueuedContainer {
+        &self.container
+    }
+
+    /// Get the time the container was launched.
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.started_at
+    }
+
+    /// Consumes the launched container, recording the exit code of its execution.
+    pub fn finish(self, exit_code: i64) -> FinishedContainer {
+        FinishedContainer::new(
+            self.container,
+            Some(self.id),
+            FinishedOutcome::Exited(exit_code),
+        )
+        .with_started_at(self.started_at)
+    }
+}
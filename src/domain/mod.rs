@@ -1,9 +1,53 @@
+mod batch;
 mod container;
+mod container_status;
+mod cron_container;
+mod dependency;
+mod env_vars;
+mod finished_container;
+mod image_pull;
+mod job_details;
+mod job_spec;
+mod lane;
+mod launched_container;
+mod preflight;
+mod queue_info;
 mod queued_container;
+mod resources;
+mod resubmit_options;
+mod run_spec;
 mod running_container;
 mod running_container_id;
+mod schedule;
+mod scheduler;
+mod selector;
+mod server_status;
+mod shutdown_policy;
+mod sweep;
 
+pub use batch::*;
 pub use container::*;
+pub use container_status::*;
+pub use cron_container::*;
+pub use dependency::*;
+pub use env_vars::*;
+pub use finished_container::*;
+pub use image_pull::*;
+pub use job_details::*;
+pub use job_spec::*;
+pub use lane::*;
+pub use launched_container::*;
+pub use preflight::*;
+pub use queue_info::*;
 pub use queued_container::*;
+pub use resources::*;
+pub use resubmit_options::*;
+pub use run_spec::*;
 pub use running_container::*;
 pub use running_container_id::*;
+pub use schedule::*;
+pub use scheduler::*;
+pub use selector::*;
+pub use server_status::*;
+pub use shutdown_policy::*;
+pub use sweep::*;
@@ -0,0 +1,145 @@
+use super::RunSpec;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Severity {
+    /// The container might fail or not run as expected.
+    Warning,
+    /// The container will fail when launched.
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Severity::Warning => "Warning",
+            Severity::Error => "Error",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// A problem found checking a container before queueing it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PreflightIssue {
+    severity: Severity,
+    message: String,
+}
+
+impl PreflightIssue {
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+        }
+    }
+
+    /// Get the severity of the issue.
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// Get a reference to the description of the issue.
+    pub fn message(&self) -> &str {
+        self.message.as_ref()
+    }
+}
+
+impl std::fmt::Display for PreflightIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.severity, self.message)
+    }
+}
+
+/// A host path mounted in the container.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BindMount {
+    /// Path in the host.
+    pub source: PathBuf,
+    /// Docker creates the source directory if it is missing, as with "--volume".
+    pub is_created: bool,
+}
+
+/// Get the bind mounts of "--volume" and "--mount type=bind" flags, named volumes and
+/// relative paths are skipped.
+pub fn bind_mounts(spec: &RunSpec) -> Vec<BindMount> {
+    spec.flags()
+        .iter()
+        .filter_map(|flag| {
+            let value = flag.value()?;
+            match flag.name() {
+                "volume" => {
+                    let source = value.split(':').next()?;
+                    source.starts_with('/').then(|| BindMount {
+                        source: source.into(),
+                        is_created: true,
+                    })
+                }
+                "mount" => {
+                    let options = value
+                        .split(',')
+                        .filter_map(|option| option.split_once('='))
+                        .collect::<Vec<_>>();
+                    let is_bind = options.contains(&("type", "bind"));
+                    let source = options
+                        .iter()
+                        .find(|(key, _)| matches!(*key, "source" | "src"))
+                        .map(|(_, source)| *source)?;
+                    (is_bind && source.starts_with('/')).then(|| BindMount {
+                        source: source.into(),
+                        is_created: false,
+                    })
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Get the container name given with "--name".
+pub fn container_name(spec: &RunSpec) -> Option<&str> {
+    spec.flags()
+        .iter()
+        .rev()
+        .find(|flag| flag.name() == "name")
+        .and_then(|flag| flag.value())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bind_mounts_are_found() {
+        let command = "docker run -d -v /data:/data -v cache:/cache --volume=/a:/b:ro \
+            --mount type=bind,source=/src,target=/dst --mount type=volume,src=/v,dst=/w \
+            --name first --name job img -v /not:/flag";
+        let spec = RunSpec::parse(command).unwrap();
+        let mounts = bind_mounts(&spec)
+            .into_iter()
+            .map(|mount| {
+                (
+                    mount.source.to_string_lossy().into_owned(),
+                    mount.is_created,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            mounts,
+            vec![
+                ("/data".to_string(), true),
+                ("/a".to_string(), true),
+                ("/src".to_string(), false),
+            ]
+        );
+        assert_eq!(Some("job"), container_name(&spec));
+    }
+}
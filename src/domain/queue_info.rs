@@ -0,0 +1,56 @@
+use super::{DependencyState, PullStatus};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Where a queued container stands in the queue.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QueueInfo {
+    dependency_state: DependencyState,
+    position: Option<usize>,
+    estimated_start: Option<DateTime<Utc>>,
+    pull_status: Option<PullStatus>,
+}
+
+impl QueueInfo {
+    pub fn new(
+        dependency_state: DependencyState,
+        position: Option<usize>,
+        estimated_start: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            dependency_state,
+            position,
+            estimated_start,
+            pull_status: None,
+        }
+    }
+
+    /// Set the prefetch status of the image of the queued container.
+    pub fn with_pull_status(mut self, pull_status: Option<PullStatus>) -> Self {
+        self.pull_status = pull_status;
+        self
+    }
+
+    /// Get a reference to the combined state of the queued container's dependencies.
+    pub fn dependency_state(&self) -> &DependencyState {
+        &self.dependency_state
+    }
+
+    /// Get the position in the queue, starting at 1, `None` if the container is paused
+    /// or one of its dependencies failed.
+    pub fn position(&self) -> Option<usize> {
+        self.position
+    }
+
+    /// Get the estimated start time, `None` if there is no estimate for a container
+    /// ahead in the queue.
+    pub fn estimated_start(&self) -> Option<DateTime<Utc>> {
+        self.estimated_start
+    }
+
+    /// Get a reference to the prefetch status of the image, `None` if the prefetcher
+    /// didn't look at it yet.
+    pub fn pull_status(&self) -> Option<&PullStatus> {
+        self.pull_status.as_ref()
+    }
+}
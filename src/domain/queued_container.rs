@@ -1,9 +1,17 @@
+use super::{
+    expand_env_vars, is_valid_queue_name, join_lines, read_env_file, Dependency, Resources,
+    RunSpec, RunSpecError, DEFAULT_QUEUE,
+};
 use crate::error_chain_fmt;
 use anyhow::{Context, Result};
-use once_cell::sync::OnceCell;
-use regex::{Captures, Regex};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::{env, path::Path};
+use std::{
+    collections::HashMap,
+    env,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 use tokio::{fs::File, io::AsyncReadExt};
 use uuid::Uuid;
 
@@ -11,8 +19,12 @@ use uuid::Uuid;
 pub enum QueuedContainerError {
     #[error("Invalid docker run command: {0}")]
     InvalidQueuedCommand(String),
-    #[error("Env vars not found: {0}")]
-    EnvVarsNotFound(String),
+    #[error("Env vars not found: {}", .0.join(", "))]
+    EnvVarsNotFound(Vec<String>),
+    #[error("Invalid name, use letters, digits, \"_\", \".\" and \"-\": {0:?}")]
+    InvalidName(String),
+    #[error("Invalid queue, use letters, digits, \"_\", \".\" and \"-\": {0:?}")]
+    InvalidQueue(String),
     #[error(transparent)]
     UnexpectedError(#[from] anyhow::Error),
 }
@@ -28,12 +40,41 @@ pub struct QueuedContainer {
     id: Uuid,
     command: String,
     status: QueuedContainerStatus,
+    dependencies: Vec<Dependency>,
+    not_before: Option<DateTime<Utc>>,
+    created: DateTime<Utc>,
+    estimate: Option<Duration>,
+    original_command: String,
+    owner: Option<String>,
+    attempts: u32,
+    sweep_id: Option<Uuid>,
+    name: Option<String>,
+    labels: HashMap<String, String>,
+    env_expansion: EnvExpansion,
+    env_files: Vec<PathBuf>,
+    detach_added: bool,
+    resources: Resources,
+    /// Host GPUs given to the container when it launched.
+    gpu_devices: Vec<u32>,
+    queue_name: String,
+}
+
+/// Where the env vars of the command are replaced.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum EnvExpansion {
+    /// When queued, from the environment of the client.
+    Client,
+    /// When launched, from the env files of the container and the environment of the
+    /// server, so their values aren't stored in the queue.
+    Server,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum QueuedContainerStatus {
     Queued,
     Paused,
+    /// The launcher is running "docker run", it can't be changed anymore.
+    Launching,
 }
 
 impl std::fmt::Display for QueuedContainerStatus {
@@ -41,86 +82,194 @@ impl std::fmt::Display for QueuedContainerStatus {
         let s = match self {
             QueuedContainerStatus::Queued => "Queued",
             QueuedContainerStatus::Paused => "Paused",
+            QueuedContainerStatus::Launching => "Launching",
         };
         write!(f, "{}", s)
     }
 }
 
 impl QueuedContainer {
-    /// * `command` - A docker run command, should include a detach flag as "-d" or "--detach"
+    /// * `command` - A docker run command, "--detach" is added if it has no detach flag
     pub fn new(command: impl Into<String>) -> Result<Self, QueuedContainerError> {
-        let id = Uuid::new_v4();
-        let command: String = command.into();
-        if !command.starts_with("docker run") {
-            return Err(QueuedContainerError::InvalidQueuedCommand(format!(
-                "Should start with \"docker run\": {:?}",
-                command
-            )));
-        }
+        Self::with_env_expansion(command, EnvExpansion::Client)
+    }
 
-        // Check if detach is present
-        let command = command.replace("\n", " ").replace("\\", "");
-        let detach_flags = command
-            .split_whitespace()
-            .skip(2)
-            .take_while(|x| x.starts_with('-'))
-            .filter(|&x| (x == "-d") | (x == "--detach") | (x == "--detach=true"))
-            .count();
-        if detach_flags != 1 {
-            return Err(QueuedContainerError::InvalidQueuedCommand(format!(
-                "Include a detach flag such as: \"-d\" or \"--detach\": {:?}",
-                command
-            )));
-        }
+    /// * `command` - A docker run command, "--detach" is added if it has no detach flag
+    /// * `env_expansion` - Where the env vars of the command are replaced
+    pub fn with_env_expansion(
+        command: impl Into<String>,
+        env_expansion: EnvExpansion,
+    ) -> Result<Self, QueuedContainerError> {
+        let id = Uuid::new_v4();
+        let original_command: String = command.into();
+        let spec = RunSpec::parse(&original_command).map_err(|error| {
+            QueuedContainerError::InvalidQueuedCommand(format!("{}: {:?}", error, original_command))
+        })?;
 
-        // Replace env vars
-        let mut vars_not_found = String::new();
-        static RE: OnceCell<Regex> = OnceCell::new();
-        let re = RE.get_or_init(|| Regex::new(r"\$(\w+)").unwrap());
-        let command = re
-            .replace_all(&command, |caps: &Captures| {
-                let var_name = &caps[1];
-                match env::var(var_name) {
-                    Ok(value) => value,
-                    Err(_) => {
-                        vars_not_found.push_str(var_name);
-                        "-".to_string()
-                    }
+        // The launcher reads the id of the container from the output of a detached
+        // "docker run", so it always runs detached
+        let detach_added = match spec.detach() {
+            Some(true) => false,
+            Some(false) => {
+                return Err(QueuedContainerError::InvalidQueuedCommand(format!(
+                    "The container runs detached, remove the \"--detach=false\" flag: {:?}",
+                    original_command
+                )))
+            }
+            None => {
+                let conflicting = spec.flags().iter().find(|flag| match flag.name() {
+                    "attach" => true,
+                    "interactive" | "tty" => flag.is_true(),
+                    _ => false,
+                });
+                if let Some(flag) = conflicting {
+                    return Err(QueuedContainerError::InvalidQueuedCommand(format!(
+                        "The container runs detached, remove the \"--{}\" flag at byte {}: {:?}",
+                        flag.name(),
+                        flag.position(),
+                        original_command
+                    )));
                 }
-            })
-            .to_string();
+                true
+            }
+        };
 
-        if !vars_not_found.is_empty() {
-            return Err(QueuedContainerError::EnvVarsNotFound(vars_not_found));
-        }
+        let command = join_lines(&original_command);
+        // Replace env vars
+        let command = match env_expansion {
+            EnvExpansion::Client => expand_env_vars(&command, |name| env::var(name).ok())
+                .map_err(QueuedContainerError::EnvVarsNotFound)?,
+            EnvExpansion::Server => command,
+        };
 
         Ok(Self {
             id,
             command,
             status: QueuedContainerStatus::Paused,
+            dependencies: Vec::new(),
+            not_before: None,
+            created: Utc::now(),
+            estimate: None,
+            original_command,
+            owner: env::var("USER").or_else(|_| env::var("LOGNAME")).ok(),
+            attempts: 0,
+            sweep_id: None,
+            name: None,
+            labels: HashMap::new(),
+            env_expansion,
+            env_files: Vec::new(),
+            detach_added,
+            resources: Resources::default(),
+            gpu_devices: Vec::new(),
+            queue_name: DEFAULT_QUEUE.to_string(),
         })
     }
 
+    /// Creates a paused copy of the queued container with a new id, it doesn't keep
+    /// the dependencies, the schedule, the name nor the sweep.
+    pub fn duplicate(&self) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            command: self.command.clone(),
+            status: QueuedContainerStatus::Paused,
+            dependencies: Vec::new(),
+            not_before: None,
+            created: Utc::now(),
+            estimate: self.estimate,
+            original_command: self.original_command.clone(),
+            owner: self.owner.clone(),
+            attempts: 0,
+            sweep_id: None,
+            name: None,
+            labels: self.labels.clone(),
+            env_expansion: self.env_expansion,
+            env_files: self.env_files.clone(),
+            detach_added: self.detach_added,
+            resources: self.resources,
+            gpu_devices: Vec::new(),
+            queue_name: self.queue_name.clone(),
+        }
+    }
+
     pub async fn from_path(path: impl AsRef<Path>) -> Result<Self, QueuedContainerError> {
+        Self::from_path_with_env_expansion(path, EnvExpansion::Client).await
+    }
+
+    pub async fn from_path_with_env_expansion(
+        path: impl AsRef<Path>,
+        env_expansion: EnvExpansion,
+    ) -> Result<Self, QueuedContainerError> {
         let mut f = File::open(path).await.context("Failed to open path.")?;
         let mut buffer = String::new();
         f.read_to_string(&mut buffer)
             .await
             .context("Failed to read file.")?;
-        let command = buffer
-            .lines()
-            .filter(|line| !line.trim_start().starts_with('#'))
-            .collect::<Vec<_>>()
-            .join("\n");
-        Self::new(command)
+        Self::with_env_expansion(strip_comments(&buffer), env_expansion)
     }
 
+    /// Get the arguments of the docker command, with the labels of the queued container
+    /// added as "--label" flags, its env files as "--env-file" flags, its resource
+    /// requests as limits and "--detach" if it was added.
     pub fn get_cmd_args(&self) -> Result<Vec<String>> {
-        let args = shellwords::split(self.command.split_once(' ').unwrap().1)
-            .context("Failed to split args.")?;
+        self.split_args(&self.command)
+    }
+
+    /// Get the arguments to launch the docker command, the env vars are replaced from
+    /// its env files and the environment if they are replaced by the server.
+    pub fn get_launch_args(&self) -> Result<Vec<String>, QueuedContainerError> {
+        if self.env_expansion == EnvExpansion::Client {
+            return Ok(self.get_cmd_args()?);
+        }
+        let mut vars = HashMap::new();
+        for path in &self.env_files {
+            vars.extend(read_env_file(path)?);
+        }
+        let command = expand_env_vars(&self.command, |name| {
+            vars.get(name).cloned().or_else(|| env::var(name).ok())
+        })
+        .map_err(QueuedContainerError::EnvVarsNotFound)?;
+        Ok(self.split_args(&command)?)
+    }
+
+    fn split_args(&self, command: &str) -> Result<Vec<String>> {
+        let mut args = RunSpec::parse(command)
+            .context("Failed to split args.")?
+            .to_args();
+        let mut labels = self.labels.iter().collect::<Vec<_>>();
+        labels.sort();
+        let labels = labels
+            .into_iter()
+            .flat_map(|(key, value)| ["--label".to_string(), format!("{}={}", key, value)]);
+        let env_files = self.env_files.iter().flat_map(|path| {
+            [
+                "--env-file".to_string(),
+                path.to_string_lossy().into_owned(),
+            ]
+        });
+        let detach = self.detach_added.then(|| "--detach".to_string());
+        let resources = self.resources.to_args(&self.gpu_devices);
+        args.splice(
+            1..1,
+            detach
+                .into_iter()
+                .chain(labels)
+                .chain(env_files)
+                .chain(resources),
+        );
         Ok(args)
     }
 
+    /// Get the parsed docker run command.
+    pub fn run_spec(&self) -> Result<RunSpec, RunSpecError> {
+        RunSpec::parse(&self.command)
+    }
+
+    /// Get the image of the docker run command.
+    pub fn image(&self) -> Option<String> {
+        let spec = self.run_spec().ok()?;
+        spec.image().map(String::from)
+    }
+
     /// Get a reference to the queued container's id.
     pub fn id(&self) -> String {
         self.id.to_string()
@@ -131,14 +280,107 @@ impl QueuedContainer {
         self.command.as_ref()
     }
 
+    /// Get a reference to the command as given, before replacing the env vars.
+    pub fn original_command(&self) -> &str {
+        self.original_command.as_ref()
+    }
+
+    /// Get a reference to the user that queued the container, as reported by the client,
+    /// it is not authenticated.
+    pub fn owner(&self) -> Option<&str> {
+        self.owner.as_deref()
+    }
+
+    /// Set the id, of a container rebuilt from the labels of its running container.
+    pub fn set_id(&mut self, id: Uuid) {
+        self.id = id;
+    }
+
+    /// Set the user that queued the container.
+    pub fn set_owner(&mut self, owner: String) {
+        self.owner = Some(owner);
+    }
+
+    /// Get the number of times the container was launched.
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Records a launch of the container.
+    pub fn add_attempt(&mut self) {
+        self.attempts += 1;
+    }
+
+    /// Forgets the attempts and the host GPUs, which only the server sets, of a container
+    /// received from a client.
+    pub fn clear_launch_state(&mut self) {
+        self.attempts = 0;
+        self.gpu_devices.clear();
+    }
+
+    /// Replaces the command with the one of `other`, keeping the id, status, resource
+    /// requests, dependencies and schedule. Fails if the command of `other` sets a limit
+    /// given by the resource requests.
+    pub fn replace_command(&mut self, other: &QueuedContainer) -> Result<(), QueuedContainerError> {
+        other.check_resource_flags(&self.resources)?;
+        self.command = other.command.clone();
+        self.original_command = other.original_command.clone();
+        self.env_expansion = other.env_expansion;
+        self.detach_added = other.detach_added;
+        Ok(())
+    }
+
+    /// Get a reference to the resources the container needs to start, they are also
+    /// its limits.
+    pub fn resources(&self) -> &Resources {
+        &self.resources
+    }
+
+    /// Get a reference to the host GPUs given to the container when it launched, empty
+    /// if it requests none or the GPUs of the host aren't limited.
+    pub fn gpu_devices(&self) -> &[u32] {
+        self.gpu_devices.as_ref()
+    }
+
+    /// Set the resources the container needs to start, the command can't set the same
+    /// limits.
+    pub fn set_resources(&mut self, resources: Resources) -> Result<(), QueuedContainerError> {
+        self.check_resource_flags(&resources)?;
+        self.resources = resources;
+        Ok(())
+    }
+
+    /// Fails if the command sets a limit that the `resources` requests also set.
+    fn check_resource_flags(&self, resources: &Resources) -> Result<(), QueuedContainerError> {
+        let spec = self.run_spec().context("Failed to parse the command.")?;
+        let requested = [
+            ("memory", resources.memory().is_some()),
+            ("cpus", resources.cpus().is_some()),
+            ("gpus", resources.gpus().is_some()),
+        ];
+        for (name, _) in requested.iter().filter(|(_, requested)| *requested) {
+            if let Some(flag) = spec.flags().iter().find(|flag| flag.name() == *name) {
+                return Err(QueuedContainerError::InvalidQueuedCommand(format!(
+                    "The \"--{}\" flag at byte {} is set from the resource requests, remove it: {:?}",
+                    name,
+                    flag.position(),
+                    self.command
+                )));
+            }
+        }
+        Ok(())
+    }
+
     /// Get a reference to the queued container's status.
     pub fn status(&self) -> &QueuedContainerStatus {
         &self.status
     }
 
-    /// Set the queued container's status to `QueuedContainerStatus::Queued`.
+    /// Set the queued container's status to `QueuedContainerStatus::Queued`, it gives
+    /// back the GPUs of a previous launch.
     pub fn queue(&mut self) {
         self.status = QueuedContainerStatus::Queued;
+        self.gpu_devices.clear();
     }
 
     /// Set the queued container's status to `QueuedContainerStatus::Paused`.
@@ -146,6 +388,13 @@ impl QueuedContainer {
         self.status = QueuedContainerStatus::Paused;
     }
 
+    /// Set the queued container's status to `QueuedContainerStatus::Launching`, on the
+    /// `gpu_devices` host GPUs.
+    pub fn launch(&mut self, gpu_devices: Vec<u32>) {
+        self.status = QueuedContainerStatus::Launching;
+        self.gpu_devices = gpu_devices;
+    }
+
     pub fn is_paused(&self) -> bool {
         self.status == QueuedContainerStatus::Paused
     }
@@ -153,9 +402,138 @@ impl QueuedContainer {
     pub fn is_queued(&self) -> bool {
         self.status == QueuedContainerStatus::Queued
     }
+
+    pub fn is_launching(&self) -> bool {
+        self.status == QueuedContainerStatus::Launching
+    }
+
+    /// Get a reference to the queued containers that have to finish before this one runs.
+    pub fn dependencies(&self) -> &[Dependency] {
+        self.dependencies.as_ref()
+    }
+
+    pub fn add_dependency(&mut self, dependency: Dependency) {
+        self.dependencies.push(dependency);
+    }
+
+    /// Get the time the queued container was created.
+    pub fn created(&self) -> DateTime<Utc> {
+        self.created
+    }
+
+    /// Get the time before which the queued container can't run.
+    pub fn not_before(&self) -> Option<DateTime<Utc>> {
+        self.not_before
+    }
+
+    /// Set the time before which the queued container can't run.
+    pub fn set_not_before(&mut self, not_before: DateTime<Utc>) {
+        self.not_before = Some(not_before);
+    }
+
+    /// Get the expected duration of the queued container given by the user.
+    pub fn estimate(&self) -> Option<Duration> {
+        self.estimate
+    }
+
+    /// Set the expected duration of the queued container.
+    pub fn set_estimate(&mut self, estimate: Duration) {
+        self.estimate = Some(estimate);
+    }
+
+    /// Get the id of the sweep the queued container belongs to.
+    pub fn sweep_id(&self) -> Option<String> {
+        self.sweep_id.map(|id| id.to_string())
+    }
+
+    /// Set the id of the sweep the queued container belongs to.
+    pub fn set_sweep_id(&mut self, sweep_id: Uuid) {
+        self.sweep_id = Some(sweep_id);
+    }
+
+    /// Get a reference to the unique name of the queued container.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Set the unique name of the queued container, it can be used instead of the id.
+    pub fn set_name(&mut self, name: impl Into<String>) -> Result<(), QueuedContainerError> {
+        let name = name.into();
+        let is_valid = name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_.-".contains(c))
+            && name.starts_with(|c: char| c.is_ascii_alphanumeric())
+            && Uuid::parse_str(&name).is_err();
+        if !is_valid {
+            return Err(QueuedContainerError::InvalidName(name));
+        }
+        self.name = Some(name);
+        Ok(())
+    }
+
+    /// Get a reference to the name of the queue the container runs in.
+    pub fn queue_name(&self) -> &str {
+        self.queue_name.as_ref()
+    }
+
+    /// Set the name of the queue the container runs in.
+    pub fn set_queue_name(
+        &mut self,
+        queue_name: impl Into<String>,
+    ) -> Result<(), QueuedContainerError> {
+        let queue_name = queue_name.into();
+        if !is_valid_queue_name(&queue_name) {
+            return Err(QueuedContainerError::InvalidQueue(queue_name));
+        }
+        self.queue_name = queue_name;
+        Ok(())
+    }
+
+    /// Get a reference to the docker labels of the queued container.
+    pub fn labels(&self) -> &HashMap<String, String> {
+        &self.labels
+    }
+
+    pub fn add_label(&mut self, key: String, value: String) {
+        self.labels.insert(key, value);
+    }
+
+    /// Get where the env vars of the command are replaced.
+    pub fn env_expansion(&self) -> EnvExpansion {
+        self.env_expansion
+    }
+
+    /// Get a reference to the docker env files of the queued container.
+    pub fn env_files(&self) -> &[PathBuf] {
+        &self.env_files
+    }
+
+    /// Adds a docker env file, read when the container is launched.
+    pub fn add_env_file(&mut self, path: PathBuf) {
+        self.env_files.push(path);
+    }
+
+    /// Whether "--detach" was added to the command because it had no detach flag.
+    pub fn detach_added(&self) -> bool {
+        self.detach_added
+    }
+
+    /// Whether the queued container is not scheduled after `now`.
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.not_before.is_none_or(|not_before| not_before <= now)
+    }
+}
+
+/// Removes the lines starting with "#", as in a docker run script.
+pub fn strip_comments(text: &str) -> String {
+    text.lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 #[cfg(test)]
+#[allow(clippy::needless_lifetimes)]
 mod tests {
     use std::env;
 
@@ -172,14 +550,46 @@ mod tests {
     }
 
     #[test]
-    fn reject_queued_containers_without_detach() {
+    fn reject_queued_containers_with_conflicting_detach_flags() {
         // Valid commands
         assert_ok!(QueuedContainer::new("docker run -d some_image"));
         assert_ok!(QueuedContainer::new("docker run --detach some_image"));
         assert_ok!(QueuedContainer::new("docker run --detach=true some_image"));
+        assert_ok!(QueuedContainer::new("docker run -itd some_image"));
+        assert_ok!(QueuedContainer::new("docker run -d=true some_image"));
+        assert_ok!(QueuedContainer::new("docker run --tty=false some_image"));
         // Invalid commands
-        assert_err!(QueuedContainer::new("docker run some_image"));
         assert_err!(QueuedContainer::new("docker run --detach=false some_image"));
+        assert_err!(QueuedContainer::new("docker run -it some_image"));
+        assert_err!(QueuedContainer::new("docker run -a stdout some_image"));
+    }
+
+    #[test_case("docker run some_image", true; "Missing")]
+    #[test_case("docker run -e -d some_image", true; "Value of another flag")]
+    #[test_case("docker run -d some_image", false; "Present")]
+    fn detach_is_added_if_missing(command: &str, added: bool) {
+        let container = QueuedContainer::new(command).unwrap();
+        assert_eq!(added, container.detach_added());
+        let args = container.get_cmd_args().unwrap();
+        assert_eq!(added, args[1] == "--detach");
+        assert_eq!(Some("some_image".to_string()), container.image());
+    }
+
+    #[test]
+    fn resource_requests_are_passed_as_limits() {
+        let mut container = QueuedContainer::new("docker run -d some_image").unwrap();
+        container
+            .set_resources(Resources::new(Some(1024), Some(2.0), None))
+            .unwrap();
+        let args = container.get_cmd_args().unwrap();
+        assert_eq!(
+            vec!["run", "--memory", "1024", "--cpus", "2", "-d", "some_image"],
+            args
+        );
+
+        let mut container = QueuedContainer::new("docker run -d -m 1g some_image").unwrap();
+        assert_err!(container.set_resources(Resources::new(Some(1024), None, None)));
+        assert_ok!(container.set_resources(Resources::new(None, Some(2.0), None)));
     }
 
     #[test_case("tests/examples/one_line.sh"; "One line")]
@@ -213,6 +623,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn create_queued_container_expands_env_vars_as_the_shell() {
+        env::set_var("SOME_SPACED_VAR", "some value");
+        let command = "docker run -d -e A=${SOME_SPACED_VAR} -e B=\\$HOME alpine echo '$HOME'";
+        let container = QueuedContainer::new(command).unwrap();
+        let args = container.get_cmd_args().unwrap();
+        assert_eq!(
+            args,
+            vec![
+                "run",
+                "-d",
+                "-e",
+                "A=some value",
+                "-e",
+                "B=$HOME",
+                "alpine",
+                "echo",
+                "$HOME"
+            ]
+        );
+    }
+
+    #[test]
+    fn create_queued_container_lists_missing_env_vars() {
+        let command = "docker run -d -e A=$MISSING_VAR_A -e B=${MISSING_VAR_B} alpine";
+        let error = QueuedContainer::new(command).unwrap_err();
+        assert_eq!(
+            "Env vars not found: MISSING_VAR_A, MISSING_VAR_B",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn server_env_expansion_happens_at_launch() {
+        let path = env::temp_dir().join(format!("docker_queue_{}.env", Uuid::new_v4()));
+        std::fs::write(&path, "# Secrets\nSECRET_TOKEN=abc\n").unwrap();
+        let command = "docker run -d -e TOKEN=$SECRET_TOKEN alpine";
+        let mut container =
+            QueuedContainer::with_env_expansion(command, EnvExpansion::Server).unwrap();
+        container.add_env_file(path.clone());
+
+        assert_eq!(command, container.command());
+        let args = container.get_launch_args().unwrap();
+        let env_file = path.to_string_lossy().to_string();
+        assert_eq!(
+            args,
+            vec![
+                "run",
+                "--env-file",
+                &env_file,
+                "-d",
+                "-e",
+                "TOKEN=abc",
+                "alpine"
+            ]
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
     #[test]
     fn get_cmd_args_handle_quoted_strings_docker_cmd() {
         let command = "docker run -d --rm alpine sh -c \"sleep 30 && echo something\"";
@@ -241,6 +710,37 @@ mod tests {
         assert_eq!(args, vec!["run", "--rm", "-d", "alpine", "sleep", "3"]);
     }
 
+    #[test_case("docker run -d some_image", Some("some_image"); "Only detach")]
+    #[test_case("docker run -it -d --rm -e A=1 -v /a:/b --gpus all img cmd -x", Some("img"); "Flags with values")]
+    #[test_case("docker run -d --name=test --ipc=host img:1.0", Some("img:1.0"); "Flags with equals")]
+    #[test_case("docker run -d", None; "No image")]
+    fn image_is_found(command: &str, image: Option<&str>) {
+        let container = QueuedContainer::new(command).unwrap();
+        assert_eq!(image.map(String::from), container.image());
+    }
+
+    #[test]
+    fn get_cmd_args_adds_labels() {
+        let mut container = QueuedContainer::new("docker run -d img cmd").unwrap();
+        container.add_label("team".into(), "vision".into());
+        let args = container.get_cmd_args().unwrap();
+        assert_eq!(
+            args,
+            vec!["run", "--label", "team=vision", "-d", "img", "cmd"]
+        );
+        assert_eq!(Some("img".to_string()), container.image());
+    }
+
+    #[test_case("train-1.a_b", true; "Valid name")]
+    #[test_case("-train", false; "Leading dash")]
+    #[test_case("train 1", false; "With space")]
+    #[test_case("", false; "Empty")]
+    #[test_case("67e55044-10b1-426f-9247-bb680e5fe0c8", false; "Uuid")]
+    fn set_name_validates_the_name(name: &str, is_valid: bool) {
+        let mut container = QueuedContainer::new("docker run -d img").unwrap();
+        assert_eq!(is_valid, container.set_name(name).is_ok());
+    }
+
     #[test]
     fn get_cmd_args_handle_quotes_on_options() {
         let command = "docker run --rm -d --gpus '\"device=0\"' --ipc=host";
@@ -0,0 +1,285 @@
+use crate::error_chain_fmt;
+use serde::{Deserialize, Serialize};
+
+#[derive(thiserror::Error)]
+pub enum ResourcesError {
+    #[error("Invalid memory, expected a number of bytes with an optional unit as \"512m\" or \"4g\": {0:?}")]
+    InvalidMemory(String),
+    #[error("Invalid number of CPUs, expected a positive number as \"1.5\": {0:?}")]
+    InvalidCpus(String),
+}
+
+impl std::fmt::Debug for ResourcesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        error_chain_fmt(self, f)
+    }
+}
+
+/// Amounts of host resources, the requests of a container or the capacity of the host.
+/// A resource that is not set is not requested, or not limited for a capacity.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Resources {
+    /// Memory in bytes.
+    memory: Option<u64>,
+    cpus: Option<f64>,
+    gpus: Option<u32>,
+}
+
+impl Resources {
+    pub fn new(memory: Option<u64>, cpus: Option<f64>, gpus: Option<u32>) -> Self {
+        Self { memory, cpus, gpus }
+    }
+
+    /// Get the memory in bytes.
+    pub fn memory(&self) -> Option<u64> {
+        self.memory
+    }
+
+    /// Get the number of CPUs.
+    pub fn cpus(&self) -> Option<f64> {
+        self.cpus
+    }
+
+    /// Get the number of GPUs.
+    pub fn gpus(&self) -> Option<u32> {
+        self.gpus
+    }
+
+    /// Whether no resource is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Get the sum of both amounts, a resource is set if it is set in either.
+    pub fn add(&self, other: &Resources) -> Self {
+        fn add<T: std::ops::Add<Output = T>>(a: Option<T>, b: Option<T>) -> Option<T> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(a + b),
+                (a, b) => a.or(b),
+            }
+        }
+        Self {
+            memory: add(self.memory, other.memory),
+            cpus: add(self.cpus, other.cpus),
+            gpus: add(self.gpus, other.gpus),
+        }
+    }
+
+    /// Whether these requests fit in `capacity` next to the `used` resources.
+    pub fn fits(&self, used: &Resources, capacity: &Resources) -> bool {
+        fn fits<T: std::ops::Add<Output = T> + PartialOrd + Default>(
+            requested: Option<T>,
+            used: Option<T>,
+            capacity: Option<T>,
+        ) -> bool {
+            capacity.is_none_or(|capacity| {
+                requested.unwrap_or_default() + used.unwrap_or_default() <= capacity
+            })
+        }
+        fits(self.memory, used.memory, capacity.memory)
+            && fits(self.cpus, used.cpus, capacity.cpus)
+            && fits(self.gpus, used.gpus, capacity.gpus)
+    }
+
+    /// Get the docker run flags that limit the container to these requests, on the
+    /// `gpu_devices` host GPUs if there are any or else on any GPUs.
+    pub fn to_args(&self, gpu_devices: &[u32]) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(memory) = self.memory {
+            args.extend(["--memory".to_string(), memory.to_string()]);
+        }
+        if let Some(cpus) = self.cpus {
+            args.extend(["--cpus".to_string(), cpus.to_string()]);
+        }
+        if !gpu_devices.is_empty() {
+            let devices = gpu_devices
+                .iter()
+                .map(u32::to_string)
+                .collect::<Vec<_>>()
+                .join(",");
+            // Docker splits the value on commas unless it is quoted
+            args.extend(["--gpus".to_string(), format!("\"device={}\"", devices)]);
+        } else if let Some(gpus) = self.gpus {
+            args.extend(["--gpus".to_string(), gpus.to_string()]);
+        }
+        args
+    }
+}
+
+/// Shows the resources as "memory 4.0G, cpus 2, gpus 1", "-" if none is set.
+impl std::fmt::Display for Resources {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(memory) = self.memory {
+            parts.push(format!("memory {}", show_memory(memory)));
+        }
+        if let Some(cpus) = self.cpus {
+            parts.push(format!("cpus {}", cpus));
+        }
+        if let Some(gpus) = self.gpus {
+            parts.push(format!("gpus {}", gpus));
+        }
+        if parts.is_empty() {
+            return write!(f, "-");
+        }
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// The resources requested by the running containers and the capacity of the host.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ResourceUsage {
+    used: Resources,
+    capacity: Resources,
+}
+
+impl ResourceUsage {
+    pub fn new(used: Resources, capacity: Resources) -> Self {
+        Self { used, capacity }
+    }
+
+    /// Get a reference to the resources requested by the running containers.
+    pub fn used(&self) -> &Resources {
+        &self.used
+    }
+
+    /// Get a reference to the capacity of the host, empty if it is not limited.
+    pub fn capacity(&self) -> &Resources {
+        &self.capacity
+    }
+}
+
+/// Shows the usage as "memory 2.0G/8.0G, cpus 1/4", only for the limited resources.
+impl std::fmt::Display for ResourceUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(capacity) = self.capacity.memory {
+            let used = self.used.memory.unwrap_or_default();
+            parts.push(format!(
+                "memory {}/{}",
+                show_memory(used),
+                show_memory(capacity)
+            ));
+        }
+        if let Some(capacity) = self.capacity.cpus {
+            parts.push(format!(
+                "cpus {}/{}",
+                self.used.cpus.unwrap_or_default(),
+                capacity
+            ));
+        }
+        if let Some(capacity) = self.capacity.gpus {
+            parts.push(format!(
+                "gpus {}/{}",
+                self.used.gpus.unwrap_or_default(),
+                capacity
+            ));
+        }
+        if parts.is_empty() {
+            return write!(f, "-");
+        }
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+const MEMORY_UNITS: [(char, u32); 4] = [('k', 1), ('m', 2), ('g', 3), ('t', 4)];
+
+/// Parses a memory amount as docker does, "512m" or "4g", in bytes.
+pub fn parse_memory(s: &str) -> Result<u64, ResourcesError> {
+    let invalid = || ResourcesError::InvalidMemory(s.to_string());
+    let lower = s.trim().to_lowercase();
+    let number = lower.strip_suffix('b').unwrap_or(&lower);
+    let (number, exponent) = match number.chars().last() {
+        Some(last) if last.is_ascii_alphabetic() => {
+            let (_, exponent) = MEMORY_UNITS
+                .iter()
+                .find(|(unit, _)| *unit == last)
+                .ok_or_else(invalid)?;
+            (&number[..number.len() - 1], *exponent)
+        }
+        _ => (number, 0),
+    };
+    let number = number.parse::<u64>().map_err(|_| invalid())?;
+    number
+        .checked_mul(1024u64.pow(exponent))
+        .ok_or_else(invalid)
+}
+
+/// Parses a number of CPUs, "1.5" for one and a half.
+pub fn parse_cpus(s: &str) -> Result<f64, ResourcesError> {
+    s.trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|cpus| cpus.is_finite() && *cpus > 0.0)
+        .ok_or_else(|| ResourcesError::InvalidCpus(s.to_string()))
+}
+
+/// Shows bytes in the largest unit with at least one of it, as "4.0G".
+fn show_memory(bytes: u64) -> String {
+    MEMORY_UNITS
+        .iter()
+        .rev()
+        .map(|&(unit, exponent)| (unit, 1024u64.pow(exponent)))
+        .find(|&(_, size)| bytes >= size)
+        .map(|(unit, size)| {
+            format!(
+                "{:.1}{}",
+                bytes as f64 / size as f64,
+                unit.to_ascii_uppercase()
+            )
+        })
+        .unwrap_or_else(|| format!("{}B", bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claim::assert_err;
+    use test_case::test_case;
+
+    #[test_case("1024", 1024; "Bytes")]
+    #[test_case("512m", 512 * 1024 * 1024; "Megabytes")]
+    #[test_case("4G", 4 * 1024 * 1024 * 1024; "Uppercase")]
+    #[test_case("2gb", 2 * 1024 * 1024 * 1024; "With b")]
+    fn parse_memory_works(s: &str, expected: u64) {
+        assert_eq!(expected, parse_memory(s).unwrap());
+    }
+
+    #[test]
+    fn parse_resources_rejects_invalid_values() {
+        assert_err!(parse_memory("4x"));
+        assert_err!(parse_memory("g"));
+        assert_err!(parse_cpus("0"));
+        assert_err!(parse_cpus("two"));
+    }
+
+    #[test]
+    fn fits_checks_only_the_limited_resources() {
+        let capacity = Resources::new(Some(8 * 1024), Some(4.0), None);
+        let used = Resources::new(Some(4 * 1024), Some(3.0), Some(2));
+
+        assert!(Resources::new(Some(4 * 1024), Some(1.0), Some(8)).fits(&used, &capacity));
+        assert!(!Resources::new(Some(5 * 1024), None, None).fits(&used, &capacity));
+        assert!(!Resources::new(None, Some(1.5), None).fits(&used, &capacity));
+        assert!(Resources::default().fits(&used, &capacity));
+    }
+
+    #[test]
+    fn resources_display_and_args() {
+        let resources = Resources::new(Some(4 * 1024 * 1024 * 1024), Some(1.5), Some(1));
+        assert_eq!("memory 4.0G, cpus 1.5, gpus 1", resources.to_string());
+        assert_eq!(
+            vec!["--memory", "4294967296", "--cpus", "1.5", "--gpus", "1"],
+            resources.to_args(&[])
+        );
+        assert_eq!(
+            vec!["--gpus", "\"device=1,2\""],
+            Resources::new(None, None, Some(2)).to_args(&[1, 2])
+        );
+        assert_eq!("-", Resources::default().to_string());
+
+        let capacity = Resources::new(None, Some(4.0), Some(2));
+        let usage = ResourceUsage::new(resources, capacity);
+        assert_eq!("cpus 1.5/4, gpus 1/2", usage.to_string());
+    }
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// How a finished or running container gets queued again.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ResubmitOptions {
+    /// Queue the new container at the front instead of at the back.
+    pub front: bool,
+    /// The new container gets queued but not started even if the queue is empty.
+    pub paused: bool,
+}
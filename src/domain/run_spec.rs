@@ -0,0 +1,420 @@
+use crate::error_chain_fmt;
+
+#[derive(thiserror::Error, PartialEq)]
+pub enum RunSpecError {
+    #[error("Should start with \"docker run\"")]
+    NotDockerRun,
+    #[error("Unterminated quote at byte {0}")]
+    UnterminatedQuote(usize),
+    #[error("Unknown flag {0:?} at byte {1}")]
+    UnknownFlag(String, usize),
+    #[error("Flag {0:?} at byte {1} needs a value")]
+    MissingValue(String, usize),
+    #[error("Invalid boolean {1:?} for flag {0:?} at byte {2}")]
+    InvalidBoolean(String, String, usize),
+}
+
+impl std::fmt::Debug for RunSpecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        error_chain_fmt(self, f)
+    }
+}
+
+/// Flags of docker run that don't take a value, they accept one as "--rm=false".
+const BOOLEAN_FLAGS: [&str; 14] = [
+    "detach",
+    "rm",
+    "interactive",
+    "tty",
+    "init",
+    "privileged",
+    "publish-all",
+    "read-only",
+    "no-healthcheck",
+    "oom-kill-disable",
+    "sig-proxy",
+    "help",
+    "disable-content-trust",
+    "quiet",
+];
+
+/// Short flags of docker run with their long name.
+const SHORT_FLAGS: [(char, &str); 15] = [
+    ('a', "attach"),
+    ('c', "cpu-shares"),
+    ('d', "detach"),
+    ('e', "env"),
+    ('h', "hostname"),
+    ('i', "interactive"),
+    ('l', "label"),
+    ('m', "memory"),
+    ('p', "publish"),
+    ('P', "publish-all"),
+    ('q', "quiet"),
+    ('t', "tty"),
+    ('u', "user"),
+    ('v', "volume"),
+    ('w', "workdir"),
+];
+
+/// A flag of a docker run command.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunFlag {
+    name: String,
+    value: Option<String>,
+    position: usize,
+}
+
+impl RunFlag {
+    /// Get a reference to the long name of the flag, without the dashes.
+    pub fn name(&self) -> &str {
+        self.name.as_ref()
+    }
+
+    /// Get a reference to the value of the flag, `None` for a boolean flag without one.
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    /// Get the byte position of the flag in the command.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Whether the flag is a boolean flag set to true.
+    pub fn is_true(&self) -> bool {
+        self.value()
+            .is_none_or(|value| parse_bool(value) == Some(true))
+    }
+}
+
+/// A docker run command split as the shell does, with the flags before the image parsed
+/// by the grammar of docker run: boolean and value flags, combined short flags as "-itd",
+/// values after "=" or in the next word, and "--" before the image.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunSpec {
+    options: Vec<String>,
+    flags: Vec<RunFlag>,
+    image: Option<String>,
+    args: Vec<String>,
+}
+
+impl RunSpec {
+    pub fn parse(command: &str) -> Result<Self, RunSpecError> {
+        let mut words = split_words(command)?.into_iter();
+        if words.next().map(|word| word.text).as_deref() != Some("docker") {
+            return Err(RunSpecError::NotDockerRun);
+        }
+        let mut subcommand = words.next().map(|word| word.text);
+        if subcommand.as_deref() == Some("container") {
+            subcommand = words.next().map(|word| word.text);
+        }
+        if subcommand.as_deref() != Some("run") {
+            return Err(RunSpecError::NotDockerRun);
+        }
+
+        let mut spec = RunSpec::default();
+        while let Some(word) = words.next() {
+            if word.text == "--" {
+                spec.options.push(word.text);
+                spec.image = words.next().map(|word| word.text);
+                break;
+            }
+            if !word.text.starts_with('-') || word.text == "-" {
+                spec.image = Some(word.text);
+                break;
+            }
+            spec.options.push(word.text.clone());
+
+            let mut next_value = |name: &str| match words.next() {
+                Some(next) => {
+                    spec.options.push(next.text.clone());
+                    Ok(next.text)
+                }
+                None => Err(RunSpecError::MissingValue(name.to_string(), word.position)),
+            };
+            if let Some(long) = word.text.strip_prefix("--") {
+                let (name, value) = match long.split_once('=') {
+                    Some((name, value)) => (name, Some(value.to_string())),
+                    None => (long, None),
+                };
+                let value = match value {
+                    Some(value) if is_boolean(name) && parse_bool(&value).is_none() => {
+                        return Err(RunSpecError::InvalidBoolean(
+                            format!("--{}", name),
+                            value,
+                            word.position,
+                        ));
+                    }
+                    None if !is_boolean(name) => Some(next_value(&word.text)?),
+                    value => value,
+                };
+                spec.flags.push(RunFlag {
+                    name: name.to_string(),
+                    value,
+                    position: word.position,
+                });
+                continue;
+            }
+
+            let letters = &word.text[1..];
+            for (i, letter) in letters.char_indices() {
+                let position = word.position + 1 + i;
+                let name = SHORT_FLAGS
+                    .iter()
+                    .find(|(short, _)| *short == letter)
+                    .map(|(_, name)| name.to_string())
+                    .ok_or_else(|| RunSpecError::UnknownFlag(format!("-{}", letter), position))?;
+                let rest = &letters[i + letter.len_utf8()..];
+                if is_boolean(&name) {
+                    let value = rest.strip_prefix('=').map(String::from);
+                    if let Some(value) = &value {
+                        if parse_bool(value).is_none() {
+                            let flag = format!("-{}", letter);
+                            return Err(RunSpecError::InvalidBoolean(
+                                flag,
+                                value.clone(),
+                                position,
+                            ));
+                        }
+                    }
+                    let has_value = value.is_some();
+                    spec.flags.push(RunFlag {
+                        name,
+                        value,
+                        position,
+                    });
+                    if has_value {
+                        break;
+                    }
+                } else {
+                    // A value flag takes the rest of the word, or the next one
+                    let rest = rest.strip_prefix('=').unwrap_or(rest);
+                    let value = if rest.is_empty() {
+                        next_value(&format!("-{}", letter))?
+                    } else {
+                        rest.to_string()
+                    };
+                    spec.flags.push(RunFlag {
+                        name,
+                        value: Some(value),
+                        position,
+                    });
+                    break;
+                }
+            }
+        }
+        spec.args = words.map(|word| word.text).collect();
+        Ok(spec)
+    }
+
+    /// Get a reference to the flags before the image.
+    pub fn flags(&self) -> &[RunFlag] {
+        &self.flags
+    }
+
+    /// Get a reference to the image of the container.
+    pub fn image(&self) -> Option<&str> {
+        self.image.as_deref()
+    }
+
+    /// Get a reference to the args passed to the container after the image.
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    /// Whether the container runs detached, `None` if there is no detach flag. The last
+    /// detach flag wins.
+    pub fn detach(&self) -> Option<bool> {
+        self.flags
+            .iter()
+            .rev()
+            .find(|flag| flag.name() == "detach")
+            .map(RunFlag::is_true)
+    }
+
+    /// Get the arguments of docker, starting with "run", with the flags as written.
+    pub fn to_args(&self) -> Vec<String> {
+        std::iter::once("run".to_string())
+            .chain(self.options.iter().cloned())
+            .chain(self.image.iter().cloned())
+            .chain(self.args.iter().cloned())
+            .collect()
+    }
+}
+
+fn is_boolean(name: &str) -> bool {
+    BOOLEAN_FLAGS.contains(&name)
+}
+
+/// Parses a boolean as docker does.
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "1" | "t" | "T" | "true" | "TRUE" | "True" => Some(true),
+        "0" | "f" | "F" | "false" | "FALSE" | "False" => Some(false),
+        _ => None,
+    }
+}
+
+struct Word {
+    text: String,
+    position: usize,
+}
+
+/// Splits a command in words as the shell does, a line break is a space.
+fn split_words(command: &str) -> Result<Vec<Word>, RunSpecError> {
+    fn text(word: &mut Option<Word>, position: usize) -> &mut String {
+        &mut word
+            .get_or_insert_with(|| Word {
+                text: String::new(),
+                position,
+            })
+            .text
+    }
+
+    let mut words = Vec::new();
+    let mut word = None;
+    let mut chars = command.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, '\n')) => words.extend(word.take()),
+                Some((_, escaped)) => text(&mut word, i).push(escaped),
+                None => text(&mut word, i).push(c),
+            },
+            '\'' => {
+                let text = text(&mut word, i);
+                loop {
+                    match chars.next() {
+                        Some((_, '\'')) => break,
+                        Some((_, c)) => text.push(c),
+                        None => return Err(RunSpecError::UnterminatedQuote(i)),
+                    }
+                }
+            }
+            '"' => {
+                let text = text(&mut word, i);
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, '\n')) => {}
+                            Some((_, c)) if "$`\"\\".contains(c) => text.push(c),
+                            Some((_, c)) => {
+                                text.push('\\');
+                                text.push(c);
+                            }
+                            None => return Err(RunSpecError::UnterminatedQuote(i)),
+                        },
+                        Some((_, c)) => text.push(c),
+                        None => return Err(RunSpecError::UnterminatedQuote(i)),
+                    }
+                }
+            }
+            c if c.is_whitespace() => words.extend(word.take()),
+            c => text(&mut word, i).push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Joins the lines of a command, the line breaks outside quotes and their "\" become
+/// spaces.
+pub fn join_lines(command: &str) -> String {
+    let mut joined = String::with_capacity(command.len());
+    let mut quote = None;
+    let mut chars = command.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (None, '\\') if chars.peek() == Some(&'\n') => {
+                chars.next();
+                joined.push(' ');
+            }
+            (None | Some('"'), '\\') => {
+                joined.push(c);
+                joined.extend(chars.next());
+            }
+            (None, '\n') => joined.push(' '),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                joined.push(c);
+            }
+            (Some(q), c) if c == q => {
+                quote = None;
+                joined.push(c);
+            }
+            _ => joined.push(c),
+        }
+    }
+    joined.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("docker run -d img", Some(true); "Short")]
+    #[test_case("docker run -itd img", Some(true); "Combined")]
+    #[test_case("docker run -d=true img", Some(true); "Short with value")]
+    #[test_case("docker run --detach=false img", Some(false); "Long with value")]
+    #[test_case("docker run -e -d img", None; "Value starting with dash")]
+    #[test_case("docker run --name -d img", None; "Long value starting with dash")]
+    #[test_case("docker run -- -d", None; "Image after double dash")]
+    #[test_case("docker container run --rm -d img", Some(true); "Container subcommand")]
+    fn detach_is_found(command: &str, detach: Option<bool>) {
+        assert_eq!(detach, RunSpec::parse(command).unwrap().detach());
+    }
+
+    #[test]
+    fn run_spec_splits_flags_image_and_args() {
+        let command = "docker run -itd -eA=1 --name=job -v /a:/b --rm img:1.0 sh -c 'echo \\$A' -d";
+        let spec = RunSpec::parse(command).unwrap();
+        let flags = spec
+            .flags()
+            .iter()
+            .map(|flag| (flag.name(), flag.value()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            flags,
+            vec![
+                ("interactive", None),
+                ("tty", None),
+                ("detach", None),
+                ("env", Some("A=1")),
+                ("name", Some("job")),
+                ("volume", Some("/a:/b")),
+                ("rm", None),
+            ]
+        );
+        assert_eq!(Some("img:1.0"), spec.image());
+        assert_eq!(spec.args(), ["sh", "-c", "echo \\$A", "-d"]);
+        assert_eq!(spec.to_args()[..4], ["run", "-itd", "-eA=1", "--name=job"]);
+    }
+
+    #[test]
+    fn split_words_keeps_literal_backslashes() {
+        let command = r#"docker run -d img printf 'a\nb' "c\d \"e\"" f\ g"#;
+        let spec = RunSpec::parse(command).unwrap();
+        assert_eq!(spec.args(), ["printf", "a\\nb", "c\\d \"e\"", "f g"]);
+    }
+
+    #[test_case("docker ps", RunSpecError::NotDockerRun; "Not run")]
+    #[test_case("docker run -d img 'a", RunSpecError::UnterminatedQuote(18); "Quote")]
+    #[test_case("docker run -dx img", RunSpecError::UnknownFlag("-x".into(), 13); "Unknown")]
+    #[test_case("docker run -d --name", RunSpecError::MissingValue("--name".into(), 14); "Missing value")]
+    #[test_case("docker run --rm=yes img", RunSpecError::InvalidBoolean("--rm".into(), "yes".into(), 11); "Boolean")]
+    fn run_spec_errors_have_positions(command: &str, error: RunSpecError) {
+        assert_eq!(Err(error), RunSpec::parse(command));
+    }
+
+    #[test]
+    fn join_lines_keeps_quoted_line_breaks() {
+        let command = "docker run \\\n  -d img\nsh -c 'echo a\necho b'\n";
+        assert_eq!(
+            "docker run    -d img sh -c 'echo a\necho b'",
+            join_lines(command)
+        );
+    }
+}
@@ -1,8 +1,38 @@
 use bollard::models::ContainerSummaryInner;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+/// Label with the queued container id of the containers launched by the queue.
+pub const JOB_ID_LABEL: &str = "docker_queue.job_id";
+/// Label with the instance name of the server that launched the container.
+pub const SERVER_LABEL: &str = "docker_queue.server";
+/// Label with the queue of the job of the container.
+pub const QUEUE_LABEL: &str = "docker_queue.queue";
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum RunningContainer {
-    Tracked(ContainerSummaryInner),
+    /// A container launched by the queue, with the time it was launched when the server
+    /// still knows it.
+    Tracked(ContainerSummaryInner, Option<DateTime<Utc>>),
     External(ContainerSummaryInner),
 }
+
+impl RunningContainer {
+    /// Get the queued container id of a container launched by the queue.
+    pub fn job_id(&self) -> Option<&str> {
+        self.label(JOB_ID_LABEL)
+    }
+
+    /// Get the queue of the job of the container, for containers launched by the queue.
+    pub fn queue(&self) -> Option<&str> {
+        self.label(QUEUE_LABEL)
+    }
+
+    fn label(&self, key: &str) -> Option<&str> {
+        let container = match self {
+            RunningContainer::Tracked(container, _) => container,
+            RunningContainer::External(container) => container,
+        };
+        container.labels.as_ref()?.get(key).map(String::as_str)
+    }
+}
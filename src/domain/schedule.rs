@@ -0,0 +1,113 @@
+use crate::error_chain_fmt;
+use chrono::{DateTime, Duration, Local, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+#[derive(thiserror::Error)]
+pub enum ScheduleError {
+    #[error("Invalid time, expected \"HH:MM\", \"YYYY-MM-DD HH:MM\" or RFC 3339: {0:?}")]
+    InvalidTime(String),
+    #[error("Invalid delay, expected a duration as \"2h\" or \"1h 30m\": {0:?}")]
+    InvalidDelay(String),
+}
+
+impl std::fmt::Debug for ScheduleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        error_chain_fmt(self, f)
+    }
+}
+
+const TIME_FORMATS: [&str; 2] = ["%H:%M", "%H:%M:%S"];
+const DATE_TIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+];
+
+/// Parses the time given to `--at`, a time of day refers to its next occurrence.
+pub fn parse_at(s: &str) -> Result<DateTime<Utc>, ScheduleError> {
+    parse_at_from(s, Local::now())
+}
+
+/// Parses the time given to `--at` as `parse_at`, taking `now` as the current time.
+pub fn parse_at_from(s: &str, now: DateTime<Local>) -> Result<DateTime<Utc>, ScheduleError> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(s) {
+        return Ok(time.with_timezone(&Utc));
+    }
+
+    let date_time = DATE_TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok());
+    if let Some(date_time) = date_time {
+        return local_to_utc(s, date_time);
+    }
+
+    let time = TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(s, format).ok())
+        .ok_or_else(|| ScheduleError::InvalidTime(s.to_string()))?;
+    let mut date_time = now.date().naive_local().and_time(time);
+    if date_time <= now.naive_local() {
+        date_time += Duration::days(1);
+    }
+    local_to_utc(s, date_time)
+}
+
+fn local_to_utc(s: &str, date_time: NaiveDateTime) -> Result<DateTime<Utc>, ScheduleError> {
+    Local
+        .from_local_datetime(&date_time)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+        .ok_or_else(|| ScheduleError::InvalidTime(s.to_string()))
+}
+
+/// Parses the duration given to `--delay`.
+pub fn parse_delay(s: &str) -> Result<std::time::Duration, ScheduleError> {
+    humantime::parse_duration(s).map_err(|_| ScheduleError::InvalidDelay(s.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claim::assert_err;
+    use test_case::test_case;
+
+    fn local(s: &str) -> DateTime<Local> {
+        let date_time = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        Local.from_local_datetime(&date_time).unwrap()
+    }
+
+    #[test_case("22:00", "2021-11-05 22:00"; "Later today")]
+    #[test_case("08:30", "2021-11-06 08:30"; "Tomorrow")]
+    #[test_case("2021-11-07 10:00", "2021-11-07 10:00"; "Date and time")]
+    #[test_case("2021-11-07T10:00:00", "2021-11-07 10:00"; "Date and time with seconds")]
+    fn parse_at_works(s: &str, expected: &str) {
+        let now = local("2021-11-05 12:00");
+        let time = parse_at_from(s, now).unwrap();
+        assert_eq!(local(expected).with_timezone(&Utc), time);
+    }
+
+    #[test]
+    fn parse_at_accepts_rfc3339() {
+        let time = parse_at("2021-11-05T22:00:00+01:00").unwrap();
+        assert_eq!(Utc.ymd(2021, 11, 5).and_hms(21, 0, 0), time);
+    }
+
+    #[test]
+    fn parse_at_rejects_invalid_times() {
+        assert_err!(parse_at("25:00"));
+        assert_err!(parse_at("tonight"));
+    }
+
+    #[test]
+    fn parse_delay_works() {
+        assert_eq!(
+            std::time::Duration::from_secs(2 * 3600),
+            parse_delay("2h").unwrap()
+        );
+        assert_eq!(
+            std::time::Duration::from_secs(5400),
+            parse_delay("1h 30m").unwrap()
+        );
+        assert_err!(parse_delay("soon"));
+    }
+}
@@ -0,0 +1,231 @@
+use super::QueuedContainer;
+use chrono::{DateTime, Duration, Utc};
+use std::{collections::HashMap, str::FromStr};
+
+/// Time window of the usage counted by `FairShareScheduler`.
+const USAGE_WINDOW_HOURS: i64 = 24;
+
+/// A launch of a container by one of the owners sharing the queue.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Launch {
+    owner: Option<String>,
+    started_at: DateTime<Utc>,
+    finished_at: Option<DateTime<Utc>>,
+}
+
+impl Launch {
+    /// * `finished_at` - `None` while the container runs
+    pub fn new(
+        owner: Option<String>,
+        started_at: DateTime<Utc>,
+        finished_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            owner,
+            started_at,
+            finished_at,
+        }
+    }
+
+    /// Get the time the container ran in `(since, now]`.
+    fn usage(&self, since: DateTime<Utc>, now: DateTime<Utc>) -> Duration {
+        let start = self.started_at.max(since);
+        let end = self.finished_at.unwrap_or(now).min(now);
+        (end - start).max(Duration::zero())
+    }
+}
+
+/// Picks the container the launcher runs next among the ready ones.
+///
+/// The owners are the users reported by the clients, which are not authenticated, so
+/// the policies sharing the queue between owners are cooperative only: a submitter can
+/// claim to be someone else.
+pub trait Scheduler: std::fmt::Debug + Send + Sync {
+    /// Get the index of the next container in `ready`, which is in queue order, from the
+    /// `launches` of the containers that ran or are running.
+    fn pick(
+        &self,
+        ready: &[QueuedContainer],
+        launches: &[Launch],
+        now: DateTime<Utc>,
+    ) -> Option<usize>;
+}
+
+/// Runs the containers in queue order.
+#[derive(Debug)]
+pub struct FifoScheduler;
+
+impl Scheduler for FifoScheduler {
+    fn pick(&self, ready: &[QueuedContainer], _: &[Launch], _: DateTime<Utc>) -> Option<usize> {
+        (!ready.is_empty()).then_some(0)
+    }
+}
+
+/// Takes turns between the owners, the next container is the first one of the owner
+/// that launched a container the longest time ago.
+#[derive(Debug)]
+pub struct RoundRobinScheduler;
+
+impl Scheduler for RoundRobinScheduler {
+    fn pick(
+        &self,
+        ready: &[QueuedContainer],
+        launches: &[Launch],
+        _: DateTime<Utc>,
+    ) -> Option<usize> {
+        let mut last_launches: HashMap<Option<&str>, DateTime<Utc>> = HashMap::new();
+        for launch in launches {
+            let last = last_launches
+                .entry(launch.owner.as_deref())
+                .or_insert(launch.started_at);
+            *last = (*last).max(launch.started_at);
+        }
+        // Owners that never launched a container go first, `None` sorts before `Some`
+        pick_min_by_owner(ready, |owner| last_launches.get(&owner).copied())
+    }
+}
+
+/// Favors the owners that used the queue the least, the next container is the first
+/// one of the owner whose containers ran the shortest time in the last 24 hours.
+#[derive(Debug)]
+pub struct FairShareScheduler;
+
+impl Scheduler for FairShareScheduler {
+    fn pick(
+        &self,
+        ready: &[QueuedContainer],
+        launches: &[Launch],
+        now: DateTime<Utc>,
+    ) -> Option<usize> {
+        let since = now - Duration::hours(USAGE_WINDOW_HOURS);
+        let mut usages: HashMap<Option<&str>, Duration> = HashMap::new();
+        for launch in launches {
+            let usage = usages
+                .entry(launch.owner.as_deref())
+                .or_insert_with(Duration::zero);
+            *usage = *usage + launch.usage(since, now);
+        }
+        pick_min_by_owner(ready, |owner| {
+            usages.get(&owner).copied().unwrap_or_else(Duration::zero)
+        })
+    }
+}
+
+/// Get the index of the first ready container of the owner with the lowest key.
+fn pick_min_by_owner<K: Ord>(
+    ready: &[QueuedContainer],
+    key: impl Fn(Option<&str>) -> K,
+) -> Option<usize> {
+    ready
+        .iter()
+        .enumerate()
+        // `min_by_key` keeps the first of equal keys, so the queue order breaks ties
+        .min_by_key(|(_, container)| key(container.owner()))
+        .map(|(index, _)| index)
+}
+
+/// How the launcher picks the next container, set when the server starts.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SchedulingPolicy {
+    #[default]
+    Fifo,
+    RoundRobin,
+    FairShare,
+}
+
+impl FromStr for SchedulingPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "fifo" => Ok(Self::Fifo),
+            "round-robin" => Ok(Self::RoundRobin),
+            "fair-share" => Ok(Self::FairShare),
+            _ => Err(anyhow::anyhow!(
+                "Expected \"fifo\", \"round-robin\" or \"fair-share\": {:?}",
+                s
+            )),
+        }
+    }
+}
+
+impl SchedulingPolicy {
+    /// Get the scheduler that implements the policy.
+    pub fn scheduler(&self) -> Box<dyn Scheduler> {
+        match self {
+            SchedulingPolicy::Fifo => Box::new(FifoScheduler),
+            SchedulingPolicy::RoundRobin => Box::new(RoundRobinScheduler),
+            SchedulingPolicy::FairShare => Box::new(FairShareScheduler),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn containers(owners: &[&str]) -> Vec<QueuedContainer> {
+        owners
+            .iter()
+            .map(|owner| {
+                let mut container = QueuedContainer::new("docker run -d some_image").unwrap();
+                container.set_owner(owner.to_string());
+                container
+            })
+            .collect()
+    }
+
+    fn launch(
+        owner: &str,
+        started_hours_ago: i64,
+        hours: Option<i64>,
+        now: DateTime<Utc>,
+    ) -> Launch {
+        let started_at = now - Duration::hours(started_hours_ago);
+        let finished_at = hours.map(|hours| started_at + Duration::hours(hours));
+        Launch::new(Some(owner.to_string()), started_at, finished_at)
+    }
+
+    #[test]
+    fn fifo_scheduler_picks_the_first_container() {
+        let ready = containers(&["alice", "bob"]);
+        assert_eq!(Some(0), FifoScheduler.pick(&ready, &[], Utc::now()));
+        assert_eq!(None, FifoScheduler.pick(&[], &[], Utc::now()));
+    }
+
+    #[test]
+    fn round_robin_scheduler_takes_turns_between_owners() {
+        let now = Utc::now();
+        let ready = containers(&["alice", "alice", "bob", "carol"]);
+        let launches = [
+            launch("alice", 2, Some(1), now),
+            launch("bob", 3, Some(1), now),
+        ];
+        // Carol never launched anything
+        assert_eq!(Some(3), RoundRobinScheduler.pick(&ready, &launches, now));
+        let ready = containers(&["bob", "alice"]);
+        let launches = [
+            launch("alice", 2, Some(1), now),
+            launch("bob", 1, None, now),
+        ];
+        assert_eq!(Some(1), RoundRobinScheduler.pick(&ready, &launches, now));
+    }
+
+    #[test]
+    fn fair_share_scheduler_favors_the_least_recent_usage() {
+        let now = Utc::now();
+        let ready = containers(&["alice", "bob", "bob"]);
+        let launches = [
+            launch("alice", 5, Some(3), now),
+            launch("bob", 2, None, now),
+            // Outside of the usage window
+            launch("bob", 100, Some(50), now),
+        ];
+        assert_eq!(Some(1), FairShareScheduler.pick(&ready, &launches, now));
+        let launches = [
+            launch("alice", 5, Some(1), now),
+            launch("bob", 2, None, now),
+        ];
+        assert_eq!(Some(0), FairShareScheduler.pick(&ready, &launches, now));
+    }
+}
@@ -0,0 +1,78 @@
+use super::QueuedContainer;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Parses a docker label as "key=value", "key" alone has an empty value.
+pub fn parse_label(s: &str) -> Result<(String, String), String> {
+    let (key, value) = s.split_once('=').unwrap_or((s, ""));
+    if key.is_empty() {
+        return Err(format!("Expected a label as \"key=value\": {:?}", s));
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// Whether `labels` has every label of `selected`.
+pub fn has_labels(labels: &HashMap<String, String>, selected: &BTreeMap<String, String>) -> bool {
+    selected
+        .iter()
+        .all(|(key, value)| labels.get(key) == Some(value))
+}
+
+/// Selects queued containers by id or name, and by labels.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Selector {
+    ids: Vec<String>,
+    labels: BTreeMap<String, String>,
+}
+
+impl Selector {
+    /// * `ids` - Ids or names of the queued containers, any of them matches
+    /// * `labels` - Labels the queued containers must have, all of them
+    pub fn new(ids: Vec<String>, labels: impl IntoIterator<Item = (String, String)>) -> Self {
+        Self {
+            ids,
+            labels: labels.into_iter().collect(),
+        }
+    }
+
+    /// Whether the selector doesn't select anything.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty() && self.labels.is_empty()
+    }
+
+    pub fn matches(&self, container: &QueuedContainer) -> bool {
+        let id_matches = self.ids.is_empty()
+            || self
+                .ids
+                .iter()
+                .any(|id| *id == container.id() || Some(id.as_str()) == container.name());
+        !self.is_empty() && id_matches && has_labels(container.labels(), &self.labels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claim::assert_err;
+
+    #[test]
+    fn parse_label_works() {
+        assert_eq!(Ok(("a".into(), "b=c".into())), parse_label("a=b=c"));
+        assert_eq!(Ok(("a".into(), "".into())), parse_label("a"));
+        assert_err!(parse_label("=b"));
+    }
+
+    #[test]
+    fn selector_matches_ids_names_and_labels() {
+        let mut container = QueuedContainer::new("docker run -d img").unwrap();
+        container.set_name("train").unwrap();
+        container.add_label("team".into(), "vision".into());
+
+        assert!(Selector::new(vec![container.id()], []).matches(&container));
+        assert!(Selector::new(vec!["train".into()], []).matches(&container));
+        assert!(Selector::new(vec![], [("team".into(), "vision".into())]).matches(&container));
+        assert!(!Selector::new(vec![], [("team".into(), "nlp".into())]).matches(&container));
+        assert!(!Selector::new(vec!["other".into()], []).matches(&container));
+        assert!(!Selector::default().matches(&container));
+    }
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+/// Whether the launcher starts the queued containers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ServerMode {
+    #[default]
+    Active,
+    /// The running containers finish but no queued container starts, before a
+    /// maintenance of the host.
+    Draining,
+}
+
+/// The mode of the server with the number of containers it runs and queues.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ServerStatus {
+    mode: ServerMode,
+    running: usize,
+    queued: usize,
+}
+
+impl ServerStatus {
+    pub fn new(mode: ServerMode, running: usize, queued: usize) -> Self {
+        Self {
+            mode,
+            running,
+            queued,
+        }
+    }
+
+    /// Get the mode of the server.
+    pub fn mode(&self) -> ServerMode {
+        self.mode
+    }
+
+    /// Get the number of running containers launched by the server.
+    pub fn running(&self) -> usize {
+        self.running
+    }
+
+    /// Get the number of queued containers, paused ones included.
+    pub fn queued(&self) -> usize {
+        self.queued
+    }
+
+    /// Whether the server is draining and its last running container finished, the
+    /// host can go down for maintenance.
+    pub fn is_drained(&self) -> bool {
+        self.mode == ServerMode::Draining && self.running == 0
+    }
+}
+
+/// Shows the status as "Active", "Draining, 2 containers running" or "Drained".
+impl std::fmt::Display for ServerStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.mode {
+            ServerMode::Active => write!(f, "Active"),
+            ServerMode::Draining if self.is_drained() => write!(f, "Drained"),
+            ServerMode::Draining => write!(
+                f,
+                "Draining, {} container{} running",
+                self.running,
+                if self.running == 1 { "" } else { "s" }
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_status_display() {
+        assert_eq!(
+            "Active",
+            ServerStatus::new(ServerMode::Active, 1, 3).to_string()
+        );
+        assert_eq!(
+            "Draining, 1 container running",
+            ServerStatus::new(ServerMode::Draining, 1, 3).to_string()
+        );
+        assert_eq!(
+            "Drained",
+            ServerStatus::new(ServerMode::Draining, 0, 3).to_string()
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use std::str::FromStr;
+
+/// What the server does with its running containers when it shuts down.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ShutdownPolicy {
+    /// The containers keep running, the server adopts them again after a restart with
+    /// the same instance name and waits for them as before.
+    #[default]
+    Leave,
+    /// The containers are stopped.
+    Stop,
+    /// The containers are stopped and queued again at the front of their queue, they
+    /// run again after a restart.
+    Requeue,
+}
+
+impl FromStr for ShutdownPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "leave" => Ok(Self::Leave),
+            "stop" => Ok(Self::Stop),
+            "requeue" => Ok(Self::Requeue),
+            _ => Err(anyhow::anyhow!(
+                "Expected \"leave\", \"stop\" or \"requeue\": {:?}",
+                s
+            )),
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use crate::error_chain_fmt;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use std::collections::BTreeMap;
+
+#[derive(thiserror::Error)]
+pub enum SweepError {
+    #[error("Invalid sweep parameters, expected a map of lists of values: {0}")]
+    InvalidParams(String),
+    #[error("Parameter {0:?} has no values.")]
+    EmptyParam(String),
+    #[error("Parameter {0:?} has no values in the params file, write \"{{{{\" and \"}}}}\" for literal braces.")]
+    UnknownParam(String),
+    #[error("The command has no placeholders to sweep over, as \"{{0.1,0.01}}\" or \"{{name}}\".")]
+    NoPlaceholders,
+}
+
+impl std::fmt::Debug for SweepError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        error_chain_fmt(self, f)
+    }
+}
+
+/// How many queued containers of a sweep are in each state.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SweepSummary {
+    id: String,
+    queued: usize,
+    paused: usize,
+    running: usize,
+    finished: usize,
+}
+
+impl SweepSummary {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn add_queued(&mut self) {
+        self.queued += 1;
+    }
+
+    pub fn add_paused(&mut self) {
+        self.paused += 1;
+    }
+
+    pub fn add_running(&mut self) {
+        self.running += 1;
+    }
+
+    pub fn add_finished(&mut self) {
+        self.finished += 1;
+    }
+
+    /// Get a reference to the sweep id.
+    pub fn id(&self) -> &str {
+        self.id.as_ref()
+    }
+
+    /// Get the number of queued containers of the sweep.
+    pub fn queued(&self) -> usize {
+        self.queued
+    }
+
+    /// Get the number of paused containers of the sweep.
+    pub fn paused(&self) -> usize {
+        self.paused
+    }
+
+    /// Get the number of running containers of the sweep.
+    pub fn running(&self) -> usize {
+        self.running
+    }
+
+    /// Get the number of finished containers of the sweep.
+    pub fn finished(&self) -> usize {
+        self.finished
+    }
+
+    /// Get the number of containers of the sweep.
+    pub fn total(&self) -> usize {
+        self.queued + self.paused + self.running + self.finished
+    }
+}
+
+/// Parses a yaml map of parameter names to lists of values, as "lr: [0.1, 0.01]".
+pub fn parse_sweep_params(text: &str) -> Result<BTreeMap<String, Vec<String>>, SweepError> {
+    let params: BTreeMap<String, Vec<Value>> =
+        serde_yaml::from_str(text).map_err(|error| SweepError::InvalidParams(error.to_string()))?;
+    params
+        .into_iter()
+        .map(|(name, values)| {
+            let values = values
+                .into_iter()
+                .map(|value| match value {
+                    Value::String(s) => Ok(s),
+                    Value::Number(n) => Ok(n.to_string()),
+                    Value::Bool(b) => Ok(b.to_string()),
+                    value => Err(SweepError::InvalidParams(format!(
+                        "{:?} is not a scalar value of {:?}",
+                        value, name
+                    ))),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok((name, values))
+        })
+        .collect()
+}
+
+enum Segment {
+    Literal(String),
+    Axis(usize),
+}
+
+/// Expands the placeholders of `template` into the cartesian product of their values:
+/// * "{0.1,0.01}" sweeps over the values between the braces.
+/// * "{name}" sweeps over the values of `params["name"]`, every "{name}" placeholder
+///   takes the same value in a command. A name missing from `params` is an error.
+///
+/// "{{" and "}}" are literal braces, as in "{{\"a\": 1, \"b\": 2}}" for a JSON argument.
+/// Braces after a "$", as in "${VAR}", empty braces and braces around anything else
+/// than values or a name, as in "{print $1}", are kept.
+pub fn expand_sweep(
+    template: &str,
+    params: &BTreeMap<String, Vec<String>>,
+) -> Result<Vec<String>, SweepError> {
+    let mut segments = Vec::new();
+    let mut axes: Vec<Vec<String>> = Vec::new();
+    let mut named_axes: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut literal = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find(['{', '}']) {
+        let (before, after) = rest.split_at(start);
+        literal.push_str(before);
+        if after.starts_with("{{") || after.starts_with("}}") {
+            literal.push_str(&after[..1]);
+            rest = &after[2..];
+            continue;
+        }
+        if after.starts_with('}') || before.ends_with('$') {
+            literal.push_str(&after[..1]);
+            rest = &after[1..];
+            continue;
+        }
+        let end = match after.find('}') {
+            Some(end) => end,
+            None => {
+                rest = after;
+                break;
+            }
+        };
+        let content = &after[1..end];
+        let axis = if content.contains('{') {
+            None
+        } else if content.contains(',') {
+            axes.push(
+                content
+                    .split(',')
+                    .map(|value| value.trim().to_string())
+                    .collect(),
+            );
+            Some(axes.len() - 1)
+        } else if let Some(values) = params.get(content) {
+            if values.is_empty() {
+                return Err(SweepError::EmptyParam(content.to_string()));
+            }
+            Some(*named_axes.entry(content).or_insert_with(|| {
+                axes.push(values.clone());
+                axes.len() - 1
+            }))
+        } else if is_param_name(content) {
+            return Err(SweepError::UnknownParam(content.to_string()));
+        } else {
+            None
+        };
+        match axis {
+            Some(axis) => {
+                segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                segments.push(Segment::Axis(axis));
+            }
+            None => literal.push_str(&after[..=end]),
+        }
+        rest = &after[end + 1..];
+    }
+    literal.push_str(rest);
+    segments.push(Segment::Literal(literal));
+    if axes.is_empty() {
+        return Err(SweepError::NoPlaceholders);
+    }
+
+    let mut indices = vec![0; axes.len()];
+    let mut commands = Vec::new();
+    loop {
+        let command = segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(literal) => literal.as_str(),
+                Segment::Axis(axis) => axes[*axis][indices[*axis]].as_str(),
+            })
+            .collect::<String>();
+        commands.push(command);
+
+        // Next combination, the last placeholder changes first
+        let mut axis = axes.len();
+        loop {
+            if axis == 0 {
+                return Ok(commands);
+            }
+            axis -= 1;
+            indices[axis] += 1;
+            if indices[axis] < axes[axis].len() {
+                break;
+            }
+            indices[axis] = 0;
+        }
+    }
+}
+
+/// Whether `content` is the name of a "{name}" placeholder.
+fn is_param_name(content: &str) -> bool {
+    !content.is_empty()
+        && content
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claim::assert_err;
+
+    #[test]
+    fn expand_sweep_expands_the_cartesian_product() {
+        let template = "docker run -d img train.py --lr {0.1,0.01} --bs {32, 64}";
+        let commands = expand_sweep(template, &BTreeMap::new()).unwrap();
+        assert_eq!(
+            commands,
+            vec![
+                "docker run -d img train.py --lr 0.1 --bs 32",
+                "docker run -d img train.py --lr 0.1 --bs 64",
+                "docker run -d img train.py --lr 0.01 --bs 32",
+                "docker run -d img train.py --lr 0.01 --bs 64",
+            ]
+        );
+    }
+
+    #[test]
+    fn expand_sweep_uses_named_params() {
+        let params = parse_sweep_params("lr: [0.1, 0.01]\nresume: [true]\n").unwrap();
+        let template = "docker run -d -e LR={lr} img train.py --lr {lr} --resume {resume}";
+        let commands = expand_sweep(template, &params).unwrap();
+        assert_eq!(
+            commands,
+            vec![
+                "docker run -d -e LR=0.1 img train.py --lr 0.1 --resume true",
+                "docker run -d -e LR=0.01 img train.py --lr 0.01 --resume true",
+            ]
+        );
+    }
+
+    #[test]
+    fn expand_sweep_keeps_other_braces() {
+        let template = "docker run -d -e A=${HOME} img sh -c \"find . -exec ls {} ; echo {a,b}\"";
+        let commands = expand_sweep(template, &BTreeMap::new()).unwrap();
+        assert_eq!(
+            commands,
+            vec![
+                "docker run -d -e A=${HOME} img sh -c \"find . -exec ls {} ; echo a\"",
+                "docker run -d -e A=${HOME} img sh -c \"find . -exec ls {} ; echo b\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn expand_sweep_keeps_escaped_braces() {
+        let template =
+            "docker run -d img train.py --lr {0.1,0.01} --config '{{\"a\": 1, \"b\": 2}}'";
+        let commands = expand_sweep(template, &BTreeMap::new()).unwrap();
+        assert_eq!(
+            commands,
+            vec![
+                "docker run -d img train.py --lr 0.1 --config '{\"a\": 1, \"b\": 2}'",
+                "docker run -d img train.py --lr 0.01 --config '{\"a\": 1, \"b\": 2}'",
+            ]
+        );
+    }
+
+    #[test]
+    fn expand_sweep_rejects_unknown_params() {
+        let params = parse_sweep_params("lr: [0.1, 0.01]\n").unwrap();
+        assert_err!(expand_sweep(
+            "docker run -d img --lr {lr} --bs {bs}",
+            &params
+        ));
+    }
+
+    #[test]
+    fn expand_sweep_rejects_templates_without_placeholders() {
+        assert_err!(expand_sweep("docker run -d img", &BTreeMap::new()));
+        assert_err!(parse_sweep_params("- 0.1\n- 0.01\n"));
+    }
+}
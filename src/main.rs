@@ -1,31 +1,142 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use clap::Parser;
 use docker_queue::{
-    client::ClientApp,
+    client::{ClientApp, ListOptions, OutputFormat, QueueOptions, SortKey, StatusFilter},
     configuration::Settings,
+    domain::{
+        check_lanes, parse_at, parse_cpus, parse_delay, parse_label, parse_lane, parse_memory,
+        Lane, Resources, ResubmitOptions, SchedulingPolicy, Selector, ShutdownPolicy,
+    },
     server::Server,
     telemetry::{get_subscriber, init_subscriber},
 };
+use std::path::{Path, PathBuf};
 use tracing::debug;
+use uuid::Uuid;
 
 #[derive(Debug, Parser)]
 struct Opts {
     #[clap(short, long, default_value = "12000")]
     port: u16,
+    /// Output format: "table", "json", "yaml" or a template as "{{.id}} {{.status}}"
+    #[clap(long, global = true, default_value = "table")]
+    format: OutputFormat,
+    /// Only write ids
+    #[clap(short, long, global = true)]
+    quiet: bool,
     #[clap(subcommand)]
     subcmd: SubCommand,
 }
 
+#[derive(Debug, Parser)]
+struct ServeOptions {
+    /// Name of the server in the labels of the containers it launches, it should stay
+    /// the same between restarts to keep tracking them [default: 127.0.0.1:<port>]
+    #[clap(long)]
+    instance: Option<String>,
+    /// Number of queued containers ahead whose images are pulled while the current
+    /// container runs, 0 to disable it
+    #[clap(long, default_value = "2")]
+    prefetch: usize,
+    /// Maximum number of images pulled at the same time, at least 1
+    #[clap(long, default_value = "1", parse(try_from_str = parse_positive))]
+    prefetch_concurrency: usize,
+    /// Memory shared by the running containers, as "64g". With a capacity, the
+    /// containers with resource requests run at the same time while they fit in it
+    #[clap(long, parse(try_from_str = parse_memory))]
+    host_memory: Option<u64>,
+    /// Number of CPUs shared by the running containers
+    #[clap(long, parse(try_from_str = parse_cpus))]
+    host_cpus: Option<f64>,
+    /// Number of GPUs shared by the running containers, each one gets its own devices
+    /// among 0 to N - 1
+    #[clap(long)]
+    host_gpus: Option<u32>,
+    /// How the next container is picked: "fifo" in queue order, "round-robin" taking
+    /// turns between the owners or "fair-share" favoring the owners whose containers ran
+    /// the least in the last 24 hours. The owner is the $USER (or $LOGNAME) of the client,
+    /// which any submitter can set, so the policies only share the host between
+    /// cooperating users
+    #[clap(long, default_value = "fifo")]
+    scheduling: SchedulingPolicy,
+    /// Queue with its own FIFO as "name" or "name=concurrency", can be repeated. Without
+    /// a concurrency its containers run one at a time unless their resource requests fit
+    #[clap(long = "queue", multiple_occurrences(true), number_of_values(1), parse(try_from_str = parse_lane))]
+    queues: Vec<Lane>,
+    /// File where the state that survives restarts is saved, the drain mode, the cron
+    /// containers and on shutdown the queued and running containers
+    /// [default: ~/.docker_queue/state-<port>.json]
+    #[clap(long)]
+    state_file: Option<PathBuf>,
+    /// What happens to the running containers on SIGINT or SIGTERM: "leave" them
+    /// running, "stop" them or "requeue" them, stopped and queued again at the front.
+    /// Launches still in progress after 30 seconds are saved as queued
+    #[clap(long, default_value = "leave")]
+    on_shutdown: ShutdownPolicy,
+}
+
 #[derive(Debug, Parser)]
 enum SubCommand {
     /// List containers
     List(ListContainers),
     /// Start server
-    Serve,
+    Serve(ServeOptions),
     /// Queue container
     Queue(QueueContainer),
-    /// Remove container
-    Remove,
+    /// Remove queued containers by id, name or label
+    Remove(SelectContainers),
+    /// Pause queued containers by id, name or label
+    Pause(SelectContainers),
+    /// Queue again paused containers by id, name or label. Without any, start the queued
+    /// containers again after "drain"
+    Resume(SelectContainers),
+    /// Stop starting queued containers and let the running ones finish, until "resume"
+    Drain,
+    /// Wait until a queued container finishes and exit with its exit code, or 125 if it
+    /// failed to launch, 240 if it was cancelled and 241 if it is not found, e.g. it
+    /// finished before the server restarted. The "status" of "--format json" tells them
+    /// apart from the codes of the container
+    Wait(WaitContainer),
+    /// Show the status of a queued container, with its position and estimated start
+    Status(ContainerStatus),
+    /// Show all the details of a job, by queued container id or docker id
+    Inspect(InspectContainer),
+    /// Edit the command of a queued container in $EDITOR, it keeps its position
+    Edit(EditContainer),
+    /// Queue again the command of a finished or running container
+    Resubmit(ResubmitContainer),
+    /// Queue a container per combination of values of a command template
+    #[clap(subcommand)]
+    Sweep(SweepSubCommand),
+    /// Manage containers queued on a cron schedule
+    #[clap(subcommand)]
+    Cron(CronSubCommand),
+}
+
+#[derive(Debug, Parser)]
+enum CronSubCommand {
+    /// Queue a container on every tick of a cron schedule
+    Add(AddCronContainer),
+    /// List cron containers
+    List,
+    /// Remove a cron container
+    Remove(RemoveCronContainer),
+}
+
+#[derive(Debug, Parser)]
+enum SweepSubCommand {
+    /// Queue a container per combination of the placeholder values of a command, as
+    /// "--lr {0.1,0.01} --bs {32,64}"
+    Add(AddSweep),
+    /// List sweeps with the number of containers in each state
+    List,
+    /// Pause the queued containers of a sweep
+    Pause(SweepId),
+    /// Queue again the paused containers of a sweep
+    Resume(SweepId),
+    /// Remove the queued containers of a sweep
+    Remove(SweepId),
 }
 
 #[derive(Debug, Parser)]
@@ -33,20 +144,193 @@ struct ListContainers {
     /// Show the full command
     #[clap(long)]
     all: bool,
+    /// Also show the dependencies, schedule, position, estimated start, job and pull
+    /// status of the containers
+    #[clap(long)]
+    wide: bool,
+    /// Only show containers with this status: "running", "queued" or "paused"
+    #[clap(long)]
+    status: Option<StatusFilter>,
+    /// Hide running containers not launched by the queue
+    #[clap(long)]
+    tracked_only: bool,
+    /// Only show the queued containers of this sweep
+    #[clap(long)]
+    sweep: Option<Uuid>,
+    /// Only show containers with this label as "key=value", can be repeated
+    #[clap(long, multiple_occurrences(true), number_of_values(1), parse(try_from_str = parse_label))]
+    label: Vec<(String, String)>,
+    /// Sort by "status", "id", "image", "created" or "names"
+    #[clap(long)]
+    sort: Option<SortKey>,
+    /// Don't color the output
+    #[clap(long)]
+    no_color: bool,
 }
 
 #[derive(Debug, Parser)]
 struct QueueContainer {
-    /// A docker run command, should include a detach flag as "-d" or "--detach"
+    /// A docker run command, "--detach" is added if it has no detach flag
+    command: String,
+    /// Treats the command as a file path to read, a directory queues a container per file
+    #[clap(short, long)]
+    path: bool,
+    /// Treats the command as a batch file to read, with a command per block of lines
+    /// separated by blank lines, or a yaml list of commands
+    #[clap(short, long, conflicts_with = "path")]
+    batch: bool,
+    /// Treats the command as a yaml or toml job spec with the keys of a Docker Compose
+    /// service ("image", "command", "environment", "volumes", "gpus"...), or as a
+    /// Docker Compose file. The GPU reservations of "deploy" become "--gpus", the keys
+    /// without docker run flag, as "restart", are ignored with a warning
+    #[clap(long, conflicts_with_all = &["path", "batch"])]
+    spec: bool,
+    /// Service of the Docker Compose file to queue, if it has more than one
+    #[clap(long, requires = "spec")]
+    service: Option<String>,
+    /// The container gets queued but not started even if the queue is empty
+    #[clap(long)]
+    paused: bool,
+    /// Wait until the containers finish and exit with the first non-zero exit code. With
+    /// "--format json" or "yaml" only their outcomes are written, as a single list
+    #[clap(long)]
+    wait: bool,
+    /// Run only after the queued container with this id or name has finished
+    #[clap(long, multiple_occurrences(true), number_of_values(1))]
+    after: Vec<String>,
+    /// Run only after the queued container with this id or name has exited with code 0
+    #[clap(long, multiple_occurrences(true), number_of_values(1))]
+    after_ok: Vec<String>,
+    /// Unique name of the container, it can be used instead of the id. Not for a batch
+    /// nor a directory
+    #[clap(long, conflicts_with = "batch")]
+    name: Option<String>,
+    /// Docker label as "key=value", can be repeated
+    #[clap(long, multiple_occurrences(true), number_of_values(1), parse(try_from_str = parse_label))]
+    label: Vec<(String, String)>,
+    /// Don't run before this time, as "22:00", "2021-11-05 22:00" or RFC 3339
+    #[clap(long, parse(try_from_str = parse_at))]
+    at: Option<DateTime<Utc>>,
+    /// Don't run before this delay has passed, as "2h" or "1h 30m"
+    #[clap(long, parse(try_from_str = parse_delay), conflicts_with = "at")]
+    delay: Option<std::time::Duration>,
+    /// Expected duration, as "2h", used to estimate when the queued containers start
+    #[clap(long, parse(try_from_str = parse_delay))]
+    estimate: Option<std::time::Duration>,
+    /// Replace the env vars when the container is launched, from the env files and the
+    /// environment of the server, instead of when it is queued
+    #[clap(long)]
+    server_env: bool,
+    /// Docker env file read when the container is launched, can be repeated
+    #[clap(long, multiple_occurrences(true), number_of_values(1))]
+    env_file: Vec<PathBuf>,
+    /// Check that the image is available, that the bind mount paths exist and that the
+    /// container name is free before queueing
+    #[clap(long)]
+    check: bool,
+    /// Memory the container needs to start, as "4g", also passed as "--memory"
+    #[clap(long, parse(try_from_str = parse_memory))]
+    memory: Option<u64>,
+    /// Number of CPUs the container needs to start, also passed as "--cpus"
+    #[clap(long, parse(try_from_str = parse_cpus))]
+    cpus: Option<f64>,
+    /// Number of GPUs the container needs to start, also passed as "--gpus"
+    #[clap(long)]
+    gpus: Option<u32>,
+    /// Queue the container runs in, one of the queues of the server [default: default]
+    #[clap(long)]
+    queue: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+struct AddCronContainer {
+    /// A cron expression as "min hour day month weekday" in local time, e.g. "0 2 * * *",
+    /// weekdays are names ("Mon-Fri") or numbers as in crontab, 0 or 7 for Sunday. As in
+    /// crontab, when both the day and the weekday are restricted either one matches
+    schedule: String,
+    /// A docker run command, "--detach" is added if it has no detach flag
     command: String,
     /// Treats the command as a file path to read
     #[clap(short, long)]
     path: bool,
+}
+
+#[derive(Debug, Parser)]
+struct SelectContainers {
+    /// Ids or names of the queued containers
+    ids: Vec<String>,
+    /// Select the containers with this label as "key=value", can be repeated
+    #[clap(long, multiple_occurrences(true), number_of_values(1), parse(try_from_str = parse_label))]
+    label: Vec<(String, String)>,
+}
+
+#[derive(Debug, Parser)]
+struct AddSweep {
+    /// A docker run command with placeholders, as "{0.1,0.01}" or "{name}", "{{" and "}}"
+    /// are literal braces
+    template: String,
+    /// Treats the template as a file path to read
+    #[clap(short, long)]
+    path: bool,
+    /// A yaml file with the values of the "{name}" placeholders, as "lr: [0.1, 0.01]"
+    #[clap(long)]
+    params: Option<String>,
+    /// The containers get queued but not started even if the queue is empty
+    #[clap(long)]
+    paused: bool,
+    /// Expected duration of each container, as "2h", used to estimate start times
+    #[clap(long, parse(try_from_str = parse_delay))]
+    estimate: Option<std::time::Duration>,
+}
+
+#[derive(Debug, Parser)]
+struct SweepId {
+    /// Id of the sweep
+    id: String,
+}
+
+#[derive(Debug, Parser)]
+struct RemoveCronContainer {
+    /// Id of the cron container
+    id: String,
+}
+
+#[derive(Debug, Parser)]
+struct ContainerStatus {
+    /// Id or name of the queued container
+    id: String,
+}
+
+#[derive(Debug, Parser)]
+struct InspectContainer {
+    /// Id or name of the queued container, or id of the docker container
+    id: String,
+}
+
+#[derive(Debug, Parser)]
+struct EditContainer {
+    /// Id or name of the queued container
+    id: String,
+}
+
+#[derive(Debug, Parser)]
+struct ResubmitContainer {
+    /// Id or name of the queued container, or id of the docker container
+    id: String,
+    /// Queue the container at the front of the queue
+    #[clap(long)]
+    front: bool,
     /// The container gets queued but not started even if the queue is empty
     #[clap(long)]
     paused: bool,
 }
 
+#[derive(Debug, Parser)]
+struct WaitContainer {
+    /// Id or name of the queued container
+    id: String,
+}
+
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> Result<()> {
     let opts = Opts::parse();
@@ -54,22 +338,175 @@ async fn main() -> Result<()> {
     init_subscriber(subscriber);
     debug!("{:#?}", opts);
 
-    if let SubCommand::Serve = opts.subcmd {
-        let app = Server::build(Settings { port: opts.port })?;
+    if let SubCommand::Serve(serve_opts) = opts.subcmd {
+        check_lanes(&serve_opts.queues)?;
+        let app = Server::build(Settings {
+            port: opts.port,
+            instance: serve_opts.instance,
+            prefetch_depth: serve_opts.prefetch,
+            prefetch_concurrency: serve_opts.prefetch_concurrency,
+            capacity: Resources::new(
+                serve_opts.host_memory,
+                serve_opts.host_cpus,
+                serve_opts.host_gpus,
+            ),
+            scheduling: serve_opts.scheduling,
+            lanes: serve_opts.queues,
+            state_file: serve_opts
+                .state_file
+                .or_else(|| default_state_file(opts.port)),
+            shutdown_policy: serve_opts.on_shutdown,
+        })?;
         app.start().await?;
     } else {
-        let mut client = ClientApp::new(opts.port, std::io::stdout());
+        let format = if opts.quiet {
+            OutputFormat::Quiet
+        } else {
+            opts.format
+        };
+        let mut client = ClientApp::new(opts.port, std::io::stdout()).format(format);
         match opts.subcmd {
-            SubCommand::List(opts) => client.list_containers(opts.all).await?,
+            SubCommand::List(opts) => {
+                let options = ListOptions {
+                    show_all: opts.all,
+                    wide: opts.wide,
+                    status: opts.status,
+                    tracked_only: opts.tracked_only,
+                    sweep: opts.sweep,
+                    labels: opts.label,
+                    sort: opts.sort,
+                    no_color: opts.no_color,
+                };
+                client.list_containers_with_options(options).await?
+            }
             SubCommand::Queue(opts) => {
+                let not_before = match opts.delay {
+                    Some(delay) => Some(Utc::now() + chrono::Duration::from_std(delay)?),
+                    None => opts.at,
+                };
+                let mut after = Vec::new();
+                for id in &opts.after {
+                    after.push(client.resolve_id(id).await?);
+                }
+                let mut after_ok = Vec::new();
+                for id in &opts.after_ok {
+                    after_ok.push(client.resolve_id(id).await?);
+                }
+                let options = QueueOptions {
+                    is_path: opts.path,
+                    is_spec: opts.spec,
+                    service: opts.service,
+                    paused: opts.paused,
+                    after,
+                    after_ok,
+                    not_before,
+                    estimate: opts.estimate,
+                    name: opts.name,
+                    labels: opts.label,
+                    server_env: opts.server_env,
+                    env_files: opts.env_file,
+                    check: opts.check,
+                    resources: Resources::new(opts.memory, opts.cpus, opts.gpus),
+                    queue_name: opts.queue,
+                    wait: opts.wait,
+                };
+                let is_dir = opts.path && Path::new(&opts.command).is_dir();
+                let ids = if opts.batch || is_dir {
+                    client
+                        .queue_containers_with_options(opts.command, options)
+                        .await?
+                } else {
+                    vec![
+                        client
+                            .queue_container_with_options(opts.command, options)
+                            .await?,
+                    ]
+                };
+                if opts.wait {
+                    std::process::exit(client.wait_containers(&ids).await?);
+                }
+            }
+            SubCommand::Remove(opts) => {
                 client
-                    .queue_container(opts.command, opts.path, opts.paused)
-                    .await?
+                    .remove_containers(&Selector::new(opts.ids, opts.label))
+                    .await?;
             }
-            SubCommand::Remove => todo!(),
-            SubCommand::Serve => {}
+            SubCommand::Pause(opts) => {
+                client
+                    .pause_containers(&Selector::new(opts.ids, opts.label))
+                    .await?;
+            }
+            SubCommand::Resume(opts) => {
+                let selector = Selector::new(opts.ids, opts.label);
+                if selector.is_empty() {
+                    client.resume_server().await?;
+                } else {
+                    client.resume_containers(&selector).await?;
+                }
+            }
+            SubCommand::Drain => {
+                client.drain_server().await?;
+            }
+            SubCommand::Wait(opts) => std::process::exit(client.wait_container(&opts.id).await?),
+            SubCommand::Status(opts) => client.container_status(&opts.id).await?,
+            SubCommand::Inspect(opts) => client.inspect_container(&opts.id).await?,
+            SubCommand::Edit(opts) => client.edit_container(&opts.id).await?,
+            SubCommand::Resubmit(opts) => {
+                let options = ResubmitOptions {
+                    front: opts.front,
+                    paused: opts.paused,
+                };
+                client.resubmit_container(&opts.id, options).await?;
+            }
+            SubCommand::Sweep(SweepSubCommand::Add(opts)) => {
+                let options = QueueOptions {
+                    is_path: opts.path,
+                    paused: opts.paused,
+                    estimate: opts.estimate,
+                    ..Default::default()
+                };
+                client
+                    .sweep_containers(opts.template, opts.params, options)
+                    .await?;
+            }
+            SubCommand::Sweep(SweepSubCommand::List) => client.list_sweeps().await?,
+            SubCommand::Sweep(SweepSubCommand::Pause(opts)) => client.pause_sweep(&opts.id).await?,
+            SubCommand::Sweep(SweepSubCommand::Resume(opts)) => {
+                client.resume_sweep(&opts.id).await?
+            }
+            SubCommand::Sweep(SweepSubCommand::Remove(opts)) => {
+                client.remove_sweep(&opts.id).await?
+            }
+            SubCommand::Cron(CronSubCommand::Add(opts)) => {
+                client
+                    .add_cron_container(opts.schedule, opts.command, opts.path)
+                    .await?;
+            }
+            SubCommand::Cron(CronSubCommand::List) => client.list_cron_containers().await?,
+            SubCommand::Cron(CronSubCommand::Remove(opts)) => {
+                client.remove_cron_container(&opts.id).await?
+            }
+            SubCommand::Serve(_) => {}
         }
     }
 
     Ok(())
 }
+
+/// Parses a number greater than 0.
+fn parse_positive(s: &str) -> Result<usize, String> {
+    s.parse::<usize>()
+        .ok()
+        .filter(|&n| n > 0)
+        .ok_or_else(|| format!("Expected a number greater than 0: {:?}", s))
+}
+
+/// Get "~/.docker_queue/state-<port>.json", `None` without a home directory.
+fn default_state_file(port: u16) -> Option<PathBuf> {
+    let home = std::env::var_os("HOME")?;
+    Some(
+        PathBuf::from(home)
+            .join(".docker_queue")
+            .join(format!("state-{}.json", port)),
+    )
+}
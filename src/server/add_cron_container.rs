@@ -0,0 +1,17 @@
+use super::{ServerError, State};
+use crate::domain::CronContainer;
+use axum::{extract::Extension, Json};
+use std::sync::Arc;
+
+#[tracing::instrument(name = "Add cron container", skip(state, cron_container), fields(cron_container = %cron_container.id()))]
+pub(super) async fn add_cron_container(
+    Json(cron_container): Json<CronContainer>,
+    Extension(state): Extension<Arc<State>>,
+) -> Result<(), ServerError> {
+    cron_container
+        .validate()
+        .map_err(|error| ServerError::InvalidRequest(error.to_string()))?;
+    state.cron_containers.lock().unwrap().push(cron_container);
+    state.save_state()?;
+    Ok(())
+}
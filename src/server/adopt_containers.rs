@@ -0,0 +1,178 @@
+use super::{get_running_containers, State};
+use crate::domain::{
+    FinishedContainer, FinishedOutcome, LaunchedContainer, QueuedContainer, RunningContainerId,
+    JOB_ID_LABEL, QUEUE_LABEL,
+};
+use anyhow::Result;
+use bollard::{models::ContainerSummaryInner, Docker};
+use chrono::{TimeZone, Utc};
+use tracing::info;
+use uuid::Uuid;
+
+impl State {
+    /// Makes the containers this server launched before a restart its running containers
+    /// again, from the saved running containers and the labels of the docker containers.
+    /// The saved ones that stopped meanwhile finish. Returns the ids of the running
+    /// containers, to wait for them.
+    #[tracing::instrument(name = "Adopt running containers", skip(self))]
+    pub(super) async fn adopt_running_containers(&self) -> Result<Vec<RunningContainerId>> {
+        let labeled = get_running_containers()
+            .await?
+            .into_iter()
+            .filter(|container| self.is_launched_here(container))
+            .collect::<Vec<_>>();
+        let stopped = self.adopt(&labeled);
+        for launched in stopped {
+            let finished = match get_exit_code(launched.id()).await {
+                Some(exit_code) => launched.finish(exit_code),
+                None => {
+                    let outcome = FinishedOutcome::Cancelled(
+                        "Removed while the server was down.".to_string(),
+                    );
+                    let started_at = launched.started_at();
+                    let id = launched.id().clone();
+                    FinishedContainer::new(launched.container().clone(), Some(id), outcome)
+                        .with_started_at(started_at)
+                }
+            };
+            info!(
+                "{} stopped while the server was down.",
+                finished.container().id()
+            );
+            self.finished_containers.lock().unwrap().push(finished);
+        }
+
+        let ids = self
+            .running_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|running| running.id().clone())
+            .collect::<Vec<_>>();
+        info!("Adopted {} running containers.", ids.len());
+        Ok(ids)
+    }
+
+    /// Replaces the running containers with the `labeled` ones, the saved running
+    /// container with the same id or else one rebuilt from the labels. A container saved
+    /// as queued because its launch didn't end before the shutdown leaves the queue when
+    /// it is running. Returns the saved running containers that are not running anymore.
+    fn adopt(&self, labeled: &[ContainerSummaryInner]) -> Vec<LaunchedContainer> {
+        let mut queued_containers = self.queued_containers.lock().unwrap();
+        let mut running_containers = self.running_containers.lock().unwrap();
+        let (mut adopted, stopped) =
+            running_containers
+                .drain(..)
+                .partition::<Vec<_>, _>(|running| {
+                    labeled
+                        .iter()
+                        .any(|container| container.id.as_deref() == Some(running.id().as_ref()))
+                });
+        for container in labeled {
+            let is_saved = adopted
+                .iter()
+                .any(|running| container.id.as_deref() == Some(running.id().as_ref()));
+            if !is_saved {
+                adopted.extend(rebuild_launched_container(container));
+            }
+        }
+        queued_containers.retain(|queued| {
+            !adopted
+                .iter()
+                .any(|running| running.container().id() == queued.id())
+        });
+        running_containers.extend(adopted);
+        stopped
+    }
+}
+
+/// Rebuilds the launched container of a running container that was not saved, from its
+/// labels. Only its job id, image, command, queue and start time are known.
+fn rebuild_launched_container(container: &ContainerSummaryInner) -> Option<LaunchedContainer> {
+    let labels = container.labels.as_ref()?;
+    let job_id = labels.get(JOB_ID_LABEL)?.parse::<Uuid>().ok()?;
+    let id = container.id.as_ref()?;
+    let image = container.image.as_deref().unwrap_or_default();
+    let command = container.command.as_deref().unwrap_or_default();
+    // The command as docker shows it might not parse back, the image is enough then
+    let mut queued = QueuedContainer::new(format!("docker run -d {} {}", image, command))
+        .or_else(|_| QueuedContainer::new(format!("docker run -d {}", image)))
+        .ok()?;
+    queued.set_id(job_id);
+    if let Some(queue) = labels.get(QUEUE_LABEL) {
+        queued.set_queue_name(queue).ok()?;
+    }
+    let started_at = container
+        .created
+        .map(|created| Utc.timestamp(created, 0))
+        .unwrap_or_else(Utc::now);
+    Some(LaunchedContainer::new(RunningContainerId::new(id), queued).with_started_at(started_at))
+}
+
+/// Get the exit code of the stopped container `id`, `None` if it was removed.
+async fn get_exit_code(id: &RunningContainerId) -> Option<i64> {
+    let docker = Docker::connect_with_local_defaults().ok()?;
+    docker
+        .inspect_container(id.as_ref(), None)
+        .await
+        .ok()?
+        .state?
+        .exit_code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::SERVER_LABEL;
+    use std::collections::HashMap;
+
+    fn summary(id: &str, job_id: &str) -> ContainerSummaryInner {
+        ContainerSummaryInner {
+            id: Some(id.to_string()),
+            image: Some("some_image".to_string()),
+            command: Some("sleep 60".to_string()),
+            created: Some(1_600_000_000),
+            labels: Some(HashMap::from([
+                (SERVER_LABEL.to_string(), "127.0.0.1:8000".to_string()),
+                (JOB_ID_LABEL.to_string(), job_id.to_string()),
+                (QUEUE_LABEL.to_string(), "gpu0".to_string()),
+            ])),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn adopt_keeps_the_saved_containers_and_rebuilds_the_others() {
+        let state = State::new().with_instance("127.0.0.1:8000");
+        let saved = QueuedContainer::new("docker run -d saved_image").unwrap();
+        let stopped = QueuedContainer::new("docker run -d stopped_image").unwrap();
+        state.running_containers.lock().unwrap().extend([
+            LaunchedContainer::new(RunningContainerId::new("a"), saved.clone()),
+            LaunchedContainer::new(RunningContainerId::new("b"), stopped.clone()),
+        ]);
+        let job_id = Uuid::new_v4();
+        // Saved as queued, its launch was still in progress on shutdown
+        let mut launched = QueuedContainer::new("docker run -d some_image sleep 60").unwrap();
+        launched.set_id(job_id);
+        state.queued_containers.lock().unwrap().push_back(launched);
+        let job_id = job_id.to_string();
+
+        let stopped_containers = state.adopt(&[summary("a", &saved.id()), summary("c", &job_id)]);
+
+        assert_eq!(1, stopped_containers.len());
+        assert_eq!(stopped.id(), stopped_containers[0].container().id());
+        assert!(state.queued_containers.lock().unwrap().is_empty());
+        let running_containers = state.running_containers.lock().unwrap();
+        assert_eq!(2, running_containers.len());
+        assert_eq!(&saved, running_containers[0].container());
+        let rebuilt = &running_containers[1];
+        assert_eq!("c", rebuilt.id().as_ref());
+        assert_eq!(job_id, rebuilt.container().id());
+        assert_eq!(
+            "docker run -d some_image sleep 60",
+            rebuilt.container().command()
+        );
+        assert_eq!("gpu0", rebuilt.container().queue_name());
+        assert_eq!(Utc.timestamp(1_600_000_000, 0), rebuilt.started_at());
+    }
+}
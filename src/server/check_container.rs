@@ -0,0 +1,136 @@
+use super::{ServerError, State};
+use crate::domain::{bind_mounts, container_name, PreflightIssue, QueuedContainer};
+use axum::{extract::Extension, Json};
+use bollard::{errors::Error as DockerError, Docker};
+use std::sync::Arc;
+
+#[tracing::instrument(name = "Check container", skip(state, queued_container), fields(container = %queued_container.id()))]
+pub(super) async fn check_container(
+    Json(queued_container): Json<QueuedContainer>,
+    Extension(state): Extension<Arc<State>>,
+) -> Result<Json<Vec<PreflightIssue>>, ServerError> {
+    Ok(Json(state.check_container(&queued_container).await))
+}
+
+impl State {
+    /// Finds the problems that would make the container fail when launched: an image
+    /// that is not available locally, bind mount paths that don't exist, and a container
+    /// name in use by another container or queued container.
+    pub(super) async fn check_container(&self, container: &QueuedContainer) -> Vec<PreflightIssue> {
+        let spec = match container.run_spec() {
+            Ok(spec) => spec,
+            Err(error) => return vec![PreflightIssue::error(error.to_string())],
+        };
+        let mut issues = Vec::new();
+
+        for mount in bind_mounts(&spec) {
+            if mount.source.exists() {
+                continue;
+            }
+            let message = format!("Bind mount path {:?} doesn't exist", mount.source);
+            if mount.is_created {
+                issues.push(PreflightIssue::warning(format!(
+                    "{}, docker will create an empty directory",
+                    message
+                )));
+            } else {
+                issues.push(PreflightIssue::error(message));
+            }
+        }
+
+        let name = container_name(&spec);
+        if let Some(name) = name {
+            if self.is_container_name_queued(name, &container.id()) {
+                issues.push(PreflightIssue::error(format!(
+                    "Container name {:?} is used by another queued container",
+                    name
+                )));
+            }
+        }
+
+        let docker = match Docker::connect_with_local_defaults() {
+            Ok(docker) => docker,
+            Err(error) => {
+                issues.push(PreflightIssue::warning(format!(
+                    "Couldn't connect to docker to check the image and the name: {}",
+                    error
+                )));
+                return issues;
+            }
+        };
+        if let Some(image) = spec.image() {
+            match docker.inspect_image(image).await {
+                Ok(_) => {}
+                Err(DockerError::DockerResponseNotFoundError { .. }) => {
+                    issues.push(PreflightIssue::warning(format!(
+                        "Image {:?} is not available locally, it will be pulled at launch",
+                        image
+                    )));
+                }
+                Err(error) => issues.push(PreflightIssue::warning(format!(
+                    "Couldn't check the image {:?}: {}",
+                    image, error
+                ))),
+            }
+        }
+        if let Some(name) = name {
+            match docker.inspect_container(name, None).await {
+                Ok(_) => issues.push(PreflightIssue::error(format!(
+                    "Container name {:?} is used by an existing container",
+                    name
+                ))),
+                Err(DockerError::DockerResponseNotFoundError { .. }) => {}
+                Err(error) => issues.push(PreflightIssue::warning(format!(
+                    "Couldn't check the container name {:?}: {}",
+                    name, error
+                ))),
+            }
+        }
+        issues
+    }
+
+    /// Whether a queued container other than `id` runs with the container name `name`.
+    fn is_container_name_queued(&self, name: &str, id: &str) -> bool {
+        self.queued_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|queued| queued.id() != id)
+            .filter_map(|queued| queued.run_spec().ok())
+            .any(|spec| container_name(&spec) == Some(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Severity;
+
+    #[tokio::test]
+    async fn check_container_finds_paths_and_names() {
+        let state = State::new();
+        let queued = QueuedContainer::new("docker run -d --name job img").unwrap();
+        state.queued_containers.lock().unwrap().push_back(queued);
+
+        let command = "docker run -d --name job -v /docker_queue_missing:/a \
+            --mount type=bind,src=/docker_queue_missing,dst=/b -v /tmp:/c img";
+        let container = QueuedContainer::new(command).unwrap();
+        let issues = state.check_container(&container).await;
+
+        let errors = issues
+            .iter()
+            .filter(|issue| issue.severity() == Severity::Error)
+            .map(PreflightIssue::message)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            vec![
+                "Bind mount path \"/docker_queue_missing\" doesn't exist",
+                "Container name \"job\" is used by another queued container",
+            ]
+        );
+        assert!(issues.iter().any(|issue| issue
+            .message()
+            .contains("docker will create an empty directory")));
+    }
+}
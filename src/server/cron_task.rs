@@ -0,0 +1,75 @@
+use super::{State, TaskMessage};
+use chrono::{DateTime, Utc};
+use std::{sync::Arc, time::Duration};
+use tokio::{sync::mpsc, time::interval};
+use tracing::info;
+
+const CRON_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+#[tracing::instrument(name = "Cron task", skip(state, tx))]
+pub(super) async fn start_cron_task(state: Arc<State>, tx: mpsc::Sender<TaskMessage>) {
+    let mut interval = interval(CRON_CHECK_INTERVAL);
+    let mut last_check = Utc::now();
+    loop {
+        interval.tick().await;
+        let now = Utc::now();
+        if state.queue_due_cron_containers(last_check, now) {
+            tx.send(TaskMessage::CheckRun)
+                .await
+                .expect("Receiver dropped.");
+        }
+        last_check = now;
+    }
+}
+
+impl State {
+    /// Queues a new container for every cron container with a tick in `(since, now]`,
+    /// returns whether any container was queued.
+    fn queue_due_cron_containers(&self, since: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        let due_containers = self
+            .cron_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|cron_container| {
+                matches!(cron_container.next_after(since), Some(next) if next <= now)
+            })
+            .map(|cron_container| {
+                let container = cron_container.instantiate();
+                info!(
+                    "Queuing {} for cron container {}.",
+                    container.id(),
+                    cron_container.id()
+                );
+                container
+            })
+            .collect::<Vec<_>>();
+        let any_due = !due_containers.is_empty();
+        self.queued_containers
+            .lock()
+            .unwrap()
+            .extend(due_containers);
+        any_due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{CronContainer, QueuedContainer};
+    use chrono::{Local, TimeZone};
+
+    #[test]
+    fn queue_due_cron_containers_queues_on_ticks() {
+        let state = State::new();
+        let container = QueuedContainer::new("docker run -d some_image").unwrap();
+        let cron_container = CronContainer::new("0 2 * * *", container).unwrap();
+        state.cron_containers.lock().unwrap().push(cron_container);
+        let time = |h, m| Local.ymd(2021, 11, 6).and_hms(h, m, 0).with_timezone(&Utc);
+
+        assert!(!state.queue_due_cron_containers(time(1, 0), time(1, 59)));
+        assert!(state.queue_due_cron_containers(time(1, 59), time(2, 0)));
+        assert!(!state.queue_due_cron_containers(time(2, 0), time(2, 1)));
+        assert_eq!(1, state.queued_containers.lock().unwrap().len());
+    }
+}
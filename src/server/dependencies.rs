@@ -0,0 +1,105 @@
+use super::State;
+use crate::domain::{DependencyState, FinishedContainer, FinishedOutcome, QueuedContainer};
+use tracing::info;
+
+impl State {
+    /// Get the combined state of the dependencies of `container`.
+    pub(super) fn dependency_state(&self, container: &QueuedContainer) -> DependencyState {
+        DependencyState::all(container.dependencies().iter().map(|dependency| {
+            let status = self.get_container_status(&dependency.id());
+            dependency.state(status.as_ref())
+        }))
+    }
+
+    /// Moves the queued containers whose dependencies failed to the finished containers,
+    /// this repeats until no more containers are cancelled, since cancelling a container
+    /// fails the dependencies on it.
+    #[tracing::instrument(name = "Cancel blocked containers", skip(self))]
+    pub(super) fn cancel_blocked_containers(&self) {
+        loop {
+            let queued_containers = { self.queued_containers.lock().unwrap().clone() };
+            let blocked = queued_containers
+                .into_iter()
+                .filter(|container| self.dependency_state(container) == DependencyState::Failed)
+                .collect::<Vec<_>>();
+            if blocked.is_empty() {
+                break;
+            }
+
+            self.queued_containers
+                .lock()
+                .unwrap()
+                .retain(|container| !blocked.contains(container));
+            let mut finished_containers = self.finished_containers.lock().unwrap();
+            for container in blocked {
+                info!("Cancelling {}, a dependency failed.", container.id());
+                let outcome = FinishedOutcome::Cancelled("A dependency failed.".to_string());
+                finished_containers.push(FinishedContainer::new(container, None, outcome));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        ContainerStatus, Dependency, DependencyCondition, LaunchedContainer, RunningContainerId,
+    };
+
+    fn queued_container_after(
+        dependency: &QueuedContainer,
+        condition: DependencyCondition,
+    ) -> QueuedContainer {
+        let mut container = QueuedContainer::new("docker run -d some_image").unwrap();
+        let id = dependency.id().parse().unwrap();
+        container.add_dependency(Dependency::new(id, condition));
+        container
+    }
+
+    #[test]
+    fn cancel_blocked_containers_cascades() {
+        let state = State::new();
+        let first = QueuedContainer::new("docker run -d some_image").unwrap();
+        let second = queued_container_after(&first, DependencyCondition::Succeeded);
+        let third = queued_container_after(&second, DependencyCondition::Finished);
+        let launched = LaunchedContainer::new(RunningContainerId::new("123456"), first);
+        state
+            .finished_containers
+            .lock()
+            .unwrap()
+            .push(launched.finish(1));
+        state
+            .queued_containers
+            .lock()
+            .unwrap()
+            .extend([second.clone(), third.clone()]);
+
+        state.cancel_blocked_containers();
+
+        assert!(state.queued_containers.lock().unwrap().is_empty());
+        for container in [second, third] {
+            assert!(matches!(
+                state.get_container_status(&container.id()),
+                Some(ContainerStatus::Finished(FinishedOutcome::Cancelled(_)))
+            ));
+        }
+    }
+
+    #[test]
+    fn dependency_state_is_pending_while_dependency_runs() {
+        let state = State::new();
+        let first = QueuedContainer::new("docker run -d some_image").unwrap();
+        let second = queued_container_after(&first, DependencyCondition::Finished);
+        state
+            .running_containers
+            .lock()
+            .unwrap()
+            .push(LaunchedContainer::new(
+                RunningContainerId::new("123456"),
+                first,
+            ));
+
+        assert_eq!(DependencyState::Pending, state.dependency_state(&second));
+    }
+}
@@ -0,0 +1,124 @@
+use super::{ServerError, State};
+use crate::domain::QueuedContainer;
+use axum::{
+    extract::{Extension, Path},
+    Json,
+};
+use std::sync::Arc;
+
+#[tracing::instrument(name = "Edit container", skip(state, container))]
+pub(super) async fn edit_container(
+    Path(id): Path<String>,
+    Json(container): Json<QueuedContainer>,
+    Extension(state): Extension<Arc<State>>,
+) -> Result<(), ServerError> {
+    state.edit_container(&state.resolve_id(&id), &container)
+}
+
+impl State {
+    /// Replaces the command of the queued container `id` with the one of `container`,
+    /// it keeps its id and position in the queue. The edited container goes through the
+    /// checks of the queued ones.
+    pub(super) fn edit_container(
+        &self,
+        id: &str,
+        container: &QueuedContainer,
+    ) -> Result<(), ServerError> {
+        {
+            let mut queued_containers = self.queued_containers.lock().unwrap();
+            if let Some(queued) = queued_containers
+                .iter_mut()
+                .find(|queued| queued.id() == id && !queued.is_launching())
+            {
+                let mut edited = queued.clone();
+                edited
+                    .replace_command(container)
+                    .map_err(|error| ServerError::InvalidRequest(error.to_string()))?;
+                self.check_resources(std::slice::from_ref(&edited))?;
+                self.check_queues(std::slice::from_ref(&edited))?;
+                *queued = edited;
+                return Ok(());
+            }
+        }
+
+        match self.get_container_status(id) {
+            Some(status) => Err(ServerError::InvalidRequest(format!(
+                "Only queued containers can be edited, container {} is {}.",
+                id, status
+            ))),
+            None => Err(ServerError::NotFound(format!(
+                "Container {} not found.",
+                id
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{LaunchedContainer, Resources, RunningContainerId};
+
+    #[test]
+    fn edit_container_keeps_id_and_position() {
+        let state = State::new();
+        let first = QueuedContainer::new("docker run -d first_image").unwrap();
+        let second = QueuedContainer::new("docker run -d second_image").unwrap();
+        state
+            .queued_containers
+            .lock()
+            .unwrap()
+            .extend([first.clone(), second.clone()]);
+
+        let edited = QueuedContainer::new("docker run -d edited_image").unwrap();
+        state.edit_container(&first.id(), &edited).unwrap();
+
+        let queued_containers = state.queued_containers.lock().unwrap();
+        assert_eq!(first.id(), queued_containers[0].id());
+        assert_eq!("docker run -d edited_image", queued_containers[0].command());
+        assert_eq!(&second, &queued_containers[1]);
+    }
+
+    #[test]
+    fn edit_container_rejects_flags_of_the_resource_requests() {
+        let state = State::new();
+        let mut container = QueuedContainer::new("docker run -d some_image").unwrap();
+        container
+            .set_resources(Resources::new(None, None, Some(1)))
+            .unwrap();
+        state
+            .queued_containers
+            .lock()
+            .unwrap()
+            .push_back(container.clone());
+
+        let edited = QueuedContainer::new("docker run -d --gpus all some_image").unwrap();
+        assert!(matches!(
+            state.edit_container(&container.id(), &edited),
+            Err(ServerError::InvalidRequest(_))
+        ));
+        let edited = QueuedContainer::new("docker run -d --memory 1g some_image").unwrap();
+        assert!(state.edit_container(&container.id(), &edited).is_ok());
+
+        let queued_containers = state.queued_containers.lock().unwrap();
+        assert_eq!(edited.command(), queued_containers[0].command());
+        assert_eq!(container.resources(), queued_containers[0].resources());
+    }
+
+    #[test]
+    fn edit_container_rejects_running_and_unknown_containers() {
+        let state = State::new();
+        let container = QueuedContainer::new("docker run -d some_image").unwrap();
+        let launched = LaunchedContainer::new(RunningContainerId::new("123"), container.clone());
+        state.running_containers.lock().unwrap().push(launched);
+
+        assert!(matches!(
+            state.edit_container(&container.id(), &container),
+            Err(ServerError::InvalidRequest(_))
+        ));
+        assert!(matches!(
+            state.edit_container("unknown", &container),
+            Err(ServerError::NotFound(_))
+        ));
+    }
+}
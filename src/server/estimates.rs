@@ -0,0 +1,205 @@
+use super::State;
+use crate::domain::{DependencyState, FinishedOutcome, QueueInfo, QueuedContainer, ServerMode};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+
+/// Number of the latest finished containers used to estimate durations.
+const HISTORY_LEN: usize = 10;
+
+impl State {
+    /// Estimates how long `container` will run, from the user estimate or else the mean
+    /// duration of the latest finished containers with the same image, or any image.
+    pub(super) fn estimate_duration(&self, container: &QueuedContainer) -> Option<Duration> {
+        if let Some(estimate) = container.estimate() {
+            return Duration::from_std(estimate).ok();
+        }
+
+        let image = container.image();
+        let finished_containers = self.finished_containers.lock().unwrap();
+        let latest_durations = |same_image: bool| {
+            finished_containers
+                .iter()
+                .rev()
+                .filter(|finished| matches!(finished.outcome(), FinishedOutcome::Exited(_)))
+                .filter(|finished| !same_image || finished.container().image() == image)
+                .filter_map(|finished| finished.duration())
+                .take(HISTORY_LEN)
+                .collect::<Vec<_>>()
+        };
+        let mut durations = latest_durations(true);
+        if durations.is_empty() {
+            durations = latest_durations(false);
+        }
+        if durations.is_empty() {
+            return None;
+        }
+        let total = durations
+            .iter()
+            .fold(Duration::zero(), |total, &duration| total + duration);
+        Some(total / durations.len() as i32)
+    }
+
+    /// Get the queue info of every queued container, in queue order. Positions and
+    /// start times are within the queue of each container, the paused containers and
+    /// the ones whose dependencies failed have none and don't delay the others.
+    pub(super) fn get_queue_infos(&self, now: DateTime<Utc>) -> Vec<(QueuedContainer, QueueInfo)> {
+        let running_containers = { self.running_containers.lock().unwrap().clone() };
+        let is_draining = self.mode() == ServerMode::Draining;
+        // A queue starts once every running container of the queue finished, as a
+        // conservative estimate when several run at the same time
+        let queue_start = |queue_name: &str| {
+            // Nothing starts before the server resumes
+            if is_draining {
+                return None;
+            }
+            running_containers
+                .iter()
+                .filter(|running| running.container().queue_name() == queue_name)
+                .map(|running| {
+                    self.estimate_duration(running.container()).map(|estimate| {
+                        let remaining = estimate - (now - running.started_at());
+                        now + remaining.max(Duration::zero())
+                    })
+                })
+                .try_fold(now, |next_start, end| end.map(|end| next_start.max(end)))
+        };
+        // Position and next start time of each queue
+        let mut lanes: HashMap<String, (usize, Option<DateTime<Utc>>)> = HashMap::new();
+
+        let queued_containers = { self.queued_containers.lock().unwrap().clone() };
+        queued_containers
+            .into_iter()
+            .map(|container| {
+                let dependency_state = self.dependency_state(&container);
+                let pull_status = self.pull_status(&container);
+                if !container.is_queued() || dependency_state == DependencyState::Failed {
+                    let info =
+                        QueueInfo::new(dependency_state, None, None).with_pull_status(pull_status);
+                    return (container, info);
+                }
+                let (position, next_start) = lanes
+                    .entry(container.queue_name().to_string())
+                    .or_insert_with(|| (0, queue_start(container.queue_name())));
+                *position += 1;
+                let estimated_start = next_start.map(|start| match container.not_before() {
+                    Some(not_before) => start.max(not_before),
+                    None => start,
+                });
+                *next_start = estimated_start
+                    .zip(self.estimate_duration(&container))
+                    .map(|(start, duration)| start + duration);
+                let info = QueueInfo::new(dependency_state, Some(*position), estimated_start)
+                    .with_pull_status(pull_status);
+                (container, info)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        Dependency, DependencyCondition, FinishedContainer, LaunchedContainer, RunningContainerId,
+    };
+    use uuid::Uuid;
+
+    fn finished_container(command: &str, minutes: i64) -> crate::domain::FinishedContainer {
+        let container = QueuedContainer::new(command).unwrap();
+        let launched = LaunchedContainer::new(RunningContainerId::new("123456"), container);
+        let started_at = launched.started_at() - Duration::minutes(minutes);
+        launched.finish(0).with_started_at(started_at)
+    }
+
+    #[test]
+    fn estimate_duration_prefers_user_estimate_then_same_image() {
+        let state = State::new();
+        let mut container = QueuedContainer::new("docker run -d image_a").unwrap();
+        assert_eq!(None, state.estimate_duration(&container));
+
+        state.finished_containers.lock().unwrap().extend([
+            finished_container("docker run -d image_a", 10),
+            finished_container("docker run -d image_a", 20),
+            finished_container("docker run -d image_b", 60),
+        ]);
+        let estimate = state.estimate_duration(&container).unwrap();
+        assert_eq!(15, estimate.num_minutes());
+
+        let other = QueuedContainer::new("docker run -d image_c").unwrap();
+        let estimate = state.estimate_duration(&other).unwrap();
+        assert_eq!(30, estimate.num_minutes());
+
+        container.set_estimate(std::time::Duration::from_secs(7200));
+        let estimate = state.estimate_duration(&container).unwrap();
+        assert_eq!(120, estimate.num_minutes());
+    }
+
+    #[test]
+    fn get_queue_infos_accumulates_estimates() {
+        let state = State::new();
+        let mut running = QueuedContainer::new("docker run -d some_image").unwrap();
+        running.set_estimate(std::time::Duration::from_secs(3600));
+        state
+            .running_containers
+            .lock()
+            .unwrap()
+            .push(LaunchedContainer::new(
+                RunningContainerId::new("123456"),
+                running,
+            ));
+        let queued = |estimate_secs: Option<u64>| {
+            let mut container = QueuedContainer::new("docker run -d some_image").unwrap();
+            if let Some(secs) = estimate_secs {
+                container.set_estimate(std::time::Duration::from_secs(secs));
+            }
+            container.queue();
+            container
+        };
+        let first = queued(Some(1800));
+        let mut paused = queued(Some(1800));
+        paused.pause();
+        let cancelled = queued(None);
+        let mut blocked = queued(Some(1800));
+        blocked.add_dependency(Dependency::new(
+            Uuid::parse_str(&cancelled.id()).unwrap(),
+            DependencyCondition::Finished,
+        ));
+        let outcome = FinishedOutcome::Cancelled("Removed.".to_string());
+        state
+            .finished_containers
+            .lock()
+            .unwrap()
+            .push(FinishedContainer::new(cancelled, None, outcome));
+        let second = queued(None);
+        let third = queued(None);
+        let mut other_queue = queued(None);
+        other_queue.set_queue_name("cpu").unwrap();
+        state.queued_containers.lock().unwrap().extend([
+            first,
+            paused,
+            other_queue,
+            blocked,
+            second,
+            third,
+        ]);
+
+        let now = Utc::now();
+        let infos = state.get_queue_infos(now);
+
+        let positions = infos.iter().map(|(_, info)| info.position());
+        assert_eq!(
+            vec![Some(1), None, Some(1), None, Some(2), Some(3)],
+            positions.collect::<Vec<_>>()
+        );
+        let starts = infos
+            .iter()
+            .map(|(_, info)| {
+                info.estimated_start()
+                    .map(|start| (start - now).num_minutes())
+            })
+            .collect::<Vec<_>>();
+        // The paused and blocked containers don't delay the second one, which has no
+        // estimate, nor history to get one. The container of the other queue starts now
+        assert_eq!(vec![Some(59), None, Some(0), None, Some(89), None], starts);
+    }
+}
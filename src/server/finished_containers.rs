@@ -0,0 +1,86 @@
+use super::State;
+use std::collections::HashSet;
+
+/// Maximum number of finished containers kept for the status of the containers, the
+/// dependencies and the estimates, the oldest ones are forgotten.
+const MAX_FINISHED_CONTAINERS: usize = 1000;
+/// Maximum number of outcomes of pruned finished containers kept for their status.
+const MAX_PRUNED_OUTCOMES: usize = 100_000;
+
+impl State {
+    /// Forgets the oldest finished containers past `MAX_FINISHED_CONTAINERS`, but the
+    /// ones a queued container depends on, and keeps only their outcomes.
+    pub(super) fn prune_finished_containers(&self) {
+        let depended_on = self
+            .queued_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|container| {
+                container
+                    .dependencies()
+                    .iter()
+                    .map(|dependency| dependency.id())
+            })
+            .collect::<HashSet<_>>();
+        let mut pruned = Vec::new();
+        {
+            let mut finished_containers = self.finished_containers.lock().unwrap();
+            let mut excess = finished_containers
+                .len()
+                .saturating_sub(MAX_FINISHED_CONTAINERS);
+            finished_containers.retain(|finished| {
+                if excess == 0 || depended_on.contains(&finished.container().id()) {
+                    return true;
+                }
+                excess -= 1;
+                pruned.push((finished.container().id(), finished.outcome().clone()));
+                false
+            });
+        }
+        let mut pruned_outcomes = self.pruned_outcomes.lock().unwrap();
+        pruned_outcomes.extend(pruned);
+        let excess = pruned_outcomes.len().saturating_sub(MAX_PRUNED_OUTCOMES);
+        pruned_outcomes.drain(..excess);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        Dependency, DependencyCondition, FinishedContainer, FinishedOutcome, QueuedContainer,
+    };
+
+    #[test]
+    fn prune_finished_containers_keeps_the_dependencies() {
+        let state = State::new();
+        let finished = (0..MAX_FINISHED_CONTAINERS + 2)
+            .map(|_| {
+                let container = QueuedContainer::new("docker run -d some_image").unwrap();
+                FinishedContainer::new(container, None, FinishedOutcome::Exited(0))
+            })
+            .collect::<Vec<_>>();
+        let mut dependent = QueuedContainer::new("docker run -d some_image").unwrap();
+        let id = finished[0].container().id().parse().unwrap();
+        dependent.add_dependency(Dependency::new(id, DependencyCondition::Succeeded));
+        state.queued_containers.lock().unwrap().push_back(dependent);
+        state
+            .finished_containers
+            .lock()
+            .unwrap()
+            .extend(finished.clone());
+
+        state.prune_finished_containers();
+
+        let finished_containers = state.finished_containers.lock().unwrap();
+        assert_eq!(MAX_FINISHED_CONTAINERS, finished_containers.len());
+        let id = |finished: &FinishedContainer| finished.container().id();
+        assert_eq!(id(&finished[0]), id(&finished_containers[0]));
+        assert_eq!(id(&finished[3]), id(&finished_containers[1]));
+        let pruned_outcomes = state.pruned_outcomes.lock().unwrap();
+        assert_eq!(2, pruned_outcomes.len());
+        assert_eq!(id(&finished[1]), pruned_outcomes[0].0);
+        assert_eq!(FinishedOutcome::Exited(0), pruned_outcomes[0].1);
+    }
+}
@@ -0,0 +1,127 @@
+use super::State;
+use crate::domain::{ContainerStatus, QueuedContainerStatus};
+use axum::{
+    extract::{Extension, Path},
+    Json,
+};
+use std::sync::Arc;
+
+#[tracing::instrument(name = "Get container status", skip(state))]
+pub(super) async fn get_container_status(
+    Path(id): Path<String>,
+    Extension(state): Extension<Arc<State>>,
+) -> Json<Option<ContainerStatus>> {
+    let status = state.get_container_status(&state.resolve_id(&id));
+    Json(status)
+}
+
+impl State {
+    /// Looks for the queued container `id` in the queue, the running container,
+    /// the finished containers and the outcomes of the pruned ones, in that order.
+    pub(super) fn get_container_status(&self, id: &str) -> Option<ContainerStatus> {
+        let queued_status = self
+            .queued_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|container| container.id() == id)
+            .map(|container| match container.status() {
+                QueuedContainerStatus::Queued => ContainerStatus::Queued,
+                QueuedContainerStatus::Paused => ContainerStatus::Paused,
+                QueuedContainerStatus::Launching => ContainerStatus::Launching,
+            });
+        if queued_status.is_some() {
+            return queued_status;
+        }
+
+        let running_status = self
+            .running_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|container| container.container().id() == id)
+            .map(|container| ContainerStatus::Running(container.id().clone()));
+        if running_status.is_some() {
+            return running_status;
+        }
+
+        let finished_status = self
+            .finished_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|container| container.container().id() == id)
+            .map(|container| ContainerStatus::Finished(container.outcome().clone()));
+        if finished_status.is_some() {
+            return finished_status;
+        }
+
+        self.pruned_outcomes
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|(pruned_id, _)| pruned_id == id)
+            .map(|(_, outcome)| ContainerStatus::Finished(outcome.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{FinishedOutcome, LaunchedContainer, QueuedContainer, RunningContainerId};
+
+    #[tokio::test]
+    async fn get_container_status_works() {
+        let state = State::new();
+        let container = QueuedContainer::new("docker run -d some_image").unwrap();
+        let id = container.id();
+        assert_eq!(None, state.get_container_status(&id));
+
+        state
+            .queued_containers
+            .lock()
+            .unwrap()
+            .push_back(container.clone());
+        assert_eq!(
+            Some(ContainerStatus::Paused),
+            state.get_container_status(&id)
+        );
+
+        state.queued_containers.lock().unwrap().clear();
+        let running_id = RunningContainerId::new("123456");
+        let launched = LaunchedContainer::new(running_id.clone(), container);
+        state
+            .running_containers
+            .lock()
+            .unwrap()
+            .push(launched.clone());
+        assert_eq!(
+            Some(ContainerStatus::Running(running_id)),
+            state.get_container_status(&id)
+        );
+
+        state.running_containers.lock().unwrap().clear();
+        state
+            .finished_containers
+            .lock()
+            .unwrap()
+            .push(launched.finish(3));
+        assert_eq!(
+            Some(ContainerStatus::Finished(FinishedOutcome::Exited(3))),
+            state.get_container_status(&id)
+        );
+
+        state.finished_containers.lock().unwrap().clear();
+        state
+            .pruned_outcomes
+            .lock()
+            .unwrap()
+            .push_back((id.clone(), FinishedOutcome::Exited(4)));
+        assert_eq!(
+            Some(ContainerStatus::Finished(FinishedOutcome::Exited(4))),
+            state.get_container_status(&id)
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use super::{ServerError, State};
+use crate::domain::{JobDetails, RunningContainerId};
+use axum::{
+    extract::{Extension, Path},
+    Json,
+};
+use bollard::Docker;
+use std::sync::Arc;
+use tracing::warn;
+
+#[tracing::instrument(name = "Get job", skip(state))]
+pub(super) async fn get_job(
+    Path(id): Path<String>,
+    Extension(state): Extension<Arc<State>>,
+) -> Result<Json<JobDetails>, ServerError> {
+    let details = state
+        .get_job(&state.resolve_id(&id))
+        .ok_or_else(|| ServerError::NotFound(format!("Job {} not found.", id)))?;
+    let log_path = match details.running_id() {
+        Some(running_id) => get_log_path(running_id).await,
+        None => None,
+    };
+    Ok(Json(details.with_log_path(log_path)))
+}
+
+impl State {
+    /// Looks for a job by its queued container id or by (a prefix of) its docker id,
+    /// in the queue, the running container and the finished containers.
+    pub(super) fn get_job(&self, id: &str) -> Option<JobDetails> {
+        let is_docker_id = |running_id: &RunningContainerId| is_docker_id(id, running_id);
+
+        let queued = self
+            .queued_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|container| container.id() == id)
+            .map(|container| {
+                JobDetails::from(container).with_pull_status(self.pull_status(container))
+            });
+        if queued.is_some() {
+            return queued;
+        }
+
+        let running = self
+            .running_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|container| container.container().id() == id || is_docker_id(container.id()))
+            .map(JobDetails::from);
+        if running.is_some() {
+            return running;
+        }
+
+        self.finished_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|container| {
+                container.container().id() == id || container.running_id().is_some_and(is_docker_id)
+            })
+            .map(JobDetails::from)
+    }
+}
+
+/// Whether `id` is a (prefix of a) docker container id.
+pub(super) fn is_docker_id(id: &str, running_id: &RunningContainerId) -> bool {
+    !id.is_empty() && running_id.as_ref().starts_with(id)
+}
+
+/// Asks docker for the log file of the container, `None` if it is not available anymore.
+async fn get_log_path(id: &RunningContainerId) -> Option<String> {
+    let docker = Docker::connect_with_local_defaults().ok()?;
+    match docker.inspect_container(id.as_ref(), None).await {
+        Ok(response) => response.log_path,
+        Err(error) => {
+            warn!("Failed to inspect container {}: {}", id.as_ref(), error);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ContainerStatus, LaunchedContainer, QueuedContainer};
+
+    #[test]
+    fn get_job_finds_by_queued_id_and_docker_id() {
+        let state = State::new();
+        let container = QueuedContainer::new("docker run -d some_image").unwrap();
+        let id = container.id();
+        let running_id = RunningContainerId::new("0123456789abcdef");
+        let launched = LaunchedContainer::new(running_id.clone(), container);
+        state
+            .running_containers
+            .lock()
+            .unwrap()
+            .push(launched.clone());
+
+        let details = state.get_job(&id).unwrap();
+        assert_eq!(
+            &ContainerStatus::Running(running_id.clone()),
+            details.status()
+        );
+        assert_eq!(Some(&running_id), details.running_id());
+        assert_eq!(Some(details), state.get_job("0123456789ab"));
+
+        state.running_containers.lock().unwrap().clear();
+        state
+            .finished_containers
+            .lock()
+            .unwrap()
+            .push(launched.finish(3));
+        let details = state.get_job("0123456789ab").unwrap();
+        assert_eq!(id, details.id());
+        assert_eq!(Some(3), details.exit_code());
+        assert!(details.finished_at().is_some());
+
+        assert_eq!(None, state.get_job("fedcba"));
+        assert_eq!(None, state.get_job(""));
+    }
+}
@@ -0,0 +1,22 @@
+use super::State;
+use crate::domain::QueueInfo;
+use axum::{
+    extract::{Extension, Path},
+    Json,
+};
+use chrono::Utc;
+use std::sync::Arc;
+
+#[tracing::instrument(name = "Get queue info", skip(state))]
+pub(super) async fn get_queue_info(
+    Path(id): Path<String>,
+    Extension(state): Extension<Arc<State>>,
+) -> Json<Option<QueueInfo>> {
+    let id = state.resolve_id(&id);
+    let info = state
+        .get_queue_infos(Utc::now())
+        .into_iter()
+        .find(|(container, _)| container.id() == id)
+        .map(|(_, info)| info);
+    Json(info)
+}
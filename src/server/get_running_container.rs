@@ -12,21 +12,32 @@ pub(super) async fn get_running_container(
 }
 
 impl State {
+    /// Get the id of the first launched of the running containers.
     pub(super) fn get_running_container(&self) -> Option<RunningContainerId> {
-        self.running_container.lock().unwrap().clone()
+        self.running_containers
+            .lock()
+            .unwrap()
+            .first()
+            .map(|container| container.id().clone())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::{LaunchedContainer, QueuedContainer};
 
     #[tokio::test]
     async fn get_running_container_works() {
         let state = State::new();
         assert!(state.get_running_container().is_none());
         let id = RunningContainerId::new("123456");
-        *state.running_container.lock().unwrap() = Some(id);
+        let container = QueuedContainer::new("docker run -d some_image").unwrap();
+        state
+            .running_containers
+            .lock()
+            .unwrap()
+            .push(LaunchedContainer::new(id, container));
         assert!(state.get_running_container().is_some());
     }
 }
@@ -0,0 +1,68 @@
+use super::{ServerError, State};
+use crate::domain::{QueuedContainer, DEFAULT_QUEUE};
+
+impl State {
+    /// Get the maximum number of running containers of the queue `queue_name`.
+    pub(super) fn lane_concurrency(&self, queue_name: &str) -> Option<usize> {
+        self.lanes
+            .iter()
+            .find(|lane| lane.name() == queue_name)
+            .and_then(|lane| lane.concurrency())
+    }
+
+    /// Get the number of running and launching containers of the queue `queue_name`.
+    pub(super) fn running_in_lane(&self, queue_name: &str) -> usize {
+        self.started_containers()
+            .iter()
+            .filter(|started| started.queue_name() == queue_name)
+            .count()
+    }
+
+    /// Fails if any of the `containers` is in a queue that is not configured, other
+    /// than the default queue.
+    pub(super) fn check_queues(&self, containers: &[QueuedContainer]) -> Result<(), ServerError> {
+        let unknown = containers
+            .iter()
+            .map(QueuedContainer::queue_name)
+            .find(|&name| {
+                name != DEFAULT_QUEUE && !self.lanes.iter().any(|lane| lane.name() == name)
+            });
+        if let Some(name) = unknown {
+            let mut names = vec![DEFAULT_QUEUE];
+            names.extend(
+                self.lanes
+                    .iter()
+                    .map(|lane| lane.name())
+                    .filter(|&name| name != DEFAULT_QUEUE),
+            );
+            return Err(ServerError::InvalidRequest(format!(
+                "Unknown queue {:?}, expected one of: {}",
+                name,
+                names.join(", ")
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Lane;
+
+    #[test]
+    fn check_queues_rejects_unknown_queues() {
+        let state = State::new().with_lanes(vec![Lane::new("gpu0", Some(1))]);
+        let mut container = QueuedContainer::new("docker run -d some_image").unwrap();
+        assert!(state.check_queues(std::slice::from_ref(&container)).is_ok());
+        container.set_queue_name("gpu0").unwrap();
+        assert!(state.check_queues(std::slice::from_ref(&container)).is_ok());
+        container.set_queue_name("gpu1").unwrap();
+        assert!(matches!(
+            state.check_queues(&[container]),
+            Err(ServerError::InvalidRequest(_))
+        ));
+        assert_eq!(Some(1), state.lane_concurrency("gpu0"));
+        assert_eq!(None, state.lane_concurrency(DEFAULT_QUEUE));
+    }
+}
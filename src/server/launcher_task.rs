@@ -1,13 +1,18 @@
 use super::State;
 use crate::{
-    domain::{QueuedContainer, RunningContainerId},
+    domain::{
+        DependencyState, FinishedContainer, FinishedOutcome, Launch, LaunchedContainer,
+        QueuedContainer, QueuedContainerError, RunningContainerId, ServerMode, JOB_ID_LABEL,
+        QUEUE_LABEL, SERVER_LABEL,
+    },
     error_chain_fmt,
 };
 use anyhow::{Context, Result};
 use bollard::Docker;
+use chrono::Utc;
 use futures::TryStreamExt;
 use std::sync::Arc;
-use tokio::{process::Command, sync::mpsc};
+use tokio::{process::Command, sync::mpsc, time::sleep};
 use tracing::{debug, error, info, Instrument};
 
 #[derive(thiserror::Error)]
@@ -15,6 +20,8 @@ pub enum LauncherTaskError {
     #[error("Error launching \"docker run\": {0}")]
     RunContainerError(String),
     #[error(transparent)]
+    InvalidCommand(#[from] QueuedContainerError),
+    #[error(transparent)]
     WaitContainerError(#[from] bollard::errors::Error),
     #[error(transparent)]
     UnexpectedError(#[from] anyhow::Error),
@@ -30,8 +37,11 @@ impl std::fmt::Debug for LauncherTaskError {
 pub enum TaskMessage {
     /// Check if there is any queued container ready and run it if possible.
     CheckRun,
-    /// Indicates the current running container has finished.
-    RunningFinished,
+    /// Indicates "docker run" returned, the launched container left the queue for the
+    /// running containers or for the finished containers if it failed.
+    Launched(Result<RunningContainerId, LauncherTaskError>),
+    /// Indicates a running container has finished with the given exit code.
+    RunningFinished(RunningContainerId, i64),
     Error(LauncherTaskError),
 }
 
@@ -47,28 +57,41 @@ pub(super) async fn start_launcher_task(
     tx: mpsc::Sender<TaskMessage>,
     mut rx: mpsc::Receiver<TaskMessage>,
 ) {
+    match state.adopt_running_containers().await {
+        Ok(ids) => ids.into_iter().for_each(|id| spawn_wait(id, &tx)),
+        Err(error) => error!("Failed to adopt the running containers: {:?}", error),
+    }
+    // The restored queue runs without waiting for a request
+    state.run_ready_containers(&tx);
     while let Some(msg) = rx.recv().await {
         info!("Received: {:?}", msg);
         let result = match msg {
-            TaskMessage::CheckRun => match state.run_first_container_in_queue().await {
-                Ok(Some(id)) => {
-                    let tx = tx.clone();
-                    tokio::spawn({
-                        async {
-                            wait_for_container(id, tx).await;
-                        }
-                        .instrument(tracing::Span::current())
-                    });
-                    Ok(())
+            TaskMessage::CheckRun => {
+                state.run_ready_containers(&tx);
+                Ok(())
+            }
+            TaskMessage::Launched(Ok(id)) => {
+                spawn_wait(id, &tx);
+                Ok(())
+            }
+            TaskMessage::Launched(Err(error)) => {
+                // The failed container left the queue, the next one might run
+                state.run_ready_containers(&tx);
+                Err(error)
+            }
+            TaskMessage::RunningFinished(id, exit_code) => {
+                let container = {
+                    let mut running_containers = state.running_containers.lock().unwrap();
+                    running_containers
+                        .iter()
+                        .position(|container| container.id() == &id)
+                        .map(|index| running_containers.remove(index))
+                };
+                if let Some(container) = container {
+                    let finished = container.finish(exit_code);
+                    state.finished_containers.lock().unwrap().push(finished);
                 }
-                Ok(None) => Ok(()),
-                Err(error) => Err(error),
-            },
-            TaskMessage::RunningFinished => {
-                *state.running_container.lock().unwrap() = None;
-                tx.send(TaskMessage::CheckRun)
-                    .await
-                    .expect("Receiver dropped.");
+                state.run_ready_containers(&tx);
                 Ok(())
             }
             TaskMessage::Error(error) => Err(error),
@@ -76,40 +99,234 @@ pub(super) async fn start_launcher_task(
         if let Err(error) = result {
             error!("Launcher task error: {:?}", error);
         }
+        state.prune_finished_containers();
     }
 }
 
 impl State {
-    #[tracing::instrument(name = "Run first container in queue", skip(self))]
-    async fn run_first_container_in_queue(
+    /// Starts the queued containers until none is ready or fits the resources left. It
+    /// loops here rather than sending `TaskMessage::CheckRun` to itself, the launcher
+    /// task is the only receiver and would wait forever on a full channel.
+    fn run_ready_containers(self: &Arc<Self>, tx: &mpsc::Sender<TaskMessage>) {
+        while let Some(container) = self.start_next_container(tx) {
+            self.spawn_launch(container, tx);
+        }
+    }
+
+    /// Get the next container to run, marked as launching, if any is ready and fits.
+    #[tracing::instrument(name = "Start next container", skip(self, tx))]
+    fn start_next_container(
+        self: &Arc<Self>,
+        tx: &mpsc::Sender<TaskMessage>,
+    ) -> Option<QueuedContainer> {
+        self.cancel_blocked_containers();
+        if self.mode() == ServerMode::Draining {
+            info!("Server draining, no container starts.");
+            return None;
+        }
+        let next_containers = self.next_ready_containers();
+        self.schedule_check_run(tx);
+        if next_containers.is_empty() {
+            info!("Nothing ready in queue.");
+            return None;
+        }
+        // Each queue runs its next container independently of the others
+        let container = next_containers
+            .iter()
+            .filter(|container| self.can_start(container))
+            .find_map(|container| self.start_launch(&container.id()));
+        if container.is_none() {
+            for container in next_containers {
+                info!(
+                    "Waiting for resources to run {} in queue {}.",
+                    container.id(),
+                    container.queue_name()
+                );
+            }
+        }
+        container
+    }
+
+    /// Runs "docker run" for the launching `container` in a task of its own, so a slow
+    /// launch doesn't hold up the other queues, and reports back with
+    /// `TaskMessage::Launched`.
+    fn spawn_launch(
+        self: &Arc<Self>,
+        mut container: QueuedContainer,
+        tx: &mpsc::Sender<TaskMessage>,
+    ) {
+        let state = Arc::clone(self);
+        let tx = tx.clone();
+        tokio::spawn(
+            async move {
+                container.add_attempt();
+                let labels = [
+                    (JOB_ID_LABEL, container.id()),
+                    (SERVER_LABEL, state.instance.clone()),
+                    (QUEUE_LABEL, container.queue_name().to_string()),
+                ];
+                let result = run_container(&container, &labels).await;
+                let result = state.finish_launch(container, result);
+                // The launcher is gone once the server shuts down, the container is
+                // running or finished anyway
+                let _ = tx.send(TaskMessage::Launched(result)).await;
+            }
+            .instrument(tracing::Span::current()),
+        );
+    }
+
+    /// Moves the launching `container` out of the queue, to the running containers if
+    /// "docker run" succeeded or else to the finished containers.
+    fn finish_launch(
         &self,
-    ) -> Result<Option<RunningContainerId>, LauncherTaskError> {
-        if !self.is_running() {
-            let container = self.queued_containers.lock().unwrap().pop_front();
-            if let Some(container) = container {
-                let id = run_container(container).await?;
-                *self.running_container.lock().unwrap() = Some(id.clone());
-                return Ok(Some(id));
-            } else {
-                info!("Nothing in queue.");
+        container: QueuedContainer,
+        result: Result<RunningContainerId, LauncherTaskError>,
+    ) -> Result<RunningContainerId, LauncherTaskError> {
+        // The container leaves the queue only once it is running or finished, so it is
+        // always found while "docker run" is in progress, as launching.
+        let mut queued_containers = self.queued_containers.lock().unwrap();
+        queued_containers.retain(|queued| queued.id() != container.id());
+        let id = match result {
+            Ok(id) => id,
+            Err(error) => {
+                let outcome = FinishedOutcome::LaunchFailed(error.to_string());
+                let finished = FinishedContainer::new(container, None, outcome);
+                self.finished_containers.lock().unwrap().push(finished);
+                return Err(error);
+            }
+        };
+        let launched = LaunchedContainer::new(id.clone(), container);
+        self.running_containers.lock().unwrap().push(launched);
+        Ok(id)
+    }
+
+    /// Marks the queued container `id` as launching on the GPUs it gets and get it,
+    /// unless it was paused or removed since it was picked or not enough GPUs are free.
+    /// It is the latest version of the container, if it was edited.
+    fn start_launch(&self, id: &str) -> Option<QueuedContainer> {
+        let mut queued_containers = self.queued_containers.lock().unwrap();
+        let index = queued_containers
+            .iter()
+            .position(|container| container.id() == id && container.is_queued())?;
+        let gpu_devices =
+            self.allocate_gpu_devices(&queued_containers[index], &queued_containers)?;
+        let container = &mut queued_containers[index];
+        container.launch(gpu_devices);
+        Some(container.clone())
+    }
+
+    /// Get the container the scheduler picks in each queue, among the queued containers
+    /// that are due and whose dependencies are satisfied, in the order of the queues'
+    /// first containers.
+    fn next_ready_containers(&self) -> Vec<QueuedContainer> {
+        let now = Utc::now();
+        let queued_containers = { self.queued_containers.lock().unwrap().clone() };
+        let ready = queued_containers
+            .into_iter()
+            .filter(|container| container.is_queued() && container.is_due(now))
+            .filter(|container| self.dependency_state(container) == DependencyState::Satisfied)
+            .collect::<Vec<_>>();
+        let mut queue_names: Vec<&str> = Vec::new();
+        for container in &ready {
+            if !queue_names.contains(&container.queue_name()) {
+                queue_names.push(container.queue_name());
             }
-        } else {
-            info!("There is something running already.");
         }
-        Ok(None)
+
+        let launches = self.launches();
+        queue_names
+            .into_iter()
+            .filter_map(|queue_name| {
+                let mut lane = ready
+                    .iter()
+                    .filter(|container| container.queue_name() == queue_name)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let index = self.scheduler.pick(&lane, &launches, now)?;
+                Some(lane.swap_remove(index))
+            })
+            .collect()
     }
 
-    fn is_running(&self) -> bool {
-        self.running_container.lock().unwrap().is_some()
+    /// Get the launches of the running and finished containers.
+    fn launches(&self) -> Vec<Launch> {
+        let finished = self
+            .finished_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|finished| {
+                finished.started_at().map(|started_at| {
+                    let owner = finished.container().owner().map(String::from);
+                    Launch::new(owner, started_at, Some(finished.finished_at()))
+                })
+            })
+            .collect::<Vec<_>>();
+        let running = self
+            .running_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|running| {
+                let owner = running.container().owner().map(String::from);
+                Launch::new(owner, running.started_at(), None)
+            })
+            .collect::<Vec<_>>();
+        finished.into_iter().chain(running).collect()
+    }
+
+    /// Sets a timer that sends `TaskMessage::CheckRun` when the earliest scheduled
+    /// container becomes due, unless there is one set already for that time or earlier.
+    fn schedule_check_run(self: &Arc<Self>, tx: &mpsc::Sender<TaskMessage>) {
+        let now = Utc::now();
+        let next = self
+            .queued_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|container| container.not_before())
+            .filter(|&not_before| not_before > now)
+            .min();
+        let next = match next {
+            Some(next) => next,
+            None => return,
+        };
+
+        {
+            let mut scheduled_check_run = self.scheduled_check_run.lock().unwrap();
+            if matches!(*scheduled_check_run, Some(scheduled) if scheduled > now && scheduled <= next)
+            {
+                return;
+            }
+            *scheduled_check_run = Some(next);
+        }
+
+        info!("Next check scheduled at {}.", next);
+        let delay = (next - now).to_std().unwrap_or_default();
+        let tx = tx.clone();
+        tokio::spawn(
+            async move {
+                sleep(delay).await;
+                // The launcher is gone once the server shuts down
+                let _ = tx.send(TaskMessage::CheckRun).await;
+            }
+            .instrument(tracing::Span::current()),
+        );
     }
 }
 
 #[tracing::instrument(name = "Run container", skip(container), fields(container = %container.id()))]
 async fn run_container(
-    container: QueuedContainer,
+    container: &QueuedContainer,
+    labels: &[(&str, String)],
 ) -> Result<RunningContainerId, LauncherTaskError> {
-    let args = container.get_cmd_args()?;
-    info!("args: {:?}", args);
+    let mut args = container.get_launch_args()?;
+    let labels = labels
+        .iter()
+        .flat_map(|(key, value)| ["--label".to_string(), format!("{}={}", key, value)]);
+    args.splice(1..1, labels);
+    // The args might have the values of the env files
+    info!("command: {:?}", container.command());
     let output = Command::new("docker")
         .args(args)
         .output()
@@ -127,9 +344,21 @@ async fn run_container(
     Ok(id)
 }
 
+/// Waits for the running container `id` in a task of its own, it sends
+/// `TaskMessage::RunningFinished` when the container exits.
+fn spawn_wait(id: RunningContainerId, tx: &mpsc::Sender<TaskMessage>) {
+    let tx = tx.clone();
+    tokio::spawn(
+        async {
+            wait_for_container(id, tx).await;
+        }
+        .instrument(tracing::Span::current()),
+    );
+}
+
 #[tracing::instrument(name = "Wait container", skip(tx))]
 async fn wait_for_container(id: RunningContainerId, tx: mpsc::Sender<TaskMessage>) {
-    match Docker::connect_with_local_defaults() {
+    let msg = match Docker::connect_with_local_defaults() {
         Ok(docker) => {
             match docker
                 .wait_container::<&str>(id.as_ref(), None)
@@ -140,32 +369,136 @@ async fn wait_for_container(id: RunningContainerId, tx: mpsc::Sender<TaskMessage
                     responses.iter().for_each(|response| {
                         debug!("{:?}", response);
                     });
-                    tx.send(TaskMessage::RunningFinished)
-                        .await
-                        .expect("Receiver dropped.");
-                }
-                Err(error) => {
-                    let error = LauncherTaskError::WaitContainerError(error).into();
-                    tx.send(error).await.expect("Receiver dropped.");
+                    match responses.last() {
+                        Some(response) => {
+                            TaskMessage::RunningFinished(id.clone(), response.status_code)
+                        }
+                        None => LauncherTaskError::UnexpectedError(anyhow::anyhow!(
+                            "No response waiting for the container."
+                        ))
+                        .into(),
+                    }
                 }
+                Err(error) => LauncherTaskError::WaitContainerError(error).into(),
             }
         }
-        Err(error) => {
-            let error = LauncherTaskError::UnexpectedError(error.into()).into();
-            tx.send(error).await.expect("Receiver dropped.");
-        }
+        Err(error) => LauncherTaskError::UnexpectedError(error.into()).into(),
+    };
+    // The launcher is gone once the server shuts down
+    if tx.send(msg).await.is_err() {
+        debug!("Launcher stopped, not waiting for {} anymore.", id.as_ref());
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::server::get_running_containers;
+    use crate::{
+        domain::{Resources, Selector},
+        server::get_running_containers,
+    };
+
+    #[test]
+    fn next_ready_container_skips_scheduled_containers() {
+        let state = State::new();
+        let mut scheduled = QueuedContainer::new("docker run -d some_image").unwrap();
+        scheduled.set_not_before(Utc::now() + chrono::Duration::hours(1));
+        scheduled.queue();
+        let mut container = QueuedContainer::new("docker run -d some_image").unwrap();
+        container.queue();
+        state
+            .queued_containers
+            .lock()
+            .unwrap()
+            .extend([scheduled.clone(), container.clone()]);
+
+        assert_eq!(vec![container], state.next_ready_containers());
+        state.queued_containers.lock().unwrap().pop_back();
+        assert!(state.next_ready_containers().is_empty());
+    }
+
+    #[test]
+    fn launching_containers_are_not_picked_nor_changed() {
+        let state = State::new();
+        let mut container = QueuedContainer::new("docker run -d some_image").unwrap();
+        container.queue();
+        state
+            .queued_containers
+            .lock()
+            .unwrap()
+            .push_back(container.clone());
+
+        let launching = state.start_launch(&container.id()).unwrap();
+        assert!(launching.is_launching());
+        assert!(state.start_launch(&container.id()).is_none());
+        assert!(state.next_ready_containers().is_empty());
+        let selector = Selector::new(vec![container.id()], []);
+        let paused = state
+            .update_containers(&selector, |container| {
+                container.pause();
+                true
+            })
+            .unwrap();
+        assert!(paused.is_empty());
+        assert!(state.remove_containers(|_| true).is_empty());
+        let edited = QueuedContainer::new("docker run -d edited_image").unwrap();
+        assert!(state.edit_container(&container.id(), &edited).is_err());
+        assert_eq!(
+            vec![launching],
+            Vec::from(state.queued_containers.lock().unwrap().clone())
+        );
+    }
+
+    #[test]
+    fn launched_containers_get_disjoint_gpus() {
+        let state = State::new().with_capacity(Resources::new(None, None, Some(3)));
+        let containers = [2, 1, 1].map(|gpus| {
+            let mut container = QueuedContainer::new("docker run -d some_image").unwrap();
+            container
+                .set_resources(Resources::new(None, None, Some(gpus)))
+                .unwrap();
+            container.queue();
+            container
+        });
+        state
+            .queued_containers
+            .lock()
+            .unwrap()
+            .extend(containers.clone());
+
+        let first = state.start_launch(&containers[0].id()).unwrap();
+        let second = state.start_launch(&containers[1].id()).unwrap();
+        assert_eq!(&[0, 1], first.gpu_devices());
+        assert_eq!(&[2], second.gpu_devices());
+        assert!(state.start_launch(&containers[2].id()).is_none());
+        assert_eq!(
+            vec!["--gpus", "\"device=0,1\""],
+            &first.get_launch_args().unwrap()[1..3]
+        );
+
+        // The first one finishes while the second one runs
+        state
+            .queued_containers
+            .lock()
+            .unwrap()
+            .retain(|container| !container.is_launching());
+        state
+            .running_containers
+            .lock()
+            .unwrap()
+            .push(LaunchedContainer::new(
+                RunningContainerId::new("123"),
+                second,
+            ));
+        let third = state.start_launch(&containers[2].id()).unwrap();
+        assert_eq!(&[0], third.gpu_devices());
+    }
 
     #[tokio::test]
     async fn run_container_works() {
         let container = QueuedContainer::new("docker run --rm -d alpine sleep 5").unwrap();
-        let id = run_container(container).await.unwrap();
+        let labels = [(JOB_ID_LABEL, container.id())];
+        let id = run_container(&container, &labels).await.unwrap();
         println!("{:#?}", id.as_ref());
         let running_containers = get_running_containers()
             .await
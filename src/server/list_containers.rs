@@ -1,11 +1,12 @@
 use super::State;
 use crate::{
-    domain::{Container, RunningContainer},
+    domain::{Container, RunningContainer, SERVER_LABEL},
     server::ServerError,
 };
 use anyhow::Result;
 use axum::{extract::Extension, Json};
 use bollard::{container::ListContainersOptions, models::ContainerSummaryInner, Docker};
+use chrono::{DateTime, Utc};
 use std::{collections::HashMap, sync::Arc};
 
 #[tracing::instrument(name = "List containers", skip(state))]
@@ -18,30 +19,61 @@ pub(super) async fn list_containers(
 
 impl State {
     pub(super) async fn get_containers(&self) -> Result<Vec<Container>> {
-        let running_id = self
-            .running_container
+        let started_at = self
+            .running_containers
             .lock()
             .unwrap()
-            .clone()
-            .map(|id| id.into());
+            .iter()
+            .map(|container| (container.id().as_ref().to_string(), container.started_at()))
+            .collect::<HashMap<_, _>>();
         let mut containers = get_running_containers()
             .await?
             .into_iter()
             .map(|container| {
-                if container.id == running_id {
-                    Container::Running(Box::new(RunningContainer::Tracked(container)))
+                if self.is_tracked(&container, &started_at) {
+                    let started_at = container
+                        .id
+                        .as_ref()
+                        .and_then(|id| started_at.get(id))
+                        .copied();
+                    Container::Running(Box::new(RunningContainer::Tracked(container, started_at)))
                 } else {
                     Container::Running(Box::new(RunningContainer::External(container)))
                 }
             })
             .collect::<Vec<_>>();
-        let mut queued_containers = { self.queued_containers.lock().unwrap().clone() }
+        let mut queued_containers = self
+            .get_queue_infos(Utc::now())
             .into_iter()
-            .map(Container::Queued)
+            .map(|(container, info)| Container::Queued(Box::new(container), info))
             .collect::<Vec<_>>();
         containers.append(&mut queued_containers);
         Ok(containers)
     }
+
+    /// Whether the container was launched by this server, from its labels so it holds
+    /// across restarts, or because it is one of the running containers.
+    fn is_tracked(
+        &self,
+        container: &ContainerSummaryInner,
+        started_at: &HashMap<String, DateTime<Utc>>,
+    ) -> bool {
+        self.is_launched_here(container)
+            || container
+                .id
+                .as_ref()
+                .is_some_and(|id| started_at.contains_key(id))
+    }
+
+    /// Whether the container has the server label of this server, a server with the same
+    /// instance name launched it.
+    pub(super) fn is_launched_here(&self, container: &ContainerSummaryInner) -> bool {
+        let server = container
+            .labels
+            .as_ref()
+            .and_then(|labels| labels.get(SERVER_LABEL));
+        server == Some(&self.instance)
+    }
 }
 
 pub async fn get_running_containers() -> Result<Vec<ContainerSummaryInner>> {
@@ -55,3 +87,30 @@ pub async fn get_running_containers() -> Result<Vec<ContainerSummaryInner>> {
     let containers = docker.list_containers(options).await?;
     Ok(containers)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(id: &str, server: Option<&str>) -> ContainerSummaryInner {
+        ContainerSummaryInner {
+            id: Some(id.to_string()),
+            labels: server
+                .map(|server| HashMap::from([(SERVER_LABEL.to_string(), server.to_string())])),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn is_tracked_uses_the_server_label() {
+        let state = State::new().with_instance("127.0.0.1:8000");
+
+        let started_at = HashMap::from([("b".to_string(), Utc::now())]);
+        let unknown = HashMap::new();
+
+        assert!(state.is_tracked(&summary("a", Some("127.0.0.1:8000")), &unknown));
+        assert!(state.is_tracked(&summary("b", None), &started_at));
+        assert!(!state.is_tracked(&summary("c", Some("127.0.0.1:9000")), &started_at));
+        assert!(!state.is_tracked(&summary("d", None), &unknown));
+    }
+}
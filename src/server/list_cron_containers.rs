@@ -0,0 +1,12 @@
+use super::State;
+use crate::domain::CronContainer;
+use axum::{extract::Extension, Json};
+use std::sync::Arc;
+
+#[tracing::instrument(name = "List cron containers", skip(state))]
+pub(super) async fn list_cron_containers(
+    Extension(state): Extension<Arc<State>>,
+) -> Json<Vec<CronContainer>> {
+    let cron_containers = state.cron_containers.lock().unwrap().clone();
+    Json(cron_containers)
+}
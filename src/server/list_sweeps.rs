@@ -0,0 +1,76 @@
+use super::State;
+use crate::domain::{QueuedContainer, SweepSummary};
+use axum::{extract::Extension, Json};
+use std::sync::Arc;
+
+#[tracing::instrument(name = "List sweeps", skip(state))]
+pub(super) async fn list_sweeps(
+    Extension(state): Extension<Arc<State>>,
+) -> Json<Vec<SweepSummary>> {
+    Json(state.list_sweeps())
+}
+
+impl State {
+    /// Counts the containers of every sweep, in the order the sweeps were queued.
+    pub(super) fn list_sweeps(&self) -> Vec<SweepSummary> {
+        let mut sweeps: Vec<SweepSummary> = Vec::new();
+        let mut count = |container: &QueuedContainer, add: fn(&mut SweepSummary)| {
+            if let Some(id) = container.sweep_id() {
+                match sweeps.iter_mut().find(|sweep| sweep.id() == id) {
+                    Some(sweep) => add(sweep),
+                    None => {
+                        let mut sweep = SweepSummary::new(id);
+                        add(&mut sweep);
+                        sweeps.push(sweep);
+                    }
+                }
+            }
+        };
+
+        for container in self.finished_containers.lock().unwrap().iter() {
+            count(container.container(), SweepSummary::add_finished);
+        }
+        for container in self.running_containers.lock().unwrap().iter() {
+            count(container.container(), SweepSummary::add_running);
+        }
+        for container in self.queued_containers.lock().unwrap().iter() {
+            if container.is_paused() {
+                count(container, SweepSummary::add_paused);
+            } else {
+                count(container, SweepSummary::add_queued);
+            }
+        }
+        sweeps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn list_sweeps_counts_containers_by_status() {
+        let state = State::new();
+        let sweep_id = Uuid::new_v4();
+        let mut paused = QueuedContainer::new("docker run -d img a").unwrap();
+        paused.set_sweep_id(sweep_id);
+        let mut queued = paused.duplicate();
+        queued.set_sweep_id(sweep_id);
+        queued.queue();
+        let other = QueuedContainer::new("docker run -d img b").unwrap();
+        state
+            .queued_containers
+            .lock()
+            .unwrap()
+            .extend([paused, queued, other]);
+
+        let sweeps = state.list_sweeps();
+
+        assert_eq!(1, sweeps.len());
+        assert_eq!(sweep_id.to_string(), sweeps[0].id());
+        assert_eq!(1, sweeps[0].paused());
+        assert_eq!(1, sweeps[0].queued());
+        assert_eq!(2, sweeps[0].total());
+    }
+}
@@ -1,16 +1,70 @@
+mod add_cron_container;
+mod adopt_containers;
+mod check_container;
+mod cron_task;
+mod dependencies;
+mod edit_container;
+mod estimates;
+mod finished_containers;
+mod get_container_status;
+mod get_job;
+mod get_queue_info;
 mod get_running_container;
+mod lanes;
 mod launcher_task;
 mod list_containers;
+mod list_cron_containers;
+mod list_sweeps;
+mod names;
+mod pause_containers;
+mod pause_sweep;
+mod prefetch_task;
 mod queue_container;
+mod queue_containers;
+mod remove_containers;
+mod remove_cron_container;
+mod remove_sweep;
+mod resources;
+mod resubmit_container;
+mod resume_containers;
+mod resume_sweep;
+mod saved_state;
+mod server_status;
+mod shutdown;
 mod startup;
 
-pub(self) use get_running_container::*;
-pub(self) use launcher_task::*;
-pub(self) use list_containers::*;
-pub(self) use queue_container::*;
+use add_cron_container::*;
+use check_container::*;
+use cron_task::*;
+use edit_container::*;
+use get_container_status::*;
+use get_job::*;
+use get_queue_info::*;
+use get_running_container::*;
+use launcher_task::*;
+use list_containers::*;
+use list_cron_containers::*;
+use list_sweeps::*;
+use pause_containers::*;
+use pause_sweep::*;
+use prefetch_task::*;
+use queue_container::*;
+use queue_containers::*;
+use remove_containers::*;
+use remove_cron_container::*;
+use remove_sweep::*;
+use resources::*;
+use resubmit_container::*;
+use resume_containers::*;
+use resume_sweep::*;
+use server_status::*;
+use shutdown::*;
 pub use startup::*;
 
-use crate::domain::{QueuedContainer, RunningContainerId};
+use crate::domain::{
+    CronContainer, FinishedContainer, FinishedOutcome, Lane, LaunchedContainer, PullStatus,
+    QueuedContainer, Resources, Scheduler, SchedulingPolicy, ServerMode,
+};
 use crate::error_chain_fmt;
 use axum::{
     body::{Bytes, Full},
@@ -18,26 +72,106 @@ use axum::{
     response::IntoResponse,
     Json,
 };
+use chrono::{DateTime, Utc};
 use serde_json::json;
 use std::sync::Mutex;
-use std::{collections::VecDeque, convert::Infallible};
+use std::{
+    collections::{HashMap, VecDeque},
+    convert::Infallible,
+    path::PathBuf,
+};
 
 struct State {
     queued_containers: Mutex<VecDeque<QueuedContainer>>,
-    running_container: Mutex<Option<RunningContainerId>>,
+    running_containers: Mutex<Vec<LaunchedContainer>>,
+    finished_containers: Mutex<Vec<FinishedContainer>>,
+    /// Outcomes of the finished containers pruned from `finished_containers` by queued
+    /// container id, oldest first, so waiting on them still gives their outcome.
+    pruned_outcomes: Mutex<VecDeque<(String, FinishedOutcome)>>,
+    /// Time of the next `TaskMessage::CheckRun` sent for a scheduled container.
+    scheduled_check_run: Mutex<Option<DateTime<Utc>>>,
+    cron_containers: Mutex<Vec<CronContainer>>,
+    /// Name of the server in the labels of the containers it launches.
+    instance: String,
+    /// Prefetch status of the images of the next queued containers, by image.
+    image_pulls: Mutex<HashMap<String, PullStatus>>,
+    /// Number of queued containers ahead whose images are prefetched, 0 to disable it.
+    prefetch_depth: usize,
+    /// Maximum number of images pulled at the same time.
+    prefetch_concurrency: usize,
+    /// Resources of the host shared by the running containers, empty to run them one
+    /// at a time.
+    capacity: Resources,
+    /// Picks the next container to run among the ready ones.
+    scheduler: Box<dyn Scheduler>,
+    /// Queues with their own FIFO and concurrency limit, next to the default queue.
+    lanes: Vec<Lane>,
+    /// Whether the launcher starts the queued containers, saved in `state_file`.
+    mode: Mutex<ServerMode>,
+    /// File where the state that survives restarts is saved, `None` to keep it in
+    /// memory only.
+    state_file: Option<PathBuf>,
 }
 
 impl State {
     fn new() -> Self {
         Self {
             queued_containers: Mutex::new(VecDeque::new()),
-            running_container: Mutex::new(None),
+            running_containers: Mutex::new(Vec::new()),
+            finished_containers: Mutex::new(Vec::new()),
+            pruned_outcomes: Mutex::new(VecDeque::new()),
+            scheduled_check_run: Mutex::new(None),
+            cron_containers: Mutex::new(Vec::new()),
+            instance: "docker_queue".to_string(),
+            image_pulls: Mutex::new(HashMap::new()),
+            prefetch_depth: 0,
+            prefetch_concurrency: 1,
+            capacity: Resources::default(),
+            scheduler: SchedulingPolicy::default().scheduler(),
+            lanes: Vec::new(),
+            mode: Mutex::new(ServerMode::default()),
+            state_file: None,
         }
     }
+
+    fn with_instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = instance.into();
+        self
+    }
+
+    fn with_prefetch(mut self, depth: usize, concurrency: usize) -> Self {
+        self.prefetch_depth = depth;
+        self.prefetch_concurrency = concurrency.max(1);
+        self
+    }
+
+    fn with_capacity(mut self, capacity: Resources) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    fn with_scheduler(mut self, scheduler: Box<dyn Scheduler>) -> Self {
+        self.scheduler = scheduler;
+        self
+    }
+
+    fn with_lanes(mut self, lanes: Vec<Lane>) -> Self {
+        self.lanes = lanes;
+        self
+    }
+
+    fn with_state_file(mut self, state_file: Option<PathBuf>) -> Self {
+        self.state_file = state_file;
+        self
+    }
 }
 
 #[derive(thiserror::Error)]
 pub enum ServerError {
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+    #[error("Not found: {0}")]
+    NotFound(String),
     #[error(transparent)]
     UnexpectedError(#[from] anyhow::Error),
 }
@@ -54,6 +188,8 @@ impl IntoResponse for ServerError {
 
     fn into_response(self) -> Response<Self::Body> {
         let (status, error_message) = match self {
+            ServerError::InvalidRequest(message) => (StatusCode::BAD_REQUEST, message),
+            ServerError::NotFound(message) => (StatusCode::NOT_FOUND, message),
             ServerError::UnexpectedError(err) => {
                 (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
             }
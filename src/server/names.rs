@@ -0,0 +1,120 @@
+use super::{ServerError, State};
+use crate::domain::QueuedContainer;
+use std::collections::VecDeque;
+
+impl State {
+    /// Get the id of the container named `id`, or `id` itself if there is none. Queued
+    /// and running containers come first, then the latest finished one.
+    pub(super) fn resolve_id(&self, id: &str) -> String {
+        let is_named = |container: &QueuedContainer| container.name() == Some(id);
+
+        let queued = self
+            .queued_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|container| is_named(container))
+            .map(QueuedContainer::id);
+        if let Some(queued) = queued {
+            return queued;
+        }
+
+        let running = self
+            .running_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|container| is_named(container.container()))
+            .map(|container| container.container().id());
+        if let Some(running) = running {
+            return running;
+        }
+
+        self.finished_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|container| is_named(container.container()))
+            .map(|container| container.container().id())
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    /// Fails if any of the `containers` has the name of one of the `queued_containers`,
+    /// of a running container or of another of the `containers`. The queue is locked by
+    /// the caller until the `containers` are in it, so the names can't be taken meanwhile.
+    pub(super) fn check_names(
+        &self,
+        queued_containers: &VecDeque<QueuedContainer>,
+        containers: &[QueuedContainer],
+    ) -> Result<(), ServerError> {
+        let mut names = queued_containers
+            .iter()
+            .filter_map(|container| container.name().map(String::from))
+            .collect::<Vec<_>>();
+        for container in self.running_containers.lock().unwrap().iter() {
+            names.extend(container.container().name().map(String::from));
+        }
+
+        for name in containers.iter().filter_map(|container| container.name()) {
+            if names.iter().any(|used| used == name) {
+                return Err(ServerError::InvalidRequest(format!(
+                    "Name already in use: {}",
+                    name
+                )));
+            }
+            names.push(name.to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{FinishedContainer, FinishedOutcome};
+
+    #[test]
+    fn resolve_id_prefers_queued_containers() {
+        let state = State::new();
+        let mut finished = QueuedContainer::new("docker run -d img").unwrap();
+        finished.set_name("train").unwrap();
+        let mut queued = finished.duplicate();
+        queued.set_name("train").unwrap();
+        state
+            .finished_containers
+            .lock()
+            .unwrap()
+            .push(FinishedContainer::new(
+                finished.clone(),
+                None,
+                FinishedOutcome::Exited(0),
+            ));
+        assert_eq!(finished.id(), state.resolve_id("train"));
+
+        state
+            .queued_containers
+            .lock()
+            .unwrap()
+            .push_back(queued.clone());
+        assert_eq!(queued.id(), state.resolve_id("train"));
+        assert_eq!("unknown", state.resolve_id("unknown"));
+    }
+
+    #[test]
+    fn check_names_rejects_names_in_use() {
+        let state = State::new();
+        let mut container = QueuedContainer::new("docker run -d img").unwrap();
+        container.set_name("train").unwrap();
+        let mut queued_containers = VecDeque::new();
+        assert!(state
+            .check_names(&queued_containers, &[container.clone()])
+            .is_ok());
+        assert!(state
+            .check_names(&queued_containers, &[container.clone(), container.clone()])
+            .is_err());
+
+        queued_containers.push_back(container.clone());
+        assert!(state.check_names(&queued_containers, &[container]).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+use super::{ServerError, State};
+use crate::domain::{QueuedContainer, Selector};
+use axum::{extract::Extension, Json};
+use std::sync::Arc;
+
+#[tracing::instrument(name = "Pause containers", skip(state))]
+pub(super) async fn pause_containers(
+    Json(selector): Json<Selector>,
+    Extension(state): Extension<Arc<State>>,
+) -> Result<Json<Vec<String>>, ServerError> {
+    let paused = state.update_containers(&selector, |container| {
+        let is_queued = container.is_queued();
+        container.pause();
+        is_queued
+    })?;
+    Ok(Json(paused))
+}
+
+impl State {
+    /// Calls `update` on every queued container selected but the launching ones, returns
+    /// the ids of the ones for which it returned `true`.
+    pub(super) fn update_containers(
+        &self,
+        selector: &Selector,
+        mut update: impl FnMut(&mut QueuedContainer) -> bool,
+    ) -> Result<Vec<String>, ServerError> {
+        if selector.is_empty() {
+            return Err(ServerError::InvalidRequest(
+                "Select the containers by id, name or label.".to_string(),
+            ));
+        }
+        Ok(self
+            .queued_containers
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .filter(|container| selector.matches(container) && !container.is_launching())
+            .filter_map(|container| update(container).then(|| container.id()))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_containers_updates_the_selected_containers() {
+        let state = State::new();
+        let mut labeled = QueuedContainer::new("docker run -d img a").unwrap();
+        labeled.add_label("team".into(), "vision".into());
+        labeled.queue();
+        let mut other = QueuedContainer::new("docker run -d img b").unwrap();
+        other.queue();
+        state
+            .queued_containers
+            .lock()
+            .unwrap()
+            .extend([labeled.clone(), other]);
+
+        let selector = Selector::new(vec![], [("team".into(), "vision".into())]);
+        let paused = state
+            .update_containers(&selector, |container| {
+                container.pause();
+                true
+            })
+            .unwrap();
+
+        assert_eq!(vec![labeled.id()], paused);
+        let queued_containers = state.queued_containers.lock().unwrap();
+        assert!(queued_containers[0].is_paused());
+        assert!(queued_containers[1].is_queued());
+        drop(queued_containers);
+        assert!(state
+            .update_containers(&Selector::default(), |_| true)
+            .is_err());
+    }
+}
@@ -0,0 +1,49 @@
+use super::{ServerError, State};
+use crate::domain::QueuedContainer;
+use axum::{
+    extract::{Extension, Path},
+    Json,
+};
+use std::sync::Arc;
+
+#[tracing::instrument(name = "Pause sweep", skip(state))]
+pub(super) async fn pause_sweep(
+    Path(id): Path<String>,
+    Extension(state): Extension<Arc<State>>,
+) -> Result<Json<usize>, ServerError> {
+    let paused = state.update_sweep(&id, |container| {
+        let is_queued = container.is_queued();
+        container.pause();
+        is_queued
+    })?;
+    Ok(Json(paused))
+}
+
+impl State {
+    /// Calls `update` on every queued container of the sweep `id` but the launching ones,
+    /// returns how many calls returned `true`.
+    pub(super) fn update_sweep(
+        &self,
+        id: &str,
+        mut update: impl FnMut(&mut QueuedContainer) -> bool,
+    ) -> Result<usize, ServerError> {
+        let mut found = false;
+        let mut updated = 0;
+        for container in self
+            .queued_containers
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .filter(|container| container.sweep_id().as_deref() == Some(id))
+        {
+            found = true;
+            if !container.is_launching() && update(container) {
+                updated += 1;
+            }
+        }
+        if !found && !self.list_sweeps().iter().any(|sweep| sweep.id() == id) {
+            return Err(ServerError::NotFound(format!("Sweep {} not found.", id)));
+        }
+        Ok(updated)
+    }
+}
@@ -0,0 +1,148 @@
+use super::State;
+use crate::domain::{split_image_tag, PullStatus, QueuedContainer};
+use bollard::{errors::Error as DockerError, image::CreateImageOptions, Docker};
+use futures::StreamExt;
+use std::{sync::Arc, time::Duration};
+use tokio::{sync::Semaphore, time::interval};
+use tracing::{info, warn};
+
+const PREFETCH_INTERVAL: Duration = Duration::from_secs(2);
+
+#[tracing::instrument(name = "Prefetch task", skip(state))]
+pub(super) async fn start_prefetch_task(state: Arc<State>) {
+    let semaphore = Arc::new(Semaphore::new(state.prefetch_concurrency));
+    let mut interval = interval(PREFETCH_INTERVAL);
+    loop {
+        interval.tick().await;
+        for image in state.images_to_prefetch() {
+            let state = Arc::clone(&state);
+            let semaphore = Arc::clone(&semaphore);
+            tokio::spawn(async move {
+                let _permit = semaphore.acquire().await.expect("Semaphore closed.");
+                let status = state.prefetch_image(&image).await;
+                state.set_pull_status(&image, status);
+            });
+        }
+    }
+}
+
+impl State {
+    /// Get the images of the next queued containers the prefetcher didn't look at yet,
+    /// and marks them as waiting. Forgets the finished pulls of images that are not
+    /// coming up anymore.
+    fn images_to_prefetch(&self) -> Vec<String> {
+        let mut next_images: Vec<String> = Vec::new();
+        for image in self
+            .queued_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|container| container.is_queued())
+            .take(self.prefetch_depth)
+            .filter_map(QueuedContainer::image)
+            // Images expanded by the server at launch are not known yet
+            .filter(|image| !image.contains('$'))
+        {
+            if !next_images.contains(&image) {
+                next_images.push(image);
+            }
+        }
+
+        let mut image_pulls = self.image_pulls.lock().unwrap();
+        image_pulls.retain(|image, status| {
+            next_images.contains(image)
+                || matches!(status, PullStatus::Waiting | PullStatus::Pulling(_))
+        });
+        next_images.retain(|image| !image_pulls.contains_key(image));
+        for image in &next_images {
+            image_pulls.insert(image.clone(), PullStatus::Waiting);
+        }
+        next_images
+    }
+
+    /// Pulls `image` if it is not available locally.
+    async fn prefetch_image(&self, image: &str) -> PullStatus {
+        let docker = match Docker::connect_with_local_defaults() {
+            Ok(docker) => docker,
+            Err(error) => return PullStatus::Failed(error.to_string()),
+        };
+        match docker.inspect_image(image).await {
+            Ok(_) => return PullStatus::Present,
+            Err(DockerError::DockerResponseNotFoundError { .. }) => {}
+            Err(error) => return PullStatus::Failed(error.to_string()),
+        }
+
+        info!("Pulling image {}.", image);
+        self.set_pull_status(image, PullStatus::Pulling(String::new()));
+        let (from_image, tag) = split_image_tag(image);
+        let options = CreateImageOptions {
+            from_image,
+            tag,
+            ..Default::default()
+        };
+        let mut stream = docker.create_image(Some(options), None, None);
+        while let Some(result) = stream.next().await {
+            match result {
+                Ok(info) => {
+                    if let Some(error) = info.error {
+                        warn!("Failed to pull image {}: {}", image, error);
+                        return PullStatus::Failed(error);
+                    }
+                    if let Some(status) = info.status {
+                        self.set_pull_status(image, PullStatus::Pulling(status));
+                    }
+                }
+                Err(error) => {
+                    warn!("Failed to pull image {}: {}", image, error);
+                    return PullStatus::Failed(error.to_string());
+                }
+            }
+        }
+        info!("Pulled image {}.", image);
+        PullStatus::Pulled
+    }
+
+    fn set_pull_status(&self, image: &str, status: PullStatus) {
+        self.image_pulls
+            .lock()
+            .unwrap()
+            .insert(image.to_string(), status);
+    }
+
+    /// Get the prefetch status of the image of `container`.
+    pub(super) fn pull_status(&self, container: &QueuedContainer) -> Option<PullStatus> {
+        let image = container.image()?;
+        self.image_pulls.lock().unwrap().get(&image).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn images_to_prefetch_takes_the_next_queued_images_once() {
+        let state = State::new().with_prefetch(3, 1);
+        {
+            let mut queued_containers = state.queued_containers.lock().unwrap();
+            let paused = QueuedContainer::new("docker run -d paused").unwrap();
+            queued_containers.push_back(paused);
+            for image in ["a", "b", "a", "c"] {
+                let command = format!("docker run -d {}", image);
+                let mut container = QueuedContainer::new(&command).unwrap();
+                container.queue();
+                queued_containers.push_back(container);
+            }
+        }
+        state.set_pull_status("old", PullStatus::Pulled);
+        state.set_pull_status("busy", PullStatus::Pulling(String::new()));
+
+        assert_eq!(vec!["a", "b"], state.images_to_prefetch());
+        assert!(state.images_to_prefetch().is_empty());
+
+        let image_pulls = state.image_pulls.lock().unwrap();
+        assert_eq!(Some(&PullStatus::Waiting), image_pulls.get("a"));
+        assert!(!image_pulls.contains_key("old"));
+        assert!(image_pulls.contains_key("busy"));
+    }
+}
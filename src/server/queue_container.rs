@@ -12,11 +12,7 @@ pub(super) async fn queue_container(
     Extension(tx): Extension<Sender<TaskMessage>>,
 ) -> Result<(), ServerError> {
     let check_run = queued_container.is_queued();
-    state
-        .queued_containers
-        .lock()
-        .unwrap()
-        .push_back(queued_container);
+    state.enqueue(vec![queued_container])?;
     if check_run {
         tx.send(TaskMessage::CheckRun)
             .await
@@ -24,3 +20,62 @@ pub(super) async fn queue_container(
     }
     Ok(())
 }
+
+impl State {
+    /// Checks the `containers` and adds them at the back of the queue. The queue stays
+    /// locked from the check of the names until they are in it, so two requests can't
+    /// take the same name.
+    pub(super) fn enqueue(&self, mut containers: Vec<QueuedContainer>) -> Result<(), ServerError> {
+        self.check_containers(&mut containers)?;
+        let mut queued_containers = self.queued_containers.lock().unwrap();
+        self.check_names(&queued_containers, &containers)?;
+        queued_containers.extend(containers);
+        Ok(())
+    }
+
+    /// The checks of the containers added to the queue but the names, which the caller
+    /// checks with the queue locked until they are in it.
+    pub(super) fn check_containers(
+        &self,
+        containers: &mut [QueuedContainer],
+    ) -> Result<(), ServerError> {
+        // Clients queue or pause containers, only the launcher sets the launch state
+        for container in containers.iter_mut() {
+            if container.is_launching() {
+                return Err(ServerError::InvalidRequest(format!(
+                    "{} is {}, only queued or paused containers can be added.",
+                    container.id(),
+                    container.status()
+                )));
+            }
+            container.clear_launch_state();
+        }
+        // The status of the dependencies locks the queue, they are checked before
+        self.check_dependencies(containers)?;
+        self.check_resources(containers)?;
+        self.check_queues(containers)?;
+        Ok(())
+    }
+
+    /// Fails if any of the `containers` depends on a container that is unknown to the
+    /// server and not one of the `containers`.
+    pub(super) fn check_dependencies(
+        &self,
+        containers: &[QueuedContainer],
+    ) -> Result<(), ServerError> {
+        let unknown_dependencies = containers
+            .iter()
+            .flat_map(|container| container.dependencies())
+            .map(|dependency| dependency.id())
+            .filter(|id| !containers.iter().any(|container| &container.id() == id))
+            .filter(|id| self.get_container_status(id).is_none())
+            .collect::<Vec<_>>();
+        if !unknown_dependencies.is_empty() {
+            return Err(ServerError::InvalidRequest(format!(
+                "Unknown dependencies: {}",
+                unknown_dependencies.join(", ")
+            )));
+        }
+        Ok(())
+    }
+}
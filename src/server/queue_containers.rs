@@ -0,0 +1,29 @@
+use super::{ServerError, State, TaskMessage};
+use crate::domain::QueuedContainer;
+use anyhow::Context;
+use axum::{extract::Extension, Json};
+use std::sync::Arc;
+use tokio::sync::mpsc::Sender;
+
+#[tracing::instrument(name = "Queue containers", skip(state, tx, queued_containers), fields(count = queued_containers.len()))]
+pub(super) async fn queue_containers(
+    Json(queued_containers): Json<Vec<QueuedContainer>>,
+    Extension(state): Extension<Arc<State>>,
+    Extension(tx): Extension<Sender<TaskMessage>>,
+) -> Result<(), ServerError> {
+    if queued_containers.is_empty() {
+        return Err(ServerError::InvalidRequest(
+            "The batch has no containers.".to_string(),
+        ));
+    }
+    let check_run = queued_containers
+        .iter()
+        .any(|container| container.is_queued());
+    state.enqueue(queued_containers)?;
+    if check_run {
+        tx.send(TaskMessage::CheckRun)
+            .await
+            .context("Receiver dropped.")?;
+    }
+    Ok(())
+}
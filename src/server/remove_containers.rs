@@ -0,0 +1,48 @@
+use super::{ServerError, State};
+use crate::domain::{FinishedContainer, FinishedOutcome, QueuedContainer, Selector};
+use axum::{extract::Extension, Json};
+use std::sync::Arc;
+
+#[tracing::instrument(name = "Remove containers", skip(state))]
+pub(super) async fn remove_containers(
+    Json(selector): Json<Selector>,
+    Extension(state): Extension<Arc<State>>,
+) -> Result<Json<Vec<String>>, ServerError> {
+    if selector.is_empty() {
+        return Err(ServerError::InvalidRequest(
+            "Select the containers by id, name or label.".to_string(),
+        ));
+    }
+    let removed = state.remove_containers(|container| selector.matches(container));
+    Ok(Json(removed))
+}
+
+impl State {
+    /// Removes from the queue every container for which `is_removed` returns `true`, but
+    /// the launching ones, they finish as cancelled. Returns the ids of the removed
+    /// containers.
+    pub(super) fn remove_containers(
+        &self,
+        is_removed: impl Fn(&QueuedContainer) -> bool,
+    ) -> Vec<String> {
+        let removed = {
+            let mut queued_containers = self.queued_containers.lock().unwrap();
+            let (removed, kept) = queued_containers
+                .drain(..)
+                .partition::<Vec<_>, _>(|container| {
+                    !container.is_launching() && is_removed(container)
+                });
+            queued_containers.extend(kept);
+            removed
+        };
+        let ids = removed.iter().map(QueuedContainer::id).collect();
+        let outcome = FinishedOutcome::Cancelled("Removed from the queue.".to_string());
+        self.finished_containers.lock().unwrap().extend(
+            removed
+                .into_iter()
+                .map(|container| FinishedContainer::new(container, None, outcome.clone())),
+        );
+        self.prune_finished_containers();
+        ids
+    }
+}
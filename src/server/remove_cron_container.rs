@@ -0,0 +1,26 @@
+use super::{ServerError, State};
+use axum::extract::{Extension, Path};
+use std::sync::Arc;
+
+#[tracing::instrument(name = "Remove cron container", skip(state))]
+pub(super) async fn remove_cron_container(
+    Path(id): Path<String>,
+    Extension(state): Extension<Arc<State>>,
+) -> Result<(), ServerError> {
+    if state.remove_cron_container(&id) {
+        state.save_state()?;
+        Ok(())
+    } else {
+        Err(ServerError::NotFound(format!("Cron container {:?}", id)))
+    }
+}
+
+impl State {
+    /// Returns whether the cron container was found.
+    pub(super) fn remove_cron_container(&self, id: &str) -> bool {
+        let mut cron_containers = self.cron_containers.lock().unwrap();
+        let len = cron_containers.len();
+        cron_containers.retain(|cron_container| cron_container.id() != id);
+        cron_containers.len() != len
+    }
+}
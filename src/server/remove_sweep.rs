@@ -0,0 +1,65 @@
+use super::{ServerError, State};
+use axum::{
+    extract::{Extension, Path},
+    Json,
+};
+use std::sync::Arc;
+
+#[tracing::instrument(name = "Remove sweep", skip(state))]
+pub(super) async fn remove_sweep(
+    Path(id): Path<String>,
+    Extension(state): Extension<Arc<State>>,
+) -> Result<Json<usize>, ServerError> {
+    let removed = state.remove_sweep(&id)?;
+    Ok(Json(removed))
+}
+
+impl State {
+    /// Removes from the queue every container of the sweep `id`, they finish as cancelled.
+    /// A running container of the sweep keeps running.
+    pub(super) fn remove_sweep(&self, id: &str) -> Result<usize, ServerError> {
+        if !self.list_sweeps().iter().any(|sweep| sweep.id() == id) {
+            return Err(ServerError::NotFound(format!("Sweep {} not found.", id)));
+        }
+
+        let removed =
+            self.remove_containers(|container| container.sweep_id().as_deref() == Some(id));
+        Ok(removed.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ContainerStatus, FinishedOutcome, QueuedContainer};
+    use uuid::Uuid;
+
+    #[test]
+    fn remove_sweep_cancels_only_the_sweep_containers() {
+        let state = State::new();
+        let mut container = QueuedContainer::new("docker run -d img a").unwrap();
+        container.set_sweep_id(Uuid::new_v4());
+        let other = QueuedContainer::new("docker run -d img b").unwrap();
+        state
+            .queued_containers
+            .lock()
+            .unwrap()
+            .extend([container.clone(), other.clone()]);
+
+        let removed = state.remove_sweep(&container.sweep_id().unwrap()).unwrap();
+
+        assert_eq!(1, removed);
+        assert!(matches!(
+            state.get_container_status(&container.id()),
+            Some(ContainerStatus::Finished(FinishedOutcome::Cancelled(_)))
+        ));
+        assert_eq!(
+            Some(ContainerStatus::Paused),
+            state.get_container_status(&other.id())
+        );
+        assert!(matches!(
+            state.remove_sweep("unknown"),
+            Err(ServerError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,237 @@
+use super::{ServerError, State};
+use crate::domain::{QueuedContainer, ResourceUsage, Resources};
+use axum::{extract::Extension, Json};
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::Arc,
+};
+
+#[tracing::instrument(name = "Get resources", skip(state))]
+pub(super) async fn get_resources(Extension(state): Extension<Arc<State>>) -> Json<ResourceUsage> {
+    Json(ResourceUsage::new(state.used_resources(), state.capacity))
+}
+
+impl State {
+    /// Get the containers that hold resources, the running ones and the launching ones.
+    pub(super) fn started_containers(&self) -> Vec<QueuedContainer> {
+        let launching = self
+            .queued_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|container| container.is_launching())
+            .cloned()
+            .collect::<Vec<_>>();
+        let running_containers = self.running_containers.lock().unwrap();
+        running_containers
+            .iter()
+            .map(|running| running.container().clone())
+            .chain(launching)
+            .collect()
+    }
+
+    /// Get the sum of the resources requested by the running and launching containers.
+    pub(super) fn used_resources(&self) -> Resources {
+        self.started_containers()
+            .iter()
+            .fold(Resources::default(), |used, container| {
+                used.add(container.resources())
+            })
+    }
+
+    /// Whether `container` can start next to the running and launching containers, its
+    /// requests have to fit in the capacity left by the ones of every queue. In a queue
+    /// with a concurrency limit containers start up to the limit. Otherwise, without a
+    /// host capacity, or if it or a running container of its queue has no resource
+    /// requests, the containers of the queue run one at a time.
+    pub(super) fn can_start(&self, container: &QueuedContainer) -> bool {
+        let queue_name = container.queue_name();
+        let fits = container
+            .resources()
+            .fits(&self.used_resources(), &self.capacity);
+        if let Some(concurrency) = self.lane_concurrency(queue_name) {
+            return fits && self.running_in_lane(queue_name) < concurrency;
+        }
+        let (is_lane_idle, any_without_requests) = {
+            let started_containers = self.started_containers();
+            let mut lane = started_containers
+                .iter()
+                .filter(|started| started.queue_name() == queue_name)
+                .peekable();
+            let is_lane_idle = lane.peek().is_none();
+            let any_without_requests = lane.any(|started| started.resources().is_empty());
+            (is_lane_idle, any_without_requests)
+        };
+        if is_lane_idle {
+            return fits;
+        }
+        if self.capacity.is_empty() || container.resources().is_empty() || any_without_requests {
+            return false;
+        }
+        fits
+    }
+
+    /// Get the host GPUs for `container`, the first ones not given to a running container
+    /// nor to a launching one of `queued_containers`. Empty if it requests no GPU or the
+    /// GPUs of the host aren't limited, `None` if not enough GPUs are free.
+    pub(super) fn allocate_gpu_devices(
+        &self,
+        container: &QueuedContainer,
+        queued_containers: &VecDeque<QueuedContainer>,
+    ) -> Option<Vec<u32>> {
+        let (requested, capacity) = match (container.resources().gpus(), self.capacity.gpus()) {
+            (Some(requested), Some(capacity)) => (requested as usize, capacity),
+            _ => return Some(Vec::new()),
+        };
+        let used = {
+            let running_containers = self.running_containers.lock().unwrap();
+            running_containers
+                .iter()
+                .map(|running| running.container())
+                .chain(
+                    queued_containers
+                        .iter()
+                        .filter(|queued| queued.is_launching()),
+                )
+                .flat_map(|container| container.gpu_devices().iter().copied())
+                .collect::<HashSet<_>>()
+        };
+        let free = (0..capacity)
+            .filter(|device| !used.contains(device))
+            .take(requested)
+            .collect::<Vec<_>>();
+        (free.len() == requested).then_some(free)
+    }
+
+    /// Fails if the resource requests of any of the `containers` exceed the capacity of
+    /// the host, they would never start.
+    pub(super) fn check_resources(
+        &self,
+        containers: &[QueuedContainer],
+    ) -> Result<(), ServerError> {
+        let too_large = containers.iter().find(|container| {
+            !container
+                .resources()
+                .fits(&Resources::default(), &self.capacity)
+        });
+        if let Some(container) = too_large {
+            return Err(ServerError::InvalidRequest(format!(
+                "The resource requests of {} exceed the capacity of the host: {} > {}",
+                container.id(),
+                container.resources(),
+                self.capacity
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Lane, LaunchedContainer, RunningContainerId};
+
+    fn container(resources: Resources) -> QueuedContainer {
+        let mut container = QueuedContainer::new("docker run -d some_image").unwrap();
+        container.set_resources(resources).unwrap();
+        container
+    }
+
+    #[test]
+    fn can_start_fits_the_requests_in_the_capacity_left() {
+        let state = State::new().with_capacity(Resources::new(None, Some(4.0), Some(1)));
+        let gpu = container(Resources::new(None, Some(2.0), Some(1)));
+        let cpu = container(Resources::new(None, Some(2.0), None));
+        let no_requests = container(Resources::default());
+        assert!(state.can_start(&gpu));
+
+        state
+            .running_containers
+            .lock()
+            .unwrap()
+            .push(LaunchedContainer::new(
+                RunningContainerId::new("123"),
+                gpu.clone(),
+            ));
+        assert!(state.can_start(&cpu));
+        assert!(!state.can_start(&gpu));
+        assert!(!state.can_start(&no_requests));
+        assert_eq!(
+            Resources::new(None, Some(2.0), Some(1)),
+            state.used_resources()
+        );
+    }
+
+    #[test]
+    fn launching_containers_hold_their_resources() {
+        let state = State::new().with_capacity(Resources::new(None, Some(4.0), None));
+        let mut launching = container(Resources::new(None, Some(3.0), None));
+        launching.launch(Vec::new());
+        state.queued_containers.lock().unwrap().push_back(launching);
+
+        assert_eq!(
+            Resources::new(None, Some(3.0), None),
+            state.used_resources()
+        );
+        assert!(!state.can_start(&container(Resources::new(None, Some(2.0), None))));
+    }
+
+    #[test]
+    fn containers_run_one_at_a_time_without_capacity() {
+        let state = State::new();
+        let cpu = container(Resources::new(None, Some(1.0), None));
+        state
+            .running_containers
+            .lock()
+            .unwrap()
+            .push(LaunchedContainer::new(
+                RunningContainerId::new("123"),
+                cpu.clone(),
+            ));
+        assert!(!state.can_start(&cpu));
+    }
+
+    #[test]
+    fn queues_run_their_containers_independently() {
+        let state = State::new()
+            .with_capacity(Resources::new(None, Some(4.0), None))
+            .with_lanes(vec![Lane::new("cpu", Some(2))]);
+        let running = container(Resources::default());
+        let default = container(Resources::default());
+        let mut cpu = container(Resources::new(None, Some(3.0), None));
+        cpu.set_queue_name("cpu").unwrap();
+        state
+            .running_containers
+            .lock()
+            .unwrap()
+            .push(LaunchedContainer::new(
+                RunningContainerId::new("123"),
+                running,
+            ));
+        assert!(!state.can_start(&default));
+        assert!(state.can_start(&cpu));
+
+        state
+            .running_containers
+            .lock()
+            .unwrap()
+            .push(LaunchedContainer::new(
+                RunningContainerId::new("456"),
+                cpu.clone(),
+            ));
+        // Under the concurrency limit but over the capacity
+        assert!(!state.can_start(&cpu));
+    }
+
+    #[test]
+    fn check_resources_rejects_requests_larger_than_the_capacity() {
+        let state = State::new().with_capacity(Resources::new(Some(1024), None, None));
+        assert!(state
+            .check_resources(&[container(Resources::new(Some(1024), Some(64.0), None))])
+            .is_ok());
+        assert!(matches!(
+            state.check_resources(&[container(Resources::new(Some(2048), None, None))]),
+            Err(ServerError::InvalidRequest(_))
+        ));
+    }
+}
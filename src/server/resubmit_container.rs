@@ -0,0 +1,174 @@
+use super::{is_docker_id, ServerError, State, TaskMessage};
+use crate::domain::{QueuedContainer, ResubmitOptions};
+use anyhow::Context;
+use axum::{
+    extract::{Extension, Path},
+    Json,
+};
+use std::sync::Arc;
+use tokio::sync::mpsc::Sender;
+
+#[tracing::instrument(name = "Resubmit container", skip(state, tx))]
+pub(super) async fn resubmit_container(
+    Path(id): Path<String>,
+    Json(options): Json<ResubmitOptions>,
+    Extension(state): Extension<Arc<State>>,
+    Extension(tx): Extension<Sender<TaskMessage>>,
+) -> Result<Json<QueuedContainer>, ServerError> {
+    let container = state.resubmit_container(&state.resolve_id(&id), &options)?;
+    if container.is_queued() {
+        tx.send(TaskMessage::CheckRun)
+            .await
+            .context("Receiver dropped.")?;
+    }
+    Ok(Json(container))
+}
+
+impl State {
+    /// Queues a copy of the running or finished container with queued container id or
+    /// docker id `id`, the copy gets a new id. It goes through the checks of the queued
+    /// containers, the server might have restarted with less capacity or other queues.
+    pub(super) fn resubmit_container(
+        &self,
+        id: &str,
+        options: &ResubmitOptions,
+    ) -> Result<QueuedContainer, ServerError> {
+        let mut container = self
+            .get_submitted_container(id)
+            .ok_or_else(|| {
+                if self.get_container_status(id).is_some() {
+                    ServerError::InvalidRequest(format!("Container {} is still in the queue.", id))
+                } else {
+                    ServerError::NotFound(format!("Container {} not found.", id))
+                }
+            })?
+            .duplicate();
+        if !options.paused {
+            container.queue();
+        }
+        self.check_containers(std::slice::from_mut(&mut container))?;
+
+        let mut queued_containers = self.queued_containers.lock().unwrap();
+        self.check_names(&queued_containers, std::slice::from_ref(&container))?;
+        if options.front {
+            queued_containers.push_front(container.clone());
+        } else {
+            queued_containers.push_back(container.clone());
+        }
+        Ok(container)
+    }
+
+    /// Looks for the running or finished container with queued container id or docker
+    /// id `id`.
+    fn get_submitted_container(&self, id: &str) -> Option<QueuedContainer> {
+        let running = self
+            .running_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|container| container.container().id() == id || is_docker_id(id, container.id()))
+            .map(|container| container.container().clone());
+        if running.is_some() {
+            return running;
+        }
+
+        self.finished_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|container| {
+                container.container().id() == id
+                    || container
+                        .running_id()
+                        .is_some_and(|running_id| is_docker_id(id, running_id))
+            })
+            .map(|container| container.container().clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{FinishedContainer, FinishedOutcome, Lane};
+    use uuid::Uuid;
+
+    #[test]
+    fn resubmit_container_queues_a_copy_of_a_finished_container() {
+        let state = State::new();
+        let container = QueuedContainer::new("docker run -d some_image").unwrap();
+        let queued = QueuedContainer::new("docker run -d queued_image").unwrap();
+        state
+            .queued_containers
+            .lock()
+            .unwrap()
+            .push_back(queued.clone());
+        state
+            .finished_containers
+            .lock()
+            .unwrap()
+            .push(FinishedContainer::new(
+                container.clone(),
+                None,
+                FinishedOutcome::LaunchFailed("error".to_string()),
+            ));
+
+        let options = ResubmitOptions {
+            front: true,
+            paused: false,
+        };
+        let resubmitted = state.resubmit_container(&container.id(), &options).unwrap();
+
+        assert_ne!(container.id(), resubmitted.id());
+        assert_eq!(container.command(), resubmitted.command());
+        assert!(resubmitted.is_queued());
+        let queued_containers = state.queued_containers.lock().unwrap();
+        assert_eq!(Some(&resubmitted), queued_containers.front());
+        assert_eq!(Some(&queued), queued_containers.back());
+    }
+
+    #[test]
+    fn resubmit_container_checks_the_copy_and_leaves_the_sweep() {
+        let state = State::new().with_lanes(vec![Lane::new("gpu0", Some(1))]);
+        let mut container = QueuedContainer::new("docker run -d some_image").unwrap();
+        container.set_sweep_id(Uuid::new_v4());
+        let mut removed_queue = container.clone();
+        removed_queue.set_id(Uuid::new_v4());
+        removed_queue.set_queue_name("gpu1").unwrap();
+        state.finished_containers.lock().unwrap().extend(
+            [container.clone(), removed_queue.clone()].map(|container| {
+                FinishedContainer::new(container, None, FinishedOutcome::Exited(0))
+            }),
+        );
+
+        let options = ResubmitOptions::default();
+        let resubmitted = state.resubmit_container(&container.id(), &options).unwrap();
+        assert_eq!(None, resubmitted.sweep_id());
+        assert!(matches!(
+            state.resubmit_container(&removed_queue.id(), &options),
+            Err(ServerError::InvalidRequest(_))
+        ));
+        assert_eq!(1, state.queued_containers.lock().unwrap().len());
+    }
+
+    #[test]
+    fn resubmit_container_rejects_queued_and_unknown_containers() {
+        let state = State::new();
+        let queued = QueuedContainer::new("docker run -d queued_image").unwrap();
+        state
+            .queued_containers
+            .lock()
+            .unwrap()
+            .push_back(queued.clone());
+
+        let options = ResubmitOptions::default();
+        assert!(matches!(
+            state.resubmit_container(&queued.id(), &options),
+            Err(ServerError::InvalidRequest(_))
+        ));
+        assert!(matches!(
+            state.resubmit_container("unknown", &options),
+            Err(ServerError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,25 @@
+use super::{ServerError, State, TaskMessage};
+use crate::domain::Selector;
+use anyhow::Context;
+use axum::{extract::Extension, Json};
+use std::sync::Arc;
+use tokio::sync::mpsc::Sender;
+
+#[tracing::instrument(name = "Resume containers", skip(state, tx))]
+pub(super) async fn resume_containers(
+    Json(selector): Json<Selector>,
+    Extension(state): Extension<Arc<State>>,
+    Extension(tx): Extension<Sender<TaskMessage>>,
+) -> Result<Json<Vec<String>>, ServerError> {
+    let resumed = state.update_containers(&selector, |container| {
+        let is_paused = container.is_paused();
+        container.queue();
+        is_paused
+    })?;
+    if !resumed.is_empty() {
+        tx.send(TaskMessage::CheckRun)
+            .await
+            .context("Receiver dropped.")?;
+    }
+    Ok(Json(resumed))
+}
@@ -0,0 +1,27 @@
+use super::{ServerError, State, TaskMessage};
+use anyhow::Context;
+use axum::{
+    extract::{Extension, Path},
+    Json,
+};
+use std::sync::Arc;
+use tokio::sync::mpsc::Sender;
+
+#[tracing::instrument(name = "Resume sweep", skip(state, tx))]
+pub(super) async fn resume_sweep(
+    Path(id): Path<String>,
+    Extension(state): Extension<Arc<State>>,
+    Extension(tx): Extension<Sender<TaskMessage>>,
+) -> Result<Json<usize>, ServerError> {
+    let resumed = state.update_sweep(&id, |container| {
+        let is_paused = container.is_paused();
+        container.queue();
+        is_paused
+    })?;
+    if resumed > 0 {
+        tx.send(TaskMessage::CheckRun)
+            .await
+            .context("Receiver dropped.")?;
+    }
+    Ok(Json(resumed))
+}
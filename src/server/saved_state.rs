@@ -0,0 +1,243 @@
+use super::State;
+use crate::domain::{
+    CronContainer, FinishedContainer, LaunchedContainer, QueuedContainer, ServerMode,
+};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, ffi::OsString, fs, path::PathBuf};
+
+/// The part of the state of the server that survives restarts.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SavedState {
+    mode: ServerMode,
+    cron_containers: Vec<CronContainer>,
+    /// Saved only when the server shuts down, a crash loses them.
+    queued_containers: Vec<QueuedContainer>,
+    /// Saved only when the server shuts down, the ones still running are adopted again
+    /// after the restart.
+    running_containers: Vec<LaunchedContainer>,
+    /// The finished containers the saved queued containers depend on, so the
+    /// dependencies are still known after the restart.
+    finished_containers: Vec<FinishedContainer>,
+}
+
+impl State {
+    /// Restores the state saved in the state file, if there is one.
+    pub(super) fn load_state(&self) -> Result<()> {
+        let path = match &self.state_file {
+            Some(path) if path.exists() => path,
+            _ => return Ok(()),
+        };
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read the state file {}.", path.display()))?;
+        let saved: SavedState = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse the state file {}.", path.display()))?;
+        tracing::info!(
+            "Restored mode {:?}, {} cron containers, {} queued containers and {} running \
+             containers.",
+            saved.mode,
+            saved.cron_containers.len(),
+            saved.queued_containers.len(),
+            saved.running_containers.len()
+        );
+        *self.mode.lock().unwrap() = saved.mode;
+        *self.cron_containers.lock().unwrap() = saved.cron_containers;
+        let is_queue_restored =
+            !saved.queued_containers.is_empty() || !saved.running_containers.is_empty();
+        self.queued_containers
+            .lock()
+            .unwrap()
+            .extend(saved.queued_containers);
+        self.running_containers
+            .lock()
+            .unwrap()
+            .extend(saved.running_containers);
+        self.finished_containers
+            .lock()
+            .unwrap()
+            .extend(saved.finished_containers);
+        if is_queue_restored {
+            // A crash before the next shutdown must not restore them a second time
+            self.save_state()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the state that survives restarts to the state file, without the queue.
+    pub(super) fn save_state(&self) -> Result<()> {
+        self.write_state(Vec::new(), Vec::new(), Vec::new())
+    }
+
+    /// Writes the state that survives restarts to the state file with the queued and
+    /// running containers, and the finished containers they depend on, when the server
+    /// shuts down.
+    pub(super) fn flush_state(&self) -> Result<()> {
+        let queued_containers = self
+            .queued_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        let running_containers = self.running_containers.lock().unwrap().clone();
+        let depended_on = queued_containers
+            .iter()
+            .flat_map(|container| container.dependencies())
+            .map(|dependency| dependency.id())
+            .collect::<HashSet<_>>();
+        let finished_containers = self
+            .finished_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|finished| depended_on.contains(&finished.container().id()))
+            .cloned()
+            .collect();
+        self.write_state(queued_containers, running_containers, finished_containers)
+    }
+
+    /// Writes the state file through a temporary file, so a crash never leaves it half
+    /// written.
+    fn write_state(
+        &self,
+        queued_containers: Vec<QueuedContainer>,
+        running_containers: Vec<LaunchedContainer>,
+        finished_containers: Vec<FinishedContainer>,
+    ) -> Result<()> {
+        let path = match &self.state_file {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let saved = SavedState {
+            mode: self.mode(),
+            cron_containers: self.cron_containers.lock().unwrap().clone(),
+            queued_containers,
+            running_containers,
+            finished_containers,
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}.", parent.display()))?;
+        }
+        let mut temp_path = OsString::from(path);
+        temp_path.push(".tmp");
+        let temp_path = PathBuf::from(temp_path);
+        let content = serde_json::to_string_pretty(&saved).context("Failed to serialize state.")?;
+        fs::write(&temp_path, content)
+            .with_context(|| format!("Failed to write {}.", temp_path.display()))?;
+        fs::rename(&temp_path, path)
+            .with_context(|| format!("Failed to write the state file {}.", path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Dependency, DependencyCondition, DependencyState, RunningContainerId};
+    use uuid::Uuid;
+
+    #[test]
+    fn saved_state_survives_a_restart() {
+        let path = std::env::temp_dir()
+            .join(format!("docker_queue-{}", Uuid::new_v4()))
+            .join("state.json");
+        let state = State::new().with_state_file(Some(path.clone()));
+        state.load_state().unwrap();
+        assert_eq!(ServerMode::Active, state.mode());
+
+        *state.mode.lock().unwrap() = ServerMode::Draining;
+        let container = QueuedContainer::new("docker run -d some_image").unwrap();
+        let cron_container = CronContainer::new("0 2 * * *", container).unwrap();
+        state
+            .cron_containers
+            .lock()
+            .unwrap()
+            .push(cron_container.clone());
+        state.save_state().unwrap();
+        let restarted = State::new().with_state_file(Some(path.clone()));
+        restarted.load_state().unwrap();
+        assert_eq!(ServerMode::Draining, restarted.mode());
+        assert_eq!(
+            vec![cron_container],
+            *restarted.cron_containers.lock().unwrap()
+        );
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn flushed_queue_and_running_containers_are_restored_once() {
+        let path = std::env::temp_dir().join(format!("docker_queue-{}.json", Uuid::new_v4()));
+        let state = State::new().with_state_file(Some(path.clone()));
+        let container = QueuedContainer::new("docker run -d some_image").unwrap();
+        state
+            .queued_containers
+            .lock()
+            .unwrap()
+            .push_back(container.clone());
+        let running = QueuedContainer::new("docker run -d other_image").unwrap();
+        state
+            .running_containers
+            .lock()
+            .unwrap()
+            .push(LaunchedContainer::new(
+                RunningContainerId::new("123"),
+                running,
+            ));
+        state.flush_state().unwrap();
+
+        let restarted = State::new().with_state_file(Some(path.clone()));
+        restarted.load_state().unwrap();
+        assert_eq!(
+            vec![container],
+            Vec::from(restarted.queued_containers.lock().unwrap().clone())
+        );
+        assert_eq!(1, restarted.running_containers.lock().unwrap().len());
+        let restarted_again = State::new().with_state_file(Some(path.clone()));
+        restarted_again.load_state().unwrap();
+        assert!(restarted_again.queued_containers.lock().unwrap().is_empty());
+        assert!(restarted_again
+            .running_containers
+            .lock()
+            .unwrap()
+            .is_empty());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn finished_dependencies_are_saved_with_the_queue() {
+        let path = std::env::temp_dir().join(format!("docker_queue-{}.json", Uuid::new_v4()));
+        let state = State::new().with_state_file(Some(path.clone()));
+        let [dependency, other] = ["some_image", "other_image"].map(|image| {
+            let container = QueuedContainer::new(format!("docker run -d {}", image)).unwrap();
+            LaunchedContainer::new(RunningContainerId::new(image), container).finish(0)
+        });
+        let mut container = QueuedContainer::new("docker run -d some_image").unwrap();
+        let id = dependency.container().id().parse().unwrap();
+        container.add_dependency(Dependency::new(id, DependencyCondition::Succeeded));
+        state
+            .finished_containers
+            .lock()
+            .unwrap()
+            .extend([dependency, other]);
+        state
+            .queued_containers
+            .lock()
+            .unwrap()
+            .push_back(container.clone());
+        state.flush_state().unwrap();
+
+        let restarted = State::new().with_state_file(Some(path.clone()));
+        restarted.load_state().unwrap();
+        assert_eq!(1, restarted.finished_containers.lock().unwrap().len());
+        assert_eq!(
+            DependencyState::Satisfied,
+            restarted.dependency_state(&container)
+        );
+
+        fs::remove_file(path).unwrap();
+    }
+}
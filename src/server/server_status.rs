@@ -0,0 +1,62 @@
+use super::{ServerError, State, TaskMessage};
+use crate::domain::{ServerMode, ServerStatus};
+use anyhow::Context;
+use axum::{extract::Extension, Json};
+use std::sync::Arc;
+use tokio::sync::mpsc::Sender;
+use tracing::info;
+
+#[tracing::instrument(name = "Get server status", skip(state))]
+pub(super) async fn get_server_status(
+    Extension(state): Extension<Arc<State>>,
+) -> Json<ServerStatus> {
+    Json(state.server_status())
+}
+
+#[tracing::instrument(name = "Drain server", skip(state))]
+pub(super) async fn drain_server(
+    Extension(state): Extension<Arc<State>>,
+) -> Result<Json<ServerStatus>, ServerError> {
+    state.set_mode(ServerMode::Draining)?;
+    Ok(Json(state.server_status()))
+}
+
+#[tracing::instrument(name = "Resume server", skip(state, tx))]
+pub(super) async fn resume_server(
+    Extension(state): Extension<Arc<State>>,
+    Extension(tx): Extension<Sender<TaskMessage>>,
+) -> Result<Json<ServerStatus>, ServerError> {
+    state.set_mode(ServerMode::Active)?;
+    tx.send(TaskMessage::CheckRun)
+        .await
+        .context("Receiver dropped.")?;
+    Ok(Json(state.server_status()))
+}
+
+impl State {
+    /// Get whether the launcher starts the queued containers.
+    pub(super) fn mode(&self) -> ServerMode {
+        *self.mode.lock().unwrap()
+    }
+
+    /// Sets the mode and saves it, so that it survives restarts.
+    fn set_mode(&self, mode: ServerMode) -> Result<(), ServerError> {
+        *self.mode.lock().unwrap() = mode;
+        self.save_state()?;
+        info!("Server mode: {:?}.", mode);
+        Ok(())
+    }
+
+    pub(super) fn server_status(&self) -> ServerStatus {
+        // A launching container is as good as running, the host isn't drained yet
+        let running = self.started_containers().len();
+        let queued = self
+            .queued_containers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|container| !container.is_launching())
+            .count();
+        ServerStatus::new(self.mode(), running, queued)
+    }
+}
@@ -0,0 +1,264 @@
+use super::State;
+use crate::domain::{FinishedContainer, FinishedOutcome, RunningContainerId, ShutdownPolicy};
+use anyhow::Result;
+use bollard::{container::StopContainerOptions, Docker};
+use std::time::Duration;
+use tokio::time::{sleep, timeout};
+use tracing::{error, info, warn};
+
+/// Seconds `docker stop` waits for a container to exit before killing it.
+const STOP_TIMEOUT_SECS: i64 = 10;
+/// Milliseconds between two checks for the launches in progress on shutdown.
+const LAUNCH_POLL_MILLIS: u64 = 100;
+/// Seconds the shutdown waits for the launches in progress, "docker run" might be
+/// pulling a large image or never return.
+const LAUNCH_TIMEOUT_SECS: u64 = 30;
+
+/// What happened to the containers when the server shut down.
+#[derive(Debug, Default, PartialEq)]
+pub(super) struct ShutdownSummary {
+    left: usize,
+    stopped: usize,
+    requeued: usize,
+    failed: usize,
+    queued: usize,
+    saved: bool,
+}
+
+/// Shows the summary as "Running containers: 1 left, 0 stopped, 0 requeued, 0 failed to
+/// stop. Queued containers: 3 saved."
+impl std::fmt::Display for ShutdownSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Running containers: {} left, {} stopped, {} requeued, {} failed to stop. \
+             Queued containers: {} {}.",
+            self.left,
+            self.stopped,
+            self.requeued,
+            self.failed,
+            self.queued,
+            if self.saved { "saved" } else { "not saved" }
+        )
+    }
+}
+
+/// Completes when the server receives SIGINT or SIGTERM.
+pub(super) async fn shutdown_signal() {
+    let interrupt = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to listen to SIGINT.");
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to listen to SIGTERM.")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = interrupt => info!("Received SIGINT, shutting down."),
+        _ = terminate => info!("Received SIGTERM, shutting down."),
+    }
+}
+
+impl State {
+    /// Waits for the launches in progress, applies `policy` to the running containers,
+    /// then saves the state with the queued containers.
+    #[tracing::instrument(name = "Shut down", skip(self))]
+    pub(super) async fn shut_down(&self, policy: ShutdownPolicy) -> ShutdownSummary {
+        self.wait_for_launches(Duration::from_secs(LAUNCH_TIMEOUT_SECS))
+            .await;
+        let running_containers = { self.running_containers.lock().unwrap().clone() };
+        let mut summary = ShutdownSummary::default();
+        if policy == ShutdownPolicy::Leave {
+            summary.left = running_containers.len();
+        } else {
+            let requeue = policy == ShutdownPolicy::Requeue;
+            // The last launched goes back to the queue first, so they keep their order
+            for running in running_containers.iter().rev() {
+                match stop_container(running.id()).await {
+                    Ok(()) => {
+                        self.release_stopped(running.id(), requeue);
+                        if requeue {
+                            summary.requeued += 1;
+                        } else {
+                            summary.stopped += 1;
+                        }
+                    }
+                    Err(error) => {
+                        error!("Failed to stop {}: {:?}", running.id().as_ref(), error);
+                        summary.failed += 1;
+                    }
+                }
+            }
+        }
+
+        summary.queued = self.queued_containers.lock().unwrap().len();
+        match self.flush_state() {
+            Ok(()) => summary.saved = self.state_file.is_some(),
+            Err(error) => error!("Failed to save the state: {:?}", error),
+        }
+        summary
+    }
+
+    /// Waits until no container is launching, a container started by "docker run" must
+    /// leave the queue before it is saved, or it would run again after a restart. The
+    /// containers still launching after `launch_timeout` are queued again, so they run
+    /// again after a restart, or are adopted if "docker run" started them meanwhile.
+    async fn wait_for_launches(&self, launch_timeout: Duration) {
+        let is_launching = || {
+            self.queued_containers
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|container| container.is_launching())
+        };
+        if !is_launching() {
+            return;
+        }
+        info!("Waiting for the launches in progress.");
+        let launches = async {
+            while is_launching() {
+                sleep(Duration::from_millis(LAUNCH_POLL_MILLIS)).await;
+            }
+        };
+        if timeout(launch_timeout, launches).await.is_ok() {
+            return;
+        }
+        warn!(
+            "Launches still in progress after {:?}, they are saved as queued.",
+            launch_timeout
+        );
+        self.queued_containers
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .filter(|container| container.is_launching())
+            .for_each(|container| container.queue());
+    }
+
+    /// Moves a container stopped on shutdown out of the running containers, to the
+    /// front of the queue if `requeue` or else to the finished containers.
+    fn release_stopped(&self, id: &RunningContainerId, requeue: bool) {
+        let launched = {
+            let mut running_containers = self.running_containers.lock().unwrap();
+            running_containers
+                .iter()
+                .position(|running| running.id() == id)
+                .map(|index| running_containers.remove(index))
+        };
+        let launched = match launched {
+            Some(launched) => launched,
+            None => return,
+        };
+        if requeue {
+            let mut container = launched.container().clone();
+            container.queue();
+            self.queued_containers.lock().unwrap().push_front(container);
+        } else {
+            let started_at = launched.started_at();
+            let outcome = FinishedOutcome::Cancelled("Stopped on shutdown.".to_string());
+            let finished =
+                FinishedContainer::new(launched.container().clone(), Some(id.clone()), outcome)
+                    .with_started_at(started_at);
+            self.finished_containers.lock().unwrap().push(finished);
+        }
+    }
+}
+
+#[tracing::instrument(name = "Stop container")]
+async fn stop_container(id: &RunningContainerId) -> Result<()> {
+    let docker = Docker::connect_with_local_defaults()?;
+    docker
+        .stop_container(
+            id.as_ref(),
+            Some(StopContainerOptions {
+                t: STOP_TIMEOUT_SECS,
+            }),
+        )
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{LaunchedContainer, QueuedContainer};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn shut_down_waits_for_the_launches_in_progress() {
+        let state = Arc::new(State::new());
+        let mut container = QueuedContainer::new("docker run -d some_image").unwrap();
+        container.launch(Vec::new());
+        state
+            .queued_containers
+            .lock()
+            .unwrap()
+            .push_back(container.clone());
+        tokio::spawn({
+            let state = Arc::clone(&state);
+            async move {
+                sleep(Duration::from_millis(2 * LAUNCH_POLL_MILLIS)).await;
+                state.queued_containers.lock().unwrap().clear();
+                state
+                    .running_containers
+                    .lock()
+                    .unwrap()
+                    .push(LaunchedContainer::new(
+                        RunningContainerId::new("123"),
+                        container,
+                    ));
+            }
+        });
+
+        let summary = state.shut_down(ShutdownPolicy::Leave).await;
+
+        assert_eq!(1, summary.left);
+        assert_eq!(0, summary.queued);
+    }
+
+    #[tokio::test]
+    async fn launches_past_the_timeout_are_queued_again() {
+        let state = State::new();
+        let mut container = QueuedContainer::new("docker run -d some_image").unwrap();
+        container.launch(vec![0]);
+        state.queued_containers.lock().unwrap().push_back(container);
+
+        state
+            .wait_for_launches(Duration::from_millis(2 * LAUNCH_POLL_MILLIS))
+            .await;
+
+        let queued_containers = state.queued_containers.lock().unwrap();
+        assert!(queued_containers[0].is_queued());
+        assert!(queued_containers[0].gpu_devices().is_empty());
+    }
+
+    #[test]
+    fn stopped_containers_go_back_to_the_front_of_the_queue() {
+        let state = State::new();
+        let running = QueuedContainer::new("docker run -d some_image").unwrap();
+        let queued = QueuedContainer::new("docker run -d other_image").unwrap();
+        state.queued_containers.lock().unwrap().push_back(queued);
+        state
+            .running_containers
+            .lock()
+            .unwrap()
+            .push(LaunchedContainer::new(
+                RunningContainerId::new("123"),
+                running.clone(),
+            ));
+
+        state.release_stopped(&RunningContainerId::new("123"), true);
+
+        assert!(state.running_containers.lock().unwrap().is_empty());
+        let queued_containers = state.queued_containers.lock().unwrap();
+        assert_eq!(running.id(), queued_containers[0].id());
+        assert!(queued_containers[0].is_queued());
+    }
+}
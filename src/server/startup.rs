@@ -1,11 +1,19 @@
 use super::State;
 use crate::{
     configuration::Settings,
-    server::{get_running_container, list_containers, queue_container, start_launcher_task},
+    domain::ShutdownPolicy,
+    server::{
+        add_cron_container, check_container, drain_server, edit_container, get_container_status,
+        get_job, get_queue_info, get_resources, get_running_container, get_server_status,
+        list_containers, list_cron_containers, list_sweeps, pause_containers, pause_sweep,
+        queue_container, queue_containers, remove_containers, remove_cron_container, remove_sweep,
+        resubmit_container, resume_containers, resume_server, resume_sweep, shutdown_signal,
+        start_cron_task, start_launcher_task, start_prefetch_task,
+    },
 };
 use anyhow::Result;
 use axum::{
-    routing::{get, post},
+    routing::{delete, get, post},
     AddExtensionLayer, Router,
 };
 use std::{net::TcpListener, sync::Arc};
@@ -23,7 +31,11 @@ pub struct Server {
     listener: TcpListener,
     port: u16,
     app: Router,
+    state: Arc<State>,
+    shutdown_policy: ShutdownPolicy,
     launcher_task: JoinHandle<()>,
+    cron_task: JoinHandle<()>,
+    prefetch_task: JoinHandle<()>,
 }
 
 pub async fn health_check() {}
@@ -33,20 +45,65 @@ impl Server {
         tracing::info!("Configuration: {:?}", configuration);
         let listener = TcpListener::bind(format!("127.0.0.1:{}", configuration.port))?;
         let port = listener.local_addr()?.port();
-        let shared_state = Arc::new(State::new());
+        let instance = configuration
+            .instance
+            .unwrap_or_else(|| format!("127.0.0.1:{}", port));
+        let shared_state = Arc::new(
+            State::new()
+                .with_instance(instance)
+                .with_prefetch(
+                    configuration.prefetch_depth,
+                    configuration.prefetch_concurrency,
+                )
+                .with_capacity(configuration.capacity)
+                .with_scheduler(configuration.scheduling.scheduler())
+                .with_lanes(configuration.lanes)
+                .with_state_file(configuration.state_file),
+        );
+        shared_state.load_state()?;
         let (tx, rx) = mpsc::channel(8);
         let launcher_task = tokio::spawn({
             let shared_state = Arc::clone(&shared_state);
             let tx = tx.clone();
             async move { start_launcher_task(shared_state, tx, rx).await }
         });
+        let cron_task = tokio::spawn({
+            let shared_state = Arc::clone(&shared_state);
+            let tx = tx.clone();
+            async move { start_cron_task(shared_state, tx).await }
+        });
+        let prefetch_task = tokio::spawn({
+            let shared_state = Arc::clone(&shared_state);
+            async move { start_prefetch_task(shared_state).await }
+        });
 
         let app = Router::new()
             .route("/health_check", get(health_check))
             .route("/list_containers", get(list_containers))
             .route("/queue_container", post(queue_container))
+            .route("/queue_containers", post(queue_containers))
+            .route("/check_container", post(check_container))
             .route("/get_running_container", get(get_running_container))
-            .layer(AddExtensionLayer::new(shared_state))
+            .route("/get_container_status/:id", get(get_container_status))
+            .route("/get_queue_info/:id", get(get_queue_info))
+            .route("/jobs/:id", get(get_job))
+            .route("/resources", get(get_resources))
+            .route("/status", get(get_server_status))
+            .route("/drain", post(drain_server))
+            .route("/resume", post(resume_server))
+            .route("/edit_container/:id", post(edit_container))
+            .route("/resubmit_container/:id", post(resubmit_container))
+            .route("/add_cron_container", post(add_cron_container))
+            .route("/list_cron_containers", get(list_cron_containers))
+            .route("/remove_cron_container/:id", delete(remove_cron_container))
+            .route("/pause_containers", post(pause_containers))
+            .route("/resume_containers", post(resume_containers))
+            .route("/remove_containers", post(remove_containers))
+            .route("/list_sweeps", get(list_sweeps))
+            .route("/pause_sweep/:id", post(pause_sweep))
+            .route("/resume_sweep/:id", post(resume_sweep))
+            .route("/remove_sweep/:id", delete(remove_sweep))
+            .layer(AddExtensionLayer::new(Arc::clone(&shared_state)))
             .layer(AddExtensionLayer::new(tx))
             .layer(
                 // More on TraceLayer: https://docs.rs/tower-http/0.1.1/tower_http/trace/index.html
@@ -63,7 +120,11 @@ impl Server {
             listener,
             port,
             app,
+            state: shared_state,
+            shutdown_policy: configuration.shutdown_policy,
             launcher_task,
+            cron_task,
+            prefetch_task,
         })
     }
 
@@ -71,23 +132,49 @@ impl Server {
         self.port
     }
 
+    /// Serves until SIGINT or SIGTERM, then the server stops accepting requests, applies
+    /// the shutdown policy to the running containers and saves its state.
     pub async fn start(self) -> Result<()> {
         tracing::info!("Serving at: http://127.0.0.1:{}", self.port);
-        let server_task =
-            axum::Server::from_tcp(self.listener)?.serve(self.app.into_make_service());
+        let server_task = axum::Server::from_tcp(self.listener)?
+            .serve(self.app.into_make_service())
+            .with_graceful_shutdown(shutdown_signal());
+        let (mut launcher_task, mut cron_task, mut prefetch_task) =
+            (self.launcher_task, self.cron_task, self.prefetch_task);
 
         tokio::select! {
-            _ = server_task => {
+            res = server_task => {
+                if let Err(error) = res {
+                    error!("{:?}", error);
+                }
                 info!("Server task terminated.");
             }
-            res = self.launcher_task => {
+            res = &mut launcher_task => {
                 if let Err(error) = res {
                     error!("{:?}", error);
                 }
                 info!("Launcher task terminated.");
             }
+            res = &mut cron_task => {
+                if let Err(error) = res {
+                    error!("{:?}", error);
+                }
+                info!("Cron task terminated.");
+            }
+            res = &mut prefetch_task => {
+                if let Err(error) = res {
+                    error!("{:?}", error);
+                }
+                info!("Prefetch task terminated.");
+            }
         }
 
+        // No container starts or gets queued while the state is saved
+        launcher_task.abort();
+        cron_task.abort();
+        prefetch_task.abort();
+        let summary = self.state.shut_down(self.shutdown_policy).await;
+        info!("Shutdown complete. {}", summary);
         Ok(())
     }
 }
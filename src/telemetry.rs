@@ -5,6 +5,7 @@ use tracing_log::LogTracer;
 use tracing_subscriber::{prelude::__tracing_subscriber_SubscriberExt, EnvFilter, Registry};
 
 /// Compose multiple layers into a tracing subscriber
+#[allow(clippy::extra_unused_lifetimes)]
 pub fn get_subscriber<'a, F, W>(
     name: String,
     env_filter: String,
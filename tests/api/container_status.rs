@@ -0,0 +1,57 @@
+use crate::helpers::spawn_app;
+use chrono::Utc;
+use docker_queue::client::{OutputFormat, QueueOptions};
+use std::time::Duration;
+
+#[tokio::test]
+async fn container_status_shows_position_and_estimated_start() {
+    // Arrange
+    let mut app = spawn_app().await;
+    // Scheduled later, so they keep their position without running
+    let not_before = Some(Utc::now() + chrono::Duration::hours(1));
+    let options = QueueOptions {
+        not_before,
+        estimate: Some(Duration::from_secs(3600)),
+        ..Default::default()
+    };
+    app.client
+        .queue_container_with_options("docker run -d some_image".into(), options)
+        .await
+        .unwrap();
+    let options = QueueOptions {
+        not_before,
+        ..Default::default()
+    };
+    let id = app
+        .client
+        .queue_container_with_options("docker run -d some_image".into(), options)
+        .await
+        .unwrap();
+    println!("{}", app.get_client_output());
+
+    // Act
+    app.client.format = OutputFormat::Json;
+    app.client.container_status(&id).await.unwrap();
+    let output = app.get_client_output();
+    println!("{}", output);
+
+    // Assert
+    let output: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(output["status"], "Queued");
+    assert_eq!(output["position"], 2);
+    assert!(output["estimated_start"].is_string());
+}
+
+#[tokio::test]
+async fn container_status_shows_not_found() {
+    // Arrange
+    let mut app = spawn_app().await;
+
+    // Act
+    app.client.container_status("unknown").await.unwrap();
+    let output = app.get_client_output();
+    println!("{}", output);
+
+    // Assert
+    assert!(output.contains("Not found"));
+}
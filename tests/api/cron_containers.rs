@@ -0,0 +1,42 @@
+use crate::helpers::spawn_app;
+use claim::assert_err;
+
+#[tokio::test]
+async fn cron_containers_can_be_added_listed_and_removed() {
+    // Arrange
+    let mut app = spawn_app().await;
+    let command = "docker run -d cron_image".to_string();
+
+    // Act
+    let id = app
+        .client
+        .add_cron_container("0 2 * * *".into(), command.clone(), false)
+        .await
+        .unwrap();
+    println!("{}", app.get_client_output());
+    app.client.list_cron_containers().await.unwrap();
+    let listed = app.get_client_output();
+    println!("{}", listed);
+    app.client.remove_cron_container(&id).await.unwrap();
+    println!("{}", app.get_client_output());
+    app.client.list_cron_containers().await.unwrap();
+    let removed = app.get_client_output();
+    println!("{}", removed);
+
+    // Assert
+    assert!(listed.contains(&id));
+    assert!(listed.contains(&command));
+    assert!(!removed.contains(&id));
+}
+
+#[tokio::test]
+async fn remove_cron_container_fails_for_unknown_id() {
+    // Arrange
+    let mut app = spawn_app().await;
+
+    // Act
+    let result = app.client.remove_cron_container("unknown").await;
+
+    // Assert
+    assert_err!(result);
+}
@@ -0,0 +1,41 @@
+use crate::helpers::spawn_app_with;
+use docker_queue::{configuration::Settings, domain::ServerMode};
+use std::time::Duration;
+use tokio::time::sleep;
+
+#[tokio::test]
+async fn drained_server_keeps_its_containers_queued_across_restarts() {
+    // Arrange
+    let state_file =
+        std::env::temp_dir().join(format!("docker_queue_{}.json", uuid::Uuid::new_v4()));
+    let settings = || Settings {
+        port: 0,
+        state_file: Some(state_file.clone()),
+        ..Default::default()
+    };
+    let mut app = spawn_app_with(settings()).await;
+
+    // Act
+    app.client.drain_server().await.unwrap();
+    let id = app
+        .client
+        .queue_container("docker run -d some_image".into(), false, false)
+        .await
+        .unwrap();
+    sleep(Duration::from_millis(200)).await;
+    let restarted = spawn_app_with(settings()).await;
+
+    // Assert
+    let info = app.client.get_queue_info(&id).await.unwrap().unwrap();
+    assert_eq!(None, info.estimated_start());
+    let status = app.client.get_server_status().await.unwrap();
+    assert!(status.is_drained());
+    assert_eq!(1, status.queued());
+    let status = restarted.client.get_server_status().await.unwrap();
+    assert_eq!(ServerMode::Draining, status.mode());
+
+    app.client.resume_server().await.unwrap();
+    let status = app.client.get_server_status().await.unwrap();
+    assert_eq!(ServerMode::Active, status.mode());
+    std::fs::remove_file(state_file).unwrap();
+}
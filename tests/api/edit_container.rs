@@ -0,0 +1,86 @@
+use crate::helpers::spawn_app;
+use chrono::Utc;
+use docker_queue::{
+    client::{OutputFormat, QueueOptions},
+    domain::EnvExpansion,
+};
+
+#[tokio::test]
+async fn edit_container_replaces_command_and_keeps_position() {
+    // Arrange
+    let mut app = spawn_app().await;
+    // Scheduled later, so they keep their position without running
+    let options = || QueueOptions {
+        not_before: Some(Utc::now() + chrono::Duration::hours(1)),
+        ..Default::default()
+    };
+    let id = app
+        .client
+        .queue_container_with_options("docker run -d some_image".into(), options())
+        .await
+        .unwrap();
+    app.client
+        .queue_container_with_options("docker run -d last_image".into(), options())
+        .await
+        .unwrap();
+    println!("{}", app.get_client_output());
+    std::env::set_var("VISUAL", "sed -i s/some_image/other_image/");
+
+    // Act
+    app.client.edit_container(&id).await.unwrap();
+    println!("{}", app.get_client_output());
+
+    // Assert
+    app.client.format = OutputFormat::Json;
+    app.client.container_status(&id).await.unwrap();
+    let output: serde_json::Value = serde_json::from_str(&app.get_client_output()).unwrap();
+    assert_eq!(output["position"], 1);
+    app.client.inspect_container(&id).await.unwrap();
+    let output: serde_json::Value = serde_json::from_str(&app.get_client_output()).unwrap();
+    assert_eq!(output["command"], "docker run -d other_image");
+}
+
+#[tokio::test]
+async fn edit_unknown_container_fails() {
+    // Arrange
+    let mut app = spawn_app().await;
+
+    // Act
+    let result = app.client.edit_container("unknown").await;
+
+    // Assert
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn edit_server_env_container_keeps_the_env_vars() {
+    // Arrange
+    let mut app = spawn_app().await;
+    let options = QueueOptions {
+        paused: true,
+        server_env: true,
+        ..Default::default()
+    };
+    let id = app
+        .client
+        .queue_container_with_options(
+            "docker run -d -e TOKEN=$DOCKER_QUEUE_EDIT_TOKEN some_image".into(),
+            options,
+        )
+        .await
+        .unwrap();
+    println!("{}", app.get_client_output());
+    std::env::set_var("VISUAL", "sed -i s/some_image/other_image/");
+
+    // Act
+    app.client.edit_container(&id).await.unwrap();
+    println!("{}", app.get_client_output());
+
+    // Assert
+    let details = app.client.get_job(&id).await.unwrap();
+    assert_eq!(EnvExpansion::Server, details.env_expansion());
+    assert_eq!(
+        "docker run -d -e TOKEN=$DOCKER_QUEUE_EDIT_TOKEN other_image",
+        details.command()
+    );
+}
@@ -58,7 +58,7 @@ impl TestApp {
                     .filter(|line| line.contains(check) && line.contains("Running"))
                     .map(String::from)
                     .collect::<Vec<_>>();
-                if lines.len() > 0 {
+                if !lines.is_empty() {
                     break Ok::<_, anyhow::Error>(lines);
                 }
             }
@@ -73,12 +73,20 @@ impl TestApp {
 }
 
 pub async fn spawn_app() -> TestApp {
+    spawn_app_with(Settings {
+        port: 0,
+        ..Default::default()
+    })
+    .await
+}
+
+pub async fn spawn_app_with(settings: Settings) -> TestApp {
     // Set up tracing
     Lazy::force(&TRACING);
 
-    let app = Server::build(Settings { port: 0 }).expect("Failed to build application.");
+    let app = Server::build(settings).expect("Failed to build application.");
     let port = app.port();
-    let _ = tokio::spawn(async move { app.start().await });
+    tokio::spawn(async move { app.start().await });
     let client = ClientApp::new(port, Vec::new());
 
     TestApp { port, client }
@@ -0,0 +1,74 @@
+use crate::helpers::spawn_app;
+use docker_queue::client::OutputFormat;
+
+#[tokio::test]
+async fn inspect_container_shows_job_details() {
+    // Arrange
+    let mut app = spawn_app().await;
+    std::env::set_var("INSPECT_IMAGE", "some_image");
+    let id = app
+        .client
+        .queue_container("docker run -d $INSPECT_IMAGE".into(), false, true)
+        .await
+        .unwrap();
+    println!("{}", app.get_client_output());
+
+    // Act
+    app.client.format = OutputFormat::Json;
+    app.client.inspect_container(&id).await.unwrap();
+    let output = app.get_client_output();
+    println!("{}", output);
+
+    // Assert
+    let output: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(output["id"], id);
+    assert_eq!(output["status"], "Paused");
+    assert_eq!(output["original_command"], "docker run -d $INSPECT_IMAGE");
+    assert_eq!(output["command"], "docker run -d some_image");
+    assert_eq!(
+        output["args"],
+        serde_json::json!(["run", "-d", "some_image"])
+    );
+    assert_eq!(output["attempts"], 0);
+}
+
+#[tokio::test]
+async fn inspect_unknown_container_fails() {
+    // Arrange
+    let mut app = spawn_app().await;
+
+    // Act
+    let result = app.client.inspect_container("unknown").await;
+
+    // Assert
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn inspect_container_shows_the_added_detach_flag() {
+    // Arrange
+    let mut app = spawn_app().await;
+    let id = app
+        .client
+        .queue_container("docker run --rm some_image".into(), false, true)
+        .await
+        .unwrap();
+    println!("{}", app.get_client_output());
+
+    // Act
+    app.client.inspect_container(&id).await.unwrap();
+    let output = app.get_client_output();
+
+    // Assert
+    assert!(
+        output.contains("docker run --rm some_image (--detach added)"),
+        "{}",
+        output
+    );
+    let details = app.client.get_job(&id).await.unwrap();
+    assert!(details.detach_added());
+    assert_eq!(
+        details.args().unwrap(),
+        ["run", "--detach", "--rm", "some_image"]
+    );
+}
@@ -1,4 +1,5 @@
 use crate::helpers::{rm_sleeping_container, run_sleeping_container, spawn_app};
+use docker_queue::client::{ListOptions, StatusFilter};
 
 #[tokio::test]
 async fn list_containers_contains_running_containers() {
@@ -37,3 +38,45 @@ async fn list_containers_contains_queued_containers() {
     // Assert
     assert!(output.contains(&name));
 }
+
+#[tokio::test]
+async fn list_containers_filters_by_status() {
+    // Arrange
+    let mut app = spawn_app().await;
+    let name = "docker run -d list_containers_filters_by_status".to_string();
+    app.client
+        .queue_container(name.clone(), false, true)
+        .await
+        .unwrap();
+    println!("{}", app.get_client_output());
+
+    // Act
+    let options = ListOptions {
+        show_all: true,
+        status: Some(StatusFilter::Running),
+        no_color: true,
+        ..Default::default()
+    };
+    app.client
+        .list_containers_with_options(options)
+        .await
+        .unwrap();
+    let running = app.get_client_output();
+    println!("{}", running);
+    let options = ListOptions {
+        show_all: true,
+        status: Some(StatusFilter::Paused),
+        no_color: true,
+        ..Default::default()
+    };
+    app.client
+        .list_containers_with_options(options)
+        .await
+        .unwrap();
+    let paused = app.get_client_output();
+    println!("{}", paused);
+
+    // Assert
+    assert!(!running.contains(&name));
+    assert!(paused.contains(&name));
+}
@@ -1,4 +1,13 @@
+mod container_status;
+mod cron_containers;
+mod drain_server;
+mod edit_container;
 mod health_check;
 mod helpers;
+mod inspect_container;
 mod list_containers;
+mod names_and_labels;
 mod queue_container;
+mod resubmit_container;
+mod sweeps;
+mod wait_container;
@@ -0,0 +1,101 @@
+use crate::helpers::spawn_app;
+use chrono::Utc;
+use docker_queue::{
+    client::{OutputFormat, QueueOptions},
+    domain::Selector,
+};
+
+#[tokio::test]
+async fn named_container_can_be_used_instead_of_the_id() {
+    // Arrange
+    let mut app = spawn_app().await;
+    let options = QueueOptions {
+        paused: true,
+        name: Some("train".into()),
+        labels: vec![("team".into(), "vision".into())],
+        ..Default::default()
+    };
+    let id = app
+        .client
+        .queue_container_with_options("docker run -d some_image".into(), options)
+        .await
+        .unwrap();
+    println!("{}", app.get_client_output());
+
+    // Act
+    app.client.format = OutputFormat::Json;
+    app.client.inspect_container("train").await.unwrap();
+    let output: serde_json::Value = serde_json::from_str(&app.get_client_output()).unwrap();
+
+    // Assert
+    assert_eq!(output["id"], id);
+    assert_eq!(output["labels"]["team"], "vision");
+    assert_eq!(
+        output["args"],
+        serde_json::json!(["run", "--label", "team=vision", "-d", "some_image"])
+    );
+    assert_eq!(
+        app.client.resolve_id("train").await.unwrap().to_string(),
+        id
+    );
+}
+
+#[tokio::test]
+async fn queue_container_rejects_names_in_use() {
+    // Arrange
+    let mut app = spawn_app().await;
+    let options = || QueueOptions {
+        paused: true,
+        name: Some("train".into()),
+        ..Default::default()
+    };
+    app.client
+        .queue_container_with_options("docker run -d some_image".into(), options())
+        .await
+        .unwrap();
+
+    // Act
+    let result = app
+        .client
+        .queue_container_with_options("docker run -d some_image".into(), options())
+        .await;
+
+    // Assert
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn containers_can_be_removed_by_label() {
+    // Arrange
+    let mut app = spawn_app().await;
+    let options = QueueOptions {
+        paused: true,
+        labels: vec![("team".into(), "vision".into())],
+        ..Default::default()
+    };
+    let labeled = app
+        .client
+        .queue_container_with_options("docker run -d some_image".into(), options)
+        .await
+        .unwrap();
+    let options = QueueOptions {
+        not_before: Some(Utc::now() + chrono::Duration::hours(1)),
+        ..Default::default()
+    };
+    let other = app
+        .client
+        .queue_container_with_options("docker run -d some_image".into(), options)
+        .await
+        .unwrap();
+    println!("{}", app.get_client_output());
+
+    // Act
+    let selector = Selector::new(vec![], [("team".into(), "vision".into())]);
+    let removed = app.client.remove_containers(&selector).await.unwrap();
+    println!("{}", app.get_client_output());
+
+    // Assert
+    assert_eq!(removed, vec![labeled]);
+    let info = app.client.get_queue_info(&other).await.unwrap().unwrap();
+    assert_eq!(info.position(), Some(1));
+}
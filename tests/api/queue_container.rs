@@ -1,4 +1,10 @@
 use crate::helpers::spawn_app;
+use chrono::Utc;
+use claim::assert_err;
+use docker_queue::{
+    client::{OutputFormat, QueueOptions, CANCELLED_EXIT_CODE},
+    domain::QueuedContainer,
+};
 use std::time::Duration;
 use tokio::time::{sleep, timeout};
 
@@ -20,6 +26,37 @@ async fn queue_container_adds_to_queue() {
     assert!(output.contains("added to queue"));
 }
 
+#[tokio::test]
+async fn queue_container_writes_machine_readable_output() {
+    // Arrange
+    let mut app = spawn_app().await;
+    let command = "docker run -d some_image".to_string();
+
+    // Act
+    app.client.format = OutputFormat::Json;
+    let id = app
+        .client
+        .queue_container(command.clone(), false, true)
+        .await
+        .unwrap();
+    let json = app.get_client_output();
+    println!("{}", json);
+    app.client.format = OutputFormat::Template("{{.status}} {{.id}}".into());
+    let id2 = app
+        .client
+        .queue_container(command, false, true)
+        .await
+        .unwrap();
+    let templated = app.get_client_output();
+    println!("{}", templated);
+
+    // Assert
+    let output: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(output["id"], id.as_str());
+    assert_eq!(output["status"], "Paused");
+    assert_eq!(templated, format!("Paused {}\n", id2));
+}
+
 #[tokio::test]
 async fn queue_container_runs_if_no_running_containers() {
     // Arrange
@@ -148,3 +185,251 @@ async fn queue_container_runs_after_running_container_finish_execution() {
     .await
     .unwrap();
 }
+
+#[tokio::test]
+async fn queue_container_rejects_unknown_dependencies() {
+    // Arrange
+    let mut app = spawn_app().await;
+    let command = "docker run -d some_image".into();
+    let options = QueueOptions {
+        paused: true,
+        after: vec![uuid::Uuid::new_v4()],
+        ..Default::default()
+    };
+
+    // Act
+    let result = app
+        .client
+        .queue_container_with_options(command, options)
+        .await;
+
+    // Assert
+    assert_err!(result);
+}
+
+#[tokio::test]
+async fn queue_container_cancels_dependents_of_failed_containers() {
+    // Arrange
+    let mut app = spawn_app().await;
+    let command1 = "docker run -d --rm alpine sh -c \"sleep 2 && exit 1\"".into();
+    let command2 = "docker run -d --rm alpine sh -c \"sleep 2\"".into();
+
+    // Act
+    let id1 = app
+        .client
+        .queue_container(command1, false, false)
+        .await
+        .unwrap();
+    let options = QueueOptions {
+        after_ok: vec![id1.parse().unwrap()],
+        ..Default::default()
+    };
+    let id2 = app
+        .client
+        .queue_container_with_options(command2, options)
+        .await
+        .unwrap();
+    println!("{}", app.get_client_output());
+    let exit_code = timeout(Duration::from_secs(15), app.client.wait_container(&id2))
+        .await
+        .unwrap()
+        .unwrap();
+    println!("{}", app.get_client_output());
+
+    // Assert
+    assert_eq!(exit_code, CANCELLED_EXIT_CODE);
+}
+
+#[tokio::test]
+async fn queue_containers_queues_every_file_of_a_directory() {
+    // Arrange
+    let mut app = spawn_app().await;
+    // Scheduled later, so they keep their position without running
+    let options = QueueOptions {
+        not_before: Some(Utc::now() + chrono::Duration::hours(1)),
+        ..Default::default()
+    };
+
+    // Act
+    let ids = app
+        .client
+        .queue_containers_with_options("tests/examples".into(), options)
+        .await
+        .unwrap();
+    println!("{}", app.get_client_output());
+
+    // Assert
+    assert_eq!(ids.len(), 5);
+    let info = app.client.get_queue_info(&ids[4]).await.unwrap().unwrap();
+    assert_eq!(info.position(), Some(5));
+}
+
+#[tokio::test]
+async fn queue_containers_rejects_a_name_for_a_directory() {
+    // Arrange
+    let mut app = spawn_app().await;
+    let options = QueueOptions {
+        name: Some("train".into()),
+        ..Default::default()
+    };
+
+    // Act
+    let result = app
+        .client
+        .queue_containers_with_options("tests/examples".into(), options)
+        .await;
+
+    // Assert
+    let error = result.unwrap_err().to_string();
+    assert!(
+        error.contains("can't be given to every container"),
+        "{}",
+        error
+    );
+}
+
+#[tokio::test]
+async fn queue_containers_rejects_the_whole_batch_if_a_command_is_invalid() {
+    // Arrange
+    let mut app = spawn_app().await;
+    let path = std::env::temp_dir().join(format!("docker_queue_{}.txt", uuid::Uuid::new_v4()));
+    std::fs::write(
+        &path,
+        "docker run -d --rm alpine sleep 3\n\ndocker run -it --rm alpine sleep 3\n",
+    )
+    .unwrap();
+    let options = QueueOptions {
+        paused: true,
+        ..Default::default()
+    };
+
+    // Act
+    let result = app
+        .client
+        .queue_containers_with_options(path.to_string_lossy().into(), options)
+        .await;
+    std::fs::remove_file(&path).unwrap();
+
+    // Assert
+    let error = result.unwrap_err().to_string();
+    assert!(error.contains("block 2"), "{}", error);
+    let options = QueueOptions {
+        not_before: Some(Utc::now() + chrono::Duration::hours(1)),
+        ..Default::default()
+    };
+    let id = app
+        .client
+        .queue_container_with_options("docker run -d some_image".into(), options)
+        .await
+        .unwrap();
+    let info = app.client.get_queue_info(&id).await.unwrap().unwrap();
+    assert_eq!(info.position(), Some(1));
+}
+
+#[tokio::test]
+async fn queue_container_queues_a_compose_service() {
+    // Arrange
+    let mut app = spawn_app().await;
+    let path = std::env::temp_dir().join(format!("docker_queue_{}.yml", uuid::Uuid::new_v4()));
+    std::fs::write(
+        &path,
+        "services:\n  train:\n    image: trainer\n  eval:\n    image: alpine\n    command: [\"echo\", \"a b\"]\n    depends_on: [train]\n    restart: always\n",
+    )
+    .unwrap();
+    let options = QueueOptions {
+        is_spec: true,
+        service: Some("eval".into()),
+        paused: true,
+        ..Default::default()
+    };
+
+    // Act
+    let id = app
+        .client
+        .queue_container_with_options(path.to_string_lossy().into(), options)
+        .await;
+    std::fs::remove_file(&path).unwrap();
+
+    // Assert
+    let id = id.unwrap();
+    let output = app.get_client_output();
+    assert!(
+        output.contains("Warning: Ignored keys of the job spec: depends_on, restart"),
+        "{}",
+        output
+    );
+    let details = app.client.get_job(&id).await.unwrap();
+    assert_eq!(details.command(), "docker run --detach alpine echo \"a b\"");
+    assert_eq!(
+        details.args().unwrap(),
+        ["run", "--detach", "alpine", "echo", "a b"]
+    );
+}
+
+#[tokio::test]
+async fn queue_container_with_check_rejects_containers_that_would_fail() {
+    // Arrange
+    let mut app = spawn_app().await;
+    let options = || QueueOptions {
+        paused: true,
+        check: true,
+        ..Default::default()
+    };
+
+    // Act
+    let failed = app
+        .client
+        .queue_container_with_options(
+            "docker run --mount type=bind,src=/docker_queue_missing,dst=/a img".into(),
+            options(),
+        )
+        .await;
+    let warned = app
+        .client
+        .queue_container_with_options(
+            "docker run -v /docker_queue_missing:/a img".into(),
+            options(),
+        )
+        .await;
+
+    // Assert
+    let error = failed.unwrap_err().to_string();
+    assert!(error.contains("/docker_queue_missing"), "{}", error);
+    assert!(warned.is_ok());
+    let output = app.get_client_output();
+    assert!(
+        output.contains("Warning: Bind mount path \"/docker_queue_missing\""),
+        "{}",
+        output
+    );
+}
+
+#[tokio::test]
+async fn queue_container_rejects_launching_containers() {
+    // Arrange
+    let mut app = spawn_app().await;
+    let mut container = QueuedContainer::new("docker run -d some_image").unwrap();
+    container.launch(vec![0, 1]);
+    let client = reqwest::Client::new();
+
+    // Act
+    let single = client
+        .post(format!("http://127.0.0.1:{}/queue_container", app.port))
+        .json(&container)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let batch = client
+        .post(format!("http://127.0.0.1:{}/queue_containers", app.port))
+        .json(&vec![container.clone()])
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    app.client.container_status(&container.id()).await.unwrap();
+
+    // Assert
+    assert_eq!(reqwest::StatusCode::BAD_REQUEST, single.status());
+    assert_eq!(reqwest::StatusCode::BAD_REQUEST, batch.status());
+    let output = app.get_client_output();
+    assert!(output.contains("Not found"), "{}", output);
+}
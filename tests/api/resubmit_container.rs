@@ -0,0 +1,55 @@
+use crate::helpers::spawn_app;
+use docker_queue::{client::OutputFormat, domain::ResubmitOptions};
+use std::time::Duration;
+use tokio::time::timeout;
+
+#[tokio::test]
+async fn resubmit_container_queues_finished_container_again() {
+    // Arrange
+    let mut app = spawn_app().await;
+    let command = "docker run -d --rm alpine sh -c \"exit 3\"".into();
+    let id = app
+        .client
+        .queue_container(command, false, false)
+        .await
+        .unwrap();
+    timeout(Duration::from_secs(15), app.client.wait_container(&id))
+        .await
+        .unwrap()
+        .unwrap();
+    println!("{}", app.get_client_output());
+
+    // Act
+    let options = ResubmitOptions {
+        front: true,
+        paused: true,
+    };
+    let new_id = app.client.resubmit_container(&id, options).await.unwrap();
+    println!("{}", app.get_client_output());
+
+    // Assert
+    assert_ne!(id, new_id);
+    app.client.format = OutputFormat::Json;
+    app.client.inspect_container(&new_id).await.unwrap();
+    let output: serde_json::Value = serde_json::from_str(&app.get_client_output()).unwrap();
+    assert_eq!(output["status"], "Paused");
+    assert_eq!(
+        output["command"],
+        "docker run -d --rm alpine sh -c \"exit 3\""
+    );
+}
+
+#[tokio::test]
+async fn resubmit_unknown_container_fails() {
+    // Arrange
+    let mut app = spawn_app().await;
+
+    // Act
+    let result = app
+        .client
+        .resubmit_container("unknown", ResubmitOptions::default())
+        .await;
+
+    // Assert
+    assert!(result.is_err());
+}
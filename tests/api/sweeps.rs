@@ -0,0 +1,59 @@
+use crate::helpers::spawn_app;
+use docker_queue::client::{OutputFormat, QueueOptions};
+
+#[tokio::test]
+async fn sweep_queues_a_container_per_combination() {
+    // Arrange
+    let mut app = spawn_app().await;
+    let template = "docker run -d img train.py --lr {0.1,0.01} --bs {32,64}".into();
+    let options = QueueOptions {
+        paused: true,
+        ..Default::default()
+    };
+
+    // Act
+    let sweep_id = app
+        .client
+        .sweep_containers(template, None, options)
+        .await
+        .unwrap();
+    println!("{}", app.get_client_output());
+
+    // Assert
+    let sweeps = app.client.get_sweeps().await.unwrap();
+    assert_eq!(sweeps.len(), 1);
+    assert_eq!(sweeps[0].id(), sweep_id);
+    assert_eq!(sweeps[0].paused(), 4);
+}
+
+#[tokio::test]
+async fn sweep_can_be_resumed_and_removed_as_a_group() {
+    // Arrange
+    let mut app = spawn_app().await;
+    let template = "docker run -d img train.py --lr {0.1,0.01}".into();
+    let options = QueueOptions {
+        paused: true,
+        ..Default::default()
+    };
+    let sweep_id = app
+        .client
+        .sweep_containers(template, None, options)
+        .await
+        .unwrap();
+    app.client
+        .queue_container("docker run -d some_image".into(), false, true)
+        .await
+        .unwrap();
+    println!("{}", app.get_client_output());
+
+    // Act
+    app.client.format = OutputFormat::Json;
+    app.client.remove_sweep(&sweep_id).await.unwrap();
+    let output: serde_json::Value = serde_json::from_str(&app.get_client_output()).unwrap();
+
+    // Assert
+    assert_eq!(output["removed"], 2);
+    let sweeps = app.client.get_sweeps().await.unwrap();
+    assert_eq!(sweeps[0].finished(), 2);
+    assert!(app.client.pause_sweep("unknown").await.is_err());
+}
@@ -0,0 +1,84 @@
+use crate::helpers::spawn_app;
+use docker_queue::{
+    client::{OutputFormat, QueueOptions, NOT_FOUND_EXIT_CODE},
+    domain::Selector,
+};
+
+#[tokio::test]
+async fn wait_container_returns_container_exit_code() {
+    // Arrange
+    let mut app = spawn_app().await;
+    let command = "docker run -d --rm alpine sh -c \"sleep 2 && exit 3\"".into();
+
+    // Act
+    let id = app
+        .client
+        .queue_container(command, false, false)
+        .await
+        .unwrap();
+    println!("{}", app.get_client_output());
+    let exit_code = app.client.wait_container(&id).await.unwrap();
+    println!("{}", app.get_client_output());
+
+    // Assert
+    assert_eq!(exit_code, 3);
+}
+
+#[tokio::test]
+async fn wait_container_returns_not_found_for_unknown_container() {
+    // Arrange
+    let mut app = spawn_app().await;
+
+    // Act
+    let exit_code = app
+        .client
+        .wait_container("00000000-0000-0000-0000-000000000000")
+        .await
+        .unwrap();
+    let output = app.get_client_output();
+    println!("{}", output);
+
+    // Assert
+    assert_eq!(exit_code, NOT_FOUND_EXIT_CODE);
+    assert!(output.contains("not found"));
+}
+
+#[tokio::test]
+async fn queue_with_wait_writes_a_single_json_document() {
+    // Arrange
+    let mut app = spawn_app().await;
+    app.client.format = OutputFormat::Json;
+    let options = QueueOptions {
+        paused: true,
+        wait: true,
+        ..Default::default()
+    };
+    let id = app
+        .client
+        .queue_container_with_options("docker run -d some_image".into(), options)
+        .await
+        .unwrap();
+    let queued = app.get_client_output();
+    app.client
+        .remove_containers(&Selector::new(vec![id.clone()], vec![]))
+        .await
+        .unwrap();
+    app.get_client_output();
+
+    // Act
+    let unknown = "00000000-0000-0000-0000-000000000000".to_string();
+    let exit_code = app
+        .client
+        .wait_containers(&[id.clone(), unknown])
+        .await
+        .unwrap();
+    let output = app.get_client_output();
+    println!("{}", output);
+
+    // Assert
+    assert!(queued.is_empty(), "{}", queued);
+    let output: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(output[0]["id"], id.as_str());
+    assert_eq!(output[1]["exit_code"], NOT_FOUND_EXIT_CODE);
+    assert_ne!(exit_code, 0);
+}